@@ -59,4 +59,78 @@ export interface CallbackReturns {
         chat_entry_type: number,
         chat_id: number
     }
+    [client.callback.SteamCallback.UserStatsReceived]: {
+        steam_id: bigint
+        game_id: bigint
+        success: boolean
+        error?: string
+    }
+    [client.callback.SteamCallback.UserStatsStored]: {
+        game_id: bigint
+        success: boolean
+        error?: string
+    }
+    [client.callback.SteamCallback.UserAchievementStored]: {
+        game_id: bigint
+        achievement_name: string
+        /** Both progress values are 0 when the achievement was unlocked */
+        current_progress: number
+        max_progress: number
+    }
+    [client.callback.SteamCallback.GameOverlayActivated]: {
+        active: boolean
+    }
+    [client.callback.SteamCallback.DlcInstalled]: {
+        app_id: number
+    }
+    [client.callback.SteamCallback.NewUrlLaunchParameters]: {
+        /** Same as `apps.getLaunchCommandLine()` */
+        command_line: string
+    }
+    [client.callback.SteamCallback.GameRichPresenceJoinRequested]: {
+        friend_steam_id: bigint
+        /** The friend's `connect` rich presence value */
+        connect: string
+    }
+    [client.callback.SteamCallback.ScreenshotReady]: {
+        local_handle: number
+        success: boolean
+        error?: string
+    }
+    [client.callback.SteamCallback.ValidateAuthTicketResponse]: {
+        steam_id: bigint
+        /** Differs from `steam_id` when the game is borrowed through Family Sharing */
+        owner_steam_id: bigint
+        success: boolean
+        error?: string
+    }
+    [client.callback.SteamCallback.TicketForWebApiResponse]: {
+        ticket: number[]
+        success: boolean
+        error?: string
+    }
+    [client.callback.SteamCallback.AuthSessionTicketResponse]: {
+        success: boolean
+        error?: string
+    }
+    [client.callback.SteamCallback.UserAchievementIconFetched]: {
+        achievement_name: string
+        /** Whether the icon is the unlocked one */
+        achieved: boolean
+        icon_handle: number
+    }
+    [client.callback.SteamCallback.GlobalStatsReceived]: {
+        game_id: bigint
+        success: boolean
+        error?: string
+    }
+    [client.callback.SteamCallback.LeaderboardScoresDownloaded]: {
+        leaderboard: bigint
+        entry_count: number
+    }
+    [client.callback.SteamCallback.LeaderboardUgcSet]: {
+        leaderboard: bigint
+        success: boolean
+        error?: string
+    }
 }
@@ -1,3 +1,13 @@
+export declare const enum BackendKind {
+  /** Talk to the running Steam client. */
+  Steam = 0,
+  /**
+   * Keep stats, achievements, cloud files, lobbies, workshop items and
+   * controllers in memory. Nothing is sent to Steam.
+   */
+  Mock = 1
+}
+
 export interface FriendInfo {
   name: string
   nickName?: string
@@ -7,7 +17,105 @@ export interface FriendInfo {
   id: bigint
 }
 
-export declare function init(appId?: number | undefined | null): void
+/**
+ * Initialize the Steam client, or the mock backend.
+ * @returns what got initialized, and whether Steam is relaunching the game instead
+ */
+/** @returns which subsystems are ready, without waiting */
+export declare function getReadiness(): Readiness
+
+export declare function init(options?: InitOptions | undefined | null): InitReport
+
+export interface InitOptions {
+  /**
+   * App ID of the game to load. Without it, Steam looks for a steam_appid.txt file
+   * or uses the app the game was launched as.
+   */
+  appId?: number
+  /**
+   * The backend to drive the API with, defaults to `BackendKind.Steam`.
+   * `BackendKind.Mock` keeps everything in memory and doesn't need Steam to be running.
+   */
+  backend?: BackendKind
+  /**
+   * Relaunch the game through Steam if it wasn't started by Steam, see `restartAppIfNecessary`.
+   * When that happens, init is skipped, `restarting` is set on the report and the game should quit.
+   * Requires `appId`. Defaults to false.
+   */
+  restartIfNecessary?: boolean
+  /**
+   * Write a steam_appid.txt file with `appId` to the working directory for the duration of init,
+   * for running outside of Steam during development. Requires `appId`. Defaults to false.
+   */
+  writeSteamAppIdFile?: boolean
+  /**
+   * Run callbacks (and `input.runFrame`) on a background thread, so pending promises
+   * resolve even if nothing calls `runCallbacks`. Defaults to false.
+   */
+  autoRunCallbacks?: boolean
+  /** How often the background pump runs, in milliseconds. Defaults to 8ms. */
+  callbackIntervalMs?: number
+  /** Ask Steam for the local user's stats and achievements right away. Defaults to true. */
+  requestStatsOnInit?: boolean
+  /** Same as calling `setLogLevel` before init. */
+  logLevel?: LogLevel
+  /**
+   * The app's stats and achievements schema, a VDF file like the `UserGameStatsSchema_<appId>.bin`
+   * Steam caches, the same layout as JSON, or the JSON the Web API's `GetSchemaForGame` returns.
+   * With it, the `stats` and `achievement` modules throw `NOT_FOUND` for a name that isn't in
+   * it, and `INVALID_PARAMETER` for a stat of another type, instead of returning false or null.
+   * The mock backend defines everything in it.
+   */
+  schema?: string
+  /**
+   * A file to keep the stat and achievement writes in when Steam can't store them, e.g. while
+   * its servers are unreachable. They're replayed on `SteamServersConnected`, or on the next
+   * launch once the stats come in, keeping the higher value of a stat and never locking an
+   * achievement again. The user's Steam ID and the app ID are added to the file name, e.g.
+   * `journal.json` is kept as `journal-76561197960265729-480.json`, and a journal is never
+   * replayed into another user's stats. Average rate stats aren't kept. Off by default.
+   */
+  statJournal?: string
+}
+
+export interface InitReport {
+  backend: BackendKind
+  appId: number
+  /** The build id of the installed depot, 0 when running outside of Steam */
+  buildId: number
+  /** Whether the user is connected to the Steam servers, false in offline mode */
+  loggedOn: boolean
+  steamId?: PlayerSteamId
+  /** The Steam interfaces that can be used, e.g. "ISteamUserStats" */
+  interfaces: Array<string>
+  /** Whether the user's stats were requested */
+  statsRequested: boolean
+  /** The game was relaunched through Steam, and should quit now. */
+  restarting: boolean
+}
+
+/**
+ * Initialize a dedicated game server, it doesn't need a Steam client to be running.
+ * Use `logOnAnonymous` or `logOn` from the `server` module to connect it to Steam.
+ */
+export declare function initServer(options: ServerInitOptions): void
+
+export declare const enum LogLevel {
+  /** Don't log anything */
+  Off = 0,
+  Error = 1,
+  Warn = 2,
+  Info = 3,
+  Debug = 4,
+  Trace = 5
+}
+
+export interface LogRecord {
+  level: LogLevel
+  /** The module that logged the record, e.g. "friends" */
+  target: string
+  message: string
+}
 
 export interface PlayerSteamId {
   steamId64: bigint
@@ -15,21 +123,236 @@ export interface PlayerSteamId {
   accountId: number
 }
 
+export declare function isCallbackPumpRunning(): boolean
+
+export interface Readiness {
+  /** The local user's stats and achievements were received, `stats` and `achievement` return real values */
+  stats: boolean
+  /** The local user's persona name is known */
+  persona: boolean
+  /** Steam Cloud is enabled for the user's account and for the app, files written are synced */
+  cloud: boolean
+}
+
+/**
+ * Waits until the local user's stats, persona and cloud are available, so that early calls to
+ * `stats.getInt` or `achievement.isActivated` don't come back empty.
+ * Stats are only received if they were requested, see `InitOptions.requestStatsOnInit`.
+ * @param timeoutMs - Defaults to 10 seconds, rejects with `TIMEOUT` naming what's still missing
+ */
+export declare function ready(timeoutMs?: number | undefined | null, signal?: AbortSignal | undefined | null): Promise<Readiness>
+
 export declare function restartAppIfNecessary(appId: number): boolean
 
 export declare function runCallbacks(): void
 
+export interface ServerInitOptions {
+  /** The IPv4 address to bind to, defaults to all interfaces. */
+  ip?: string
+  /** The port clients connect to for gameplay. */
+  gamePort: number
+  /** The port the server browser queries, usually the game port + 1. */
+  queryPort: number
+  /** Defaults to `ServerMode.AuthenticationAndSecure`. */
+  serverMode?: server.ServerMode
+  /**
+   * The version string of the server, in the form `x.x.x.x`.
+   * Clients with a different version won't see it in the server browser.
+   */
+  version: string
+  /** Same as `InitOptions.autoRunCallbacks`. */
+  autoRunCallbacks?: boolean
+  /** Same as `InitOptions.callbackIntervalMs`. */
+  callbackIntervalMs?: number
+}
+
+/**
+ * Forwards log records to `handler` instead of printing them to stderr.
+ * Pass null to go back to stderr.
+ */
+export declare function setLogHandler(handler: ((record: LogRecord) => void) | null): void
+
+/**
+ * Sets the most verbose level that gets logged.
+ * Release builds default to `LogLevel.Off`, debug builds to `LogLevel.Info`.
+ * @param target - Only set the level of one module, e.g. "friends". Without it, every module's level is reset to `level`.
+ */
+export declare function setLogLevel(level: LogLevel, target?: string | undefined | null): void
+
 /**
  * Shutdown the Steam client instance
+ * Tears down the lobbies, sockets and tickets it handed out, rejects pending promises
+ * with `SHUT_DOWN`, then releases the client.
  * PLEASE DON'T USE THIS. Use [`shutdown`] instead.
  */
-export declare function shutdownClient(): void
+export declare function shutdownClient(options?: ShutdownOptions | undefined | null): void
+
+export interface ShutdownOptions {
+  /** Store stats and achievements that haven't been stored yet. Defaults to true. */
+  flushStats?: boolean
+  /** Leave the lobbies that were created or joined. Defaults to true. */
+  leaveLobbies?: boolean
+  /** Close the P2P sockets that were opened. Defaults to true. */
+  closeSockets?: boolean
+  /** Cancel the auth tickets that were handed out. Defaults to true. */
+  cancelTickets?: boolean
+}
+
+/**
+ * Shutdown the game server instance
+ * PLEASE DON'T USE THIS. Use [`shutdown`] instead.
+ */
+export declare function shutdownServer(): void
+
+/**
+ * Starts running callbacks and `input.runFrame` on a background thread.
+ * Restarts the pump if it's already running.
+ * @param intervalMs - How often to run callbacks, defaults to 8ms
+ */
+export declare function startCallbackPump(intervalMs?: number | undefined | null): void
+
+/**
+ * Stops the background callback pump.
+ * @returns true if the pump was running
+ */
+export declare function stopCallbackPump(): boolean
 
 export declare namespace achievement {
+  export interface AchievementIcon {
+    width: number
+    height: number
+    /** RGBA pixels row by row, or a PNG file if it was asked for */
+    data: Buffer
+  }
+  export interface AchievementInfo {
+    name: string
+    /** In the user's language */
+    displayName: string
+    /** In the user's language */
+    description: string
+    /** Whether the achievement is hidden until it's unlocked */
+    hidden: boolean
+    unlocked: boolean
+    /** Unix time in seconds, null if it isn't unlocked or Steam doesn't know when it was */
+    unlockTime?: number
+    /**
+     * The share of players that unlocked it, from 0 to 100, null if the percentages weren't
+     * requested yet
+     */
+    globalPercent?: number
+  }
+  export interface AchievementPercent {
+    name: string
+    /** The share of players that unlocked it, from 0 to 100 */
+    percent: number
+    /** Whether the current user unlocked it */
+    achieved: boolean
+  }
+  /**
+   * Unlocks the achievement and stores it right away, use `stats.transaction` to change
+   * several at once.
+   *
+   * @throws NOT_FOUND if it isn't in the schema
+   */
   export function activate(achievement: string): boolean
+  /**
+   * Ties a stat to an achievement's progress. Writing the stat with `stats.setInt` or
+   * `stats.setFloat` past one of the milestones then shows the progress toast. Binding the same
+   * stat and achievement again replaces the binding, or a `stats.defineRule` rule.
+   *
+   * @throws NOT_FOUND if there's no such stat or achievement
+   * @throws INVALID_PARAMETER if `max` is 0
+   */
+  export function bindProgress(binding: ProgressBinding): void
+  /**
+   * Locks the achievement again and stores it right away, use `stats.transaction` to change
+   * several at once.
+   *
+   * @throws NOT_FOUND if it isn't in the schema
+   */
   export function clear(achievement: string): boolean
+  /**
+   * Writes `getAll` to a JSON file, along with the app, the user and the time, e.g. to attach
+   * to a support ticket about an achievement that didn't unlock.
+   */
+  export function exportAll(path: string): void
+  /**
+   * @returns the share of players that unlocked the achievement, from 0 to 100, or null if
+   * there's no such achievement or the percentages weren't requested yet
+   * @throws NOT_FOUND if it isn't in the schema
+   */
+  export function getAchievedPercent(achievement: string): number | null
+  /**
+   * Reads every achievement in one go, instead of `isActivated` and `getInfo` for each.
+   * `globalPercent` is filled in after `requestGlobalAchievementPercentages`.
+   * @throws FAILED if Steam listed an achievement it can't read
+   */
+  export function getAll(): Array<AchievementInfo>
+  /**
+   * Gets the icon for the achievement's current state, the locked one until it's unlocked.
+   *
+   * @param png - Whether to encode the icon as a PNG file. Default value is false.
+   * @param timeoutSeconds - The number of seconds to wait for Steam to fetch the icon. Default value is 10 seconds.
+   * @throws NOT_FOUND if there's no such achievement
+   * @throws TIMEOUT if Steam didn't fetch it in time, also the case for achievements without an icon
+   *
+   * {@link https://partner.steamgames.com/doc/api/ISteamUserStats#GetAchievementIcon}
+   */
+  export function getIcon(achievement: string, png?: boolean | undefined | null, timeoutSeconds?: number | undefined | null, signal?: AbortSignal | undefined | null): Promise<AchievementIcon>
+  /** @throws NOT_FOUND if there's no such achievement */
+  export function getInfo(achievement: string): AchievementInfo
+  /**
+   * @returns every achievement, the most unlocked first, or an empty array if the percentages
+   * weren't requested yet
+   */
+  export function getMostAchieved(): Array<AchievementPercent>
+  /**
+   * Reads whether another user has an achievement, after `stats.requestUserStats` for them.
+   *
+   * @returns null if there's no such achievement or the user's stats weren't requested yet
+   * @throws NOT_FOUND if it isn't in the schema
+   *
+   * {@link https://partner.steamgames.com/doc/api/ISteamUserStats#GetUserAchievementAndUnlockTime}
+   */
+  export function getUserAchievement(steamId: bigint, achievement: string): UserAchievement | null
+  /**
+   * Shows the user how far along an achievement is, with Steam's overlay toast.
+   *
+   * @returns false if there's no such achievement, it's already unlocked, or `current` isn't below `max`
+   * @throws NOT_FOUND if it isn't in the schema
+   *
+   * {@link https://partner.steamgames.com/doc/api/ISteamUserStats#IndicateAchievementProgress}
+   */
+  export function indicateAchievementProgress(achievement: string, current: number, max: number): boolean
+  /** @throws NOT_FOUND if it isn't in the schema */
   export function isActivated(achievement: string): boolean
   export function names(): Array<string>
+  export interface ProgressBinding {
+    stat: string
+    achievement: string
+    /** The stat value the achievement unlocks at */
+    max: number
+    /** The stat values to show the progress at. Default value is every quarter of `max`. */
+    milestones?: Array<number>
+  }
+  /**
+   * Fetches the share of players that unlocked each achievement, which `getAchievedPercent`
+   * and `getMostAchieved` read.
+   *
+   * @param timeoutSeconds - The number of seconds to wait for Steam. Default value is 10 seconds.
+   *
+   * {@link https://partner.steamgames.com/doc/api/ISteamUserStats#RequestGlobalAchievementPercentages}
+   */
+  export function requestGlobalAchievementPercentages(timeoutSeconds?: number | undefined | null, signal?: AbortSignal | undefined | null): Promise<void>
+  /** @returns false if the stat and achievement weren't bound */
+  export function unbindProgress(stat: string, achievement: string): boolean
+  /** Whether another user has an achievement, see `getUserAchievement`. */
+  export interface UserAchievement {
+    name: string
+    unlocked: boolean
+    /** Unix time in seconds, null if it isn't unlocked or Steam doesn't know when it was */
+    unlockTime?: number
+  }
 }
 
 export declare namespace apps {
@@ -39,6 +362,13 @@ export declare namespace apps {
   export function availableGameLanguages(): Array<string>
   export function currentBetaName(): string | null
   export function currentGameLanguage(): string
+  /**
+   * Gets the parameters of the `steam://run/<appid>//<params>` link the game was launched with.
+   * Listen to `SteamCallback.NewUrlLaunchParameters` for links opened while the game is running.
+   *
+   * {@link https://partner.steamgames.com/doc/api/ISteamApps#GetLaunchCommandLine}
+   */
+  export function getLaunchCommandLine(): string
   export function isAppInstalled(appId: number): boolean
   export function isCybercafe(): boolean
   export function isDlcInstalled(appId: number): boolean
@@ -54,23 +384,90 @@ export declare namespace auth {
     cancel(): void
     getBytes(): Buffer
   }
-  export function getAuthTicketForWebApi(identity: string, timeoutSeconds?: number | undefined | null): Promise<Ticket>
+  export function getAuthTicketForWebApi(identity: string, timeoutSeconds?: number | undefined | null, signal?: AbortSignal | undefined | null): Promise<Ticket>
   /**
    * @param ip - The string of IPv4 or IPv6 address. Use as NetworkIdentity of the remote system that will authenticate the ticket.
    * @param timeoutSeconds - The number of seconds to wait for the ticket to be validated. Default value is 10 seconds.
    */
-  export function getSessionTicketWithIp(ip: string, timeoutSeconds?: number | undefined | null): Promise<Ticket>
+  export function getSessionTicketWithIp(ip: string, timeoutSeconds?: number | undefined | null, signal?: AbortSignal | undefined | null): Promise<Ticket>
   /**
    * @param steamId64 - The user steam id or game server steam id. Use as NetworkIdentity of the remote system that will authenticate the ticket. If it is peer-to-peer then the user steam ID. If it is a game server, then the game server steam ID may be used if it was obtained from a trusted 3rd party
    * @param timeoutSeconds - The number of seconds to wait for the ticket to be validated. Default value is 10 seconds.
    */
-  export function getSessionTicketWithSteamId(steamId64: bigint, timeoutSeconds?: number | undefined | null): Promise<Ticket>
+  export function getSessionTicketWithSteamId(steamId64: bigint, timeoutSeconds?: number | undefined | null, signal?: AbortSignal | undefined | null): Promise<Ticket>
 }
 
 export declare namespace callback {
   export class Handle {
     disconnect(): void
   }
+  /** What a stream does with a callback when it's full. */
+  export const enum StreamPolicy {
+    /** Make room by dropping the oldest queued callback */
+    DropOldest = 0,
+    /**
+     * Hold up the callback pump until there's room, at most `blockTimeoutMs`, then drop the oldest.
+     * Needs `autoRunCallbacks`. Callbacks that run anywhere else, e.g. after the pump was stopped,
+     * drop the oldest right away, the JS thread couldn't drain the stream while it waits.
+     */
+    Block = 1,
+    /** Replace the queued callback with the same `coalesceKey` in place, or drop the oldest if there's none */
+    Coalesce = 2
+  }
+  export interface StreamOptions {
+    /** How many callbacks are queued before `policy` kicks in. Default value is 64. */
+    capacity?: number
+    /** Default value is `DropOldest` */
+    policy?: StreamPolicy
+    /** The payload field `Coalesce` compares, e.g. "steam_id". The whole payload is compared without one. */
+    coalesceKey?: string
+    /** How long `Block` holds up the pump. Default value is 1000 milliseconds. */
+    blockTimeoutMs?: number
+  }
+  /** An async iterator over the callbacks of one kind, see `stream`. */
+  export class Stream<C extends keyof import('./callbacks').CallbackReturns = keyof import('./callbacks').CallbackReturns> {
+    /**
+     * Resolves with the next callback, waiting for one if the queue is empty.
+     * It's done once the stream is closed and drained, or the client shut down.
+     */
+    next(signal?: AbortSignal | undefined | null): Promise<IteratorResult<import('./callbacks').CallbackReturns[C], undefined>>
+    /** Closes the stream, called when a `for await` loop is left early. */
+    return(): IteratorResult<import('./callbacks').CallbackReturns[C], undefined>
+    /** Stops listening. Callbacks already queued can still be read. */
+    close(): void
+    /** How many callbacks were dropped because the stream was full. */
+    get dropped(): number
+    [Symbol.asyncIterator](): Stream<C>
+  }
+  /**
+   * Streams the callbacks of `steamCallback` through a bounded queue, for `for await` loops.
+   *
+   * {@link StreamPolicy}
+   */
+  export function stream<C extends keyof import('./callbacks').CallbackReturns>(steamCallback: C, options?: StreamOptions | undefined | null): Stream<C>
+  /**
+   * Resolves with the next payload of `steamCallback` that `predicate` accepts, then stops listening.
+   *
+   * @param predicate - Called with each payload until it returns true. Without one, the first payload is taken.
+   * @param timeoutMs - Rejects with TIMEOUT after this many milliseconds. Without one, it waits until shutdown.
+   */
+  export function once<C extends keyof import('./callbacks').CallbackReturns>(steamCallback: C, predicate?: ((value: import('./callbacks').CallbackReturns[C]) => boolean) | undefined | null, timeoutMs?: number | undefined | null, signal?: AbortSignal | undefined | null): Promise<import('./callbacks').CallbackReturns[C]>
+  /**
+   * Writes every callback Steam dispatches from now on to `path`, one JSON object per line with
+   * its kind, payload and time. Replaces a recording that's already running.
+   */
+  export function startRecording(path: string): void
+  /** @returns the number of callbacks recorded, or null if nothing was recording */
+  export function stopRecording(): number | null
+  export function isRecording(): boolean
+  /**
+   * Feeds a recording made with `startRecording` to the registered handlers, without Steam
+   * being involved. Works with the mock backend.
+   *
+   * @param realtime - Keep the recorded delays between callbacks. Default value is false, they're replayed back to back.
+   * @returns the number of callbacks replayed
+   */
+  export function replay(path: string, realtime?: boolean | undefined | null, signal?: AbortSignal | undefined | null): Promise<number>
   export function register<C extends keyof import('./callbacks').CallbackReturns>(steamCallback: C, handler: (value: import('./callbacks').CallbackReturns[C]) => void): Handle
   export const enum SteamCallback {
     PersonaStateChange = 0,
@@ -83,7 +480,22 @@ export declare namespace callback {
     P2PSessionConnectFail = 7,
     GameLobbyJoinRequested = 8,
     MicroTxnAuthorizationResponse = 9,
-    LobbyChatMessage = 10
+    LobbyChatMessage = 10,
+    UserStatsReceived = 11,
+    UserStatsStored = 12,
+    UserAchievementStored = 13,
+    GameOverlayActivated = 14,
+    DlcInstalled = 15,
+    NewUrlLaunchParameters = 16,
+    GameRichPresenceJoinRequested = 17,
+    ScreenshotReady = 18,
+    ValidateAuthTicketResponse = 19,
+    TicketForWebApiResponse = 20,
+    AuthSessionTicketResponse = 21,
+    UserAchievementIconFetched = 22,
+    GlobalStatsReceived = 23,
+    LeaderboardScoresDownloaded = 24,
+    LeaderboardUgcSet = 25
   }
 }
 
@@ -103,7 +515,7 @@ export declare namespace cloud {
 }
 
 export declare namespace friends {
-  export function requestUserInformation(steamId: bigint, requireNameOnly: boolean, timeoutSeconds?: number | undefined | null): Promise<FriendInfo>
+  export function requestUserInformation(steamId: bigint, requireNameOnly: boolean, timeoutSeconds?: number | undefined | null, signal?: AbortSignal | undefined | null): Promise<FriendInfo>
 }
 
 export declare namespace input {
@@ -609,6 +1021,102 @@ export declare namespace input {
   }
 }
 
+export declare namespace leaderboard {
+  export class Leaderboard {
+    get handle(): bigint
+    getName(): string
+    getEntryCount(): number
+    getSortMethod(): SortMethod | null
+    getDisplayType(): DisplayType | null
+    /**
+     * @param method - Default value is `KeepBest`
+     * @param details - Up to 64 ints to keep with the score, e.g. how the run went
+     * @param timeoutSeconds - The number of seconds to wait for Steam. Default value is 10 seconds.
+     * @throws INVALID_PARAMETER if there are more than 64 details
+     *
+     * {@link https://partner.steamgames.com/doc/api/ISteamUserStats#UploadLeaderboardScore}
+     */
+    uploadScore(score: number, method?: UploadScoreMethod | undefined | null, details?: Array<number> | undefined | null, timeoutSeconds?: number | undefined | null, signal?: AbortSignal | undefined | null): Promise<ScoreUploaded>
+    /**
+     * Downloads the entries ranked `start` to `end`, both included and counted from 1.
+     * @param timeoutSeconds - The number of seconds to wait for Steam. Default value is 10 seconds.
+     */
+    downloadGlobal(start: number, end: number, timeoutSeconds?: number | undefined | null, signal?: AbortSignal | undefined | null): Promise<Array<LeaderboardEntry>>
+    /**
+     * Downloads the entries around the user's, e.g. `-4` to `5` for the 4 above and the 5 below.
+     * Empty if the user has no score.
+     * @param timeoutSeconds - The number of seconds to wait for Steam. Default value is 10 seconds.
+     */
+    downloadAroundUser(start: number, end: number, timeoutSeconds?: number | undefined | null, signal?: AbortSignal | undefined | null): Promise<Array<LeaderboardEntry>>
+    /**
+     * Downloads the entries of the user and their friends.
+     * @param timeoutSeconds - The number of seconds to wait for Steam. Default value is 10 seconds.
+     */
+    downloadFriends(timeoutSeconds?: number | undefined | null, signal?: AbortSignal | undefined | null): Promise<Array<LeaderboardEntry>>
+    /**
+     * Downloads the entries of the given users, the ones without a score are left out.
+     * @param timeoutSeconds - The number of seconds to wait for Steam. Default value is 10 seconds.
+     * @throws INVALID_PARAMETER if there are more than 100 users
+     */
+    downloadUsers(steamIds: Array<bigint>, timeoutSeconds?: number | undefined | null, signal?: AbortSignal | undefined | null): Promise<Array<LeaderboardEntry>>
+    /**
+     * Attaches UGC to the user's entry, e.g. a replay shared with `ISteamRemoteStorage::FileShare`.
+     * @param timeoutSeconds - The number of seconds to wait for Steam. Default value is 10 seconds.
+     *
+     * {@link https://partner.steamgames.com/doc/api/ISteamUserStats#AttachLeaderboardUGC}
+     */
+    attachUgc(ugcHandle: bigint, timeoutSeconds?: number | undefined | null, signal?: AbortSignal | undefined | null): Promise<void>
+  }
+  export const enum DisplayType {
+    Numeric = 0,
+    TimeSeconds = 1,
+    TimeMilliSeconds = 2
+  }
+  /**
+   * @param timeoutSeconds - The number of seconds to wait for Steam. Default value is 10 seconds.
+   * @returns null if there's no such leaderboard
+   *
+   * {@link https://partner.steamgames.com/doc/api/ISteamUserStats#FindLeaderboard}
+   */
+  export function find(name: string, timeoutSeconds?: number | undefined | null, signal?: AbortSignal | undefined | null): Promise<Leaderboard | null>
+  /**
+   * Finds a leaderboard, creating it with the given sort method and display type if it doesn't
+   * exist yet. Those are ignored for an existing one.
+   * @param timeoutSeconds - The number of seconds to wait for Steam. Default value is 10 seconds.
+   *
+   * {@link https://partner.steamgames.com/doc/api/ISteamUserStats#FindOrCreateLeaderboard}
+   */
+  export function findOrCreate(name: string, sortMethod: SortMethod, displayType: DisplayType, timeoutSeconds?: number | undefined | null, signal?: AbortSignal | undefined | null): Promise<Leaderboard>
+  export interface LeaderboardEntry {
+    user: PlayerSteamId
+    globalRank: number
+    score: number
+    details: Array<number>
+    /** The UGC attached to the entry with `attachUgc`, e.g. a replay */
+    ugcHandle?: bigint
+  }
+  export interface ScoreUploaded {
+    score: number
+    /** false if the user's score was better and was kept */
+    scoreChanged: boolean
+    globalRankNew: number
+    /** 0 if the user had no score before */
+    globalRankPrevious: number
+  }
+  export const enum SortMethod {
+    /** The lowest score ranks first, e.g. for the fastest time */
+    Ascending = 0,
+    /** The highest score ranks first */
+    Descending = 1
+  }
+  export const enum UploadScoreMethod {
+    /** Keep the user's current score if it's better than the uploaded one */
+    KeepBest = 0,
+    /** Always replace the user's score */
+    ForceUpdate = 1
+  }
+}
+
 export declare namespace localplayer {
   /** @returns the 2 digit ISO 3166-1-alpha-2 format country code which client is running in, e.g. "US" or "UK". */
   export function getIpCountry(): string
@@ -620,7 +1128,7 @@ export declare namespace localplayer {
 
 export declare namespace matchmaking {
   export class Lobby {
-    join(): Promise<Lobby>
+    join(signal?: AbortSignal | undefined | null): Promise<Lobby>
     leave(): void
     openInviteDialog(): void
     getMemberCount(): bigint
@@ -641,9 +1149,11 @@ export declare namespace matchmaking {
     get id(): bigint
     get idAsU64(): bigint
   }
-  export function createLobby(lobbyType: LobbyType, maxMembers: number): Promise<Lobby>
-  export function getLobbies(): Promise<Array<Lobby>>
-  export function joinLobby(lobbyId: bigint): Promise<Lobby>
+  /** @param timeoutSeconds - The number of seconds to wait for Steam to create the lobby. Default value is 10 seconds. */
+  export function createLobby(lobbyType: LobbyType, maxMembers: number, timeoutSeconds?: number | undefined | null, signal?: AbortSignal | undefined | null): Promise<Lobby>
+  export function getLobbies(signal?: AbortSignal | undefined | null): Promise<Array<Lobby>>
+  /** @param timeoutSeconds - The number of seconds to wait for Steam to join the lobby. Default value is 10 seconds. */
+  export function joinLobby(lobbyId: bigint, timeoutSeconds?: number | undefined | null, signal?: AbortSignal | undefined | null): Promise<Lobby>
   export const enum LobbyType {
     Private = 0,
     FriendsOnly = 1,
@@ -652,6 +1162,101 @@ export declare namespace matchmaking {
   }
 }
 
+/** Controls for the in-memory backend, only usable after `init` with `BackendKind.Mock`. */
+export declare namespace mock {
+  /**
+   * Plugs in a fake controller.
+   * @returns the handle of the new controller
+   */
+  export function addController(inputType: input.InputType): bigint
+  /**
+   * Adds another user's score to a leaderboard, replacing their earlier one. The leaderboard
+   * is created, sorted descending, if it doesn't exist yet.
+   */
+  export function addLeaderboardScore(leaderboard: string, steamId: bigint, score: number, details?: Array<number> | undefined | null): void
+  /** Defines an achievement so it can be used through the `achievement` module. */
+  export function defineAchievement(name: string, achieved?: boolean | undefined | null): void
+  /**
+   * Sets the icon `achievement.getIcon` returns.
+   * @param rgba - `width * height` RGBA pixels, row by row
+   * @returns false if the achievement isn't defined
+   * @throws INVALID_PARAMETER if the size doesn't match the pixels
+   */
+  export function defineAchievementIcon(name: string, width: number, height: number, rgba: Buffer): boolean
+  /**
+   * Sets the localized strings `achievement.getInfo` returns.
+   * @returns false if the achievement isn't defined
+   */
+  export function defineAchievementInfo(name: string, displayName: string, description: string, hidden?: boolean | undefined | null): boolean
+  /**
+   * Sets the share of players that unlocked an achievement, served once
+   * `achievement.requestGlobalAchievementPercentages` was called.
+   * @param percent - From 0 to 100
+   */
+  export function defineAchievementPercent(name: string, percent: number): void
+  /**
+   * Defines a global stat, served once `stats.requestGlobalStats` was called.
+   * @param history - The daily totals, today first
+   * @param statType - Default value is `Int`
+   */
+  export function defineGlobalStat(name: string, total: number, history?: Array<number> | undefined | null, statType?: stats.StatType | undefined | null): void
+  /**
+   * Defines a stat so it can be read and written through the `stats` module.
+   * @param statType - Default value is `Int`
+   */
+  export function defineStat(name: string, value: number, statType?: stats.StatType | undefined | null): void
+  /**
+   * Sets whether another user has an achievement, read with `achievement.getUserAchievement`
+   * once their stats were requested. The achievement has to be defined with `defineAchievement`
+   * as well.
+   * @param unlockTime - In seconds since the epoch. Default value is now.
+   */
+  export function defineUserAchievement(steamId: bigint, name: string, achieved: boolean, unlockTime?: number | undefined | null): void
+  /**
+   * Sets the name `friends.requestUserInformation` gives for another user, who's "[unknown]"
+   * until then.
+   */
+  export function defineUserName(steamId: bigint, name: string): void
+  /**
+   * Sets a stat of another user, read with `stats.getUserStatInt` and `stats.getUserStatFloat`
+   * once their stats were requested. The stat has to be defined with `defineStat` as well.
+   */
+  export function defineUserStat(steamId: bigint, name: string, value: number): void
+  /** @returns the last `[current, max]` progress indicated for the achievement, or null if there was none */
+  export function getAchievementProgress(name: string): Array<number> | null
+  /** @returns how many times the stats were stored since the last `reset` */
+  export function getStoreCount(): number
+  /** @returns how many auth tickets were issued and not cancelled */
+  export function getTicketCount(): number
+  /** @returns the last `[left, right]` vibration speeds sent to the controller, or null if it doesn't exist */
+  export function getVibration(handle: bigint): Array<number> | null
+  /** @returns true if the controller existed */
+  export function removeController(handle: bigint): boolean
+  /**
+   * Posts a callback the next time callbacks run, from the pump or `runCallbacks`, the way
+   * Steam would.
+   */
+  export function queueCallback(steamCallback: callback.SteamCallback, payload: any): void
+  /** Drops every stat, achievement, leaderboard, file, lobby, workshop item and controller. */
+  export function reset(): void
+  /** @returns true if the controller exists */
+  export function setAnalogAction(handle: bigint, actionName: string, x: number, y: number): boolean
+  /** @returns true if the controller exists */
+  export function setDigitalAction(handle: bigint, actionName: string, pressed: boolean): boolean
+  /**
+   * Holds back Steam's answer on auth tickets, e.g. to abort a request while it waits. The held
+   * ones are answered once it's turned off.
+   */
+  export function setHoldTickets(hold: boolean): void
+  /**
+   * Logs another user in, e.g. to test that what's kept for one user isn't used for another.
+   * Everything else stays as it is.
+   */
+  export function setSteamId(steamId64: bigint): void
+  /** Makes storing the stats fail, e.g. to test that a `stats.transaction` rolls back. */
+  export function setStoreFails(fails: boolean): void
+}
+
 export declare namespace networking {
   export function acceptP2PSession(steamId64: bigint): void
   export function isP2PPacketAvailable(): number
@@ -711,11 +1316,285 @@ export declare namespace overlay {
   }
 }
 
+export declare namespace server {
+  /**
+   * Validates a joining player's auth ticket, obtained on their side with `auth.getSessionTicketWithSteamId`,
+   * and adds them to the player list.
+   * If Steam refuses the ticket, the player is removed again and the promise rejects with `AUTH_FAILED`.
+   *
+   * @param timeoutSeconds - The number of seconds to wait for Steam to validate the ticket. Default value is 10 seconds.
+   *
+   * {@link https://partner.steamgames.com/doc/api/ISteamGameServer#BeginAuthSession}
+   */
+  export function authenticatePlayer(steamId64: bigint, ticket: Buffer, timeoutSeconds?: number | undefined | null, signal?: AbortSignal | undefined | null): Promise<ServerPlayer>
+  export function clearAllKeyValues(): void
+  export function getPlayer(steamId64: bigint): ServerPlayer | null
+  export function getPlayers(): Array<ServerPlayer>
+  /** @returns the steam id of the server, only valid after logging on */
+  export function getSteamId(): PlayerSteamId
+  export function isLoggedOn(): boolean
+  /** @returns true if the server is VAC secure, only valid after Steam connected */
+  export function isSecure(): boolean
+  export function logOff(): void
+  /**
+   * Logs on to a persistent game server account.
+   *
+   * {@link https://partner.steamgames.com/doc/api/ISteamGameServer#LogOn}
+   */
+  export function logOn(token: string): void
+  /** Logs on to a generic, anonymous account. */
+  export function logOnAnonymous(): void
+  /**
+   * Ends the player's auth session and removes them from the player list.
+   * @returns true if the player was on the list
+   *
+   * {@link https://partner.steamgames.com/doc/api/ISteamGameServer#EndAuthSession}
+   */
+  export function playerLeft(steamId64: bigint): boolean
+  export interface ServerPlayer {
+    steamId: PlayerSteamId
+    /** The account that owns the game, differs from `steamId` when it's borrowed through Family Sharing */
+    ownerSteamId?: PlayerSteamId
+    name?: string
+    score: number
+    /** Whether Steam has validated the player's auth ticket */
+    authenticated: boolean
+  }
+  export const enum ServerMode {
+    /** Don't authenticate user logins and don't list on the server list. */
+    NoAuthentication = 0,
+    /** Authenticate users, list on the server list, don't run VAC on clients that connect. */
+    Authentication = 1,
+    /** Authenticate users, list on the server list and VAC protect clients. */
+    AuthenticationAndSecure = 2
+  }
+  /**
+   * Turns heartbeats to the master server on or off, which is what lists the server on the server browser.
+   *
+   * {@link https://partner.steamgames.com/doc/api/ISteamGameServer#SetAdvertiseServerActive}
+   */
+  export function setAdvertiseServerActive(active: boolean): void
+  export function setBotPlayerCount(count: number): void
+  export function setDedicatedServer(dedicated: boolean): void
+  export function setGameData(data: string): void
+  export function setGameDescription(description: string): void
+  /** Sets the comma separated tags clients can filter the server list with. */
+  export function setGameTags(tags: string): void
+  /** Adds a rule shown in the server browser's details. */
+  export function setKeyValue(key: string, value: string): void
+  export function setMapName(mapName: string): void
+  export function setMaxPlayers(count: number): void
+  export function setModDir(modDir: string): void
+  export function setPasswordProtected(passwordProtected: boolean): void
+  /** Sets the game product identifier, usually the app id as a string. */
+  export function setProduct(product: string): void
+  export function setServerName(name: string): void
+  /**
+   * Updates the name and score shown for the player in the server browser.
+   * @returns false if the player isn't on the list
+   *
+   * {@link https://partner.steamgames.com/doc/api/ISteamGameServer#BUpdateUserData}
+   */
+  export function updatePlayer(steamId64: bigint, name: string, score: number): boolean
+}
+
 export declare namespace stats {
+  /** Collects the changes of a `transaction`, only usable inside its callback. */
+  export class Transaction {
+    /**
+     * @throws NOT_FOUND if there's no such stat
+     * @throws INVALID_PARAMETER if it isn't an int stat
+     */
+    setInt(name: string, value: number): void
+    /**
+     * @throws NOT_FOUND if there's no such stat
+     * @throws INVALID_PARAMETER if it isn't a float stat
+     */
+    setFloat(name: string, value: number): void
+    /** @throws NOT_FOUND if there's no such achievement */
+    unlock(achievement: string): void
+    /** @throws NOT_FOUND if there's no such achievement */
+    clear(achievement: string): void
+  }
+  /**
+   * Unlocks an achievement once a stat reaches a threshold. Every `setInt`, `setFloat` and
+   * committed `transaction` checks the rules on the stat, shows the progress toast at the
+   * milestones it went past, and unlocks the achievements it completed. The unlocks are
+   * stored together a second later, or when the client shuts down.
+   *
+   * The rule is checked right away too, for a stat that's already there. It replaces a rule or
+   * `achievement.bindProgress` binding between the same stat and achievement.
+   *
+   * @throws NOT_FOUND if there's no such stat or achievement
+   * @throws INVALID_PARAMETER if `threshold` is 0
+   */
+  export function defineRule(rule: StatRule): void
+  /** Reads a stat whatever its type.
+   * @throws NOT_FOUND if there's no such stat
+   */
+  export function get(name: string): StatValue
+  /**
+   * @returns null if there's no such stat
+   * @throws INVALID_PARAMETER if it's an int stat
+   * @throws NOT_FOUND if it isn't in the schema
+   */
+  export function getFloat(name: string): number | null
+  /**
+   * @returns the total over every player, or null if it isn't an aggregated float stat or the
+   * global stats weren't requested yet
+   * @throws NOT_FOUND if it isn't in the schema
+   */
+  export function getGlobalFloat(name: string): number | null
+  /**
+   * @param days - Default value is every day that was requested
+   * @returns the daily totals over every player, today first, or null if it isn't an
+   * aggregated float stat or no history was requested
+   * @throws NOT_FOUND if it isn't in the schema
+   */
+  export function getGlobalFloatHistory(name: string, days?: number | undefined | null): Array<number> | null
+  /**
+   * @returns the total over every player, or null if it isn't an aggregated int stat or the
+   * global stats weren't requested yet
+   * @throws NOT_FOUND if it isn't in the schema
+   */
+  export function getGlobalInt(name: string): bigint | null
+  /**
+   * @param days - Default value is every day that was requested
+   * @returns the daily totals over every player, today first, or null if it isn't an
+   * aggregated int stat or no history was requested
+   * @throws NOT_FOUND if it isn't in the schema
+   */
+  export function getGlobalIntHistory(name: string, days?: number | undefined | null): Array<bigint> | null
+  /**
+   * @returns null if there's no such stat
+   * @throws INVALID_PARAMETER if it isn't an int stat
+   * @throws NOT_FOUND if it isn't in the schema
+   */
   export function getInt(name: string): number | null
+  /**
+   * @returns null if there's no such float stat or the user's stats weren't requested yet
+   * @throws NOT_FOUND if it isn't in the schema
+   */
+  export function getUserStatFloat(steamId: bigint, name: string): number | null
+  /**
+   * @returns null if there's no such int stat or the user's stats weren't requested yet
+   * @throws NOT_FOUND if it isn't in the schema
+   */
+  export function getUserStatInt(steamId: bigint, name: string): number | null
+  /**
+   * Same as `achievement.unbindProgress`.
+   * @returns false if there was no rule between the stat and achievement
+   */
+  export function removeRule(stat: string, achievement: string): boolean
+  /**
+   * Fetches the stats totalled over every player, which the `getGlobal*` functions read.
+   * Only stats flagged as aggregated in the app's Steamworks settings are included.
+   *
+   * @param historyDays - How many days of daily totals to fetch as well, up to 60. Default value is 0.
+   * @param timeoutSeconds - The number of seconds to wait for Steam. Default value is 10 seconds.
+   *
+   * {@link https://partner.steamgames.com/doc/api/ISteamUserStats#RequestGlobalStats}
+   */
+  export function requestGlobalStats(historyDays?: number | undefined | null, timeoutSeconds?: number | undefined | null, signal?: AbortSignal | undefined | null): Promise<void>
+  /**
+   * Fetches another user's stats and achievements, which `getUserStatInt`, `getUserStatFloat`
+   * and `achievement.getUserAchievement` read. Resolves once Steam posted `UserStatsReceived`
+   * for the user.
+   *
+   * @param timeoutSeconds - The number of seconds to wait for Steam. Default value is 10 seconds.
+   * @throws if Steam has no stats for the user, e.g. when they don't own the app
+   *
+   * {@link https://partner.steamgames.com/doc/api/ISteamUserStats#RequestUserStats}
+   */
+  export function requestUserStats(steamId: bigint, timeoutSeconds?: number | undefined | null, signal?: AbortSignal | undefined | null): Promise<void>
   export function resetAll(achievementsToo: boolean): boolean
+  /**
+   * @returns false if there's no such stat
+   * @throws INVALID_PARAMETER if it isn't a float stat
+   * @throws NOT_FOUND if it isn't in the schema
+   */
+  export function setFloat(name: string, value: number): boolean
+  /**
+   * @returns false if there's no such stat
+   * @throws INVALID_PARAMETER if it isn't an int stat
+   * @throws NOT_FOUND if it isn't in the schema
+   */
   export function setInt(name: string, value: number): boolean
+  export interface StatRule {
+    stat: string
+    /** The stat value the achievement unlocks at */
+    threshold: number
+    achievement: string
+    /** The stat values to show the progress at. Default value is every quarter of `threshold`. */
+    progressMilestones?: Array<number>
+  }
+  /** How a stat is declared in the app's Steamworks settings. */
+  export const enum StatType {
+    Int = 0,
+    Float = 1,
+    /** A float averaged over time, written with `updateAvgRateStat` */
+    AvgRate = 2
+  }
+  export interface StatValue {
+    name: string
+    /** Steam can't tell an `AvgRate` stat from a `Float` one, both come back as `Float` there */
+    type: StatType
+    value: number
+  }
+  /**
+   * @returns false if Steam couldn't store the stats, `InitOptions.statJournal` keeps them
+   * for later then
+   */
   export function store(): boolean
+  /**
+   * Changes several stats and achievements at once, with a single `store` instead of one per
+   * change like `achievement.activate` does, which Steam rate limits.
+   *
+   * `stage` gets a `Transaction` to stage the changes on, the names are checked right away.
+   * Once it returns the changes are applied in order and stored. If one of them or the store
+   * fails, the ones already applied are undone, so the local state is the same as before. A
+   * transaction that's rolled back isn't kept in `InitOptions.statJournal` either, nor does it
+   * touch what's in it.
+   *
+   * @throws whatever `stage` throws, nothing is applied then
+   */
+  export function transaction(stage: (tx: Transaction) => void): TransactionResult
+  export const enum TransactionChange {
+    SetInt = 0,
+    SetFloat = 1,
+    Unlock = 2,
+    Clear = 3
+  }
+  export interface TransactionItem {
+    /** The stat or achievement */
+    name: string
+    change: TransactionChange
+    /**
+     * Whether the change went through locally, it's undone again if the transaction was
+     * rolled back
+     */
+    applied: boolean
+  }
+  export interface TransactionResult {
+    /** Whether every change was applied and stored */
+    committed: boolean
+    /** Whether the applied changes were undone because one of them, or the store, failed */
+    rolledBack: boolean
+    /** In the order the changes were staged */
+    items: Array<TransactionItem>
+  }
+  /**
+   * Adds a session to an average rate stat, e.g. the points earned per hour.
+   * Sessions aren't kept in `InitOptions.statJournal`, replaying one could count it twice.
+   *
+   * @param countThisSession - What was counted during the session, e.g. the points earned
+   * @param sessionLength - How long the session was, in the unit the stat's window is set in
+   * @returns false if there's no such stat, or it isn't an average rate stat
+   * @throws NOT_FOUND if it isn't in the schema
+   *
+   * {@link https://partner.steamgames.com/doc/api/ISteamUserStats#UpdateAvgRateStat}
+   */
+  export function updateAvgRateStat(name: string, countThisSession: number, sessionLength: number): boolean
 }
 
 export declare namespace utils {
@@ -737,9 +1616,9 @@ export declare namespace utils {
   export function getServerRealTime(): number
   export function isSteamRunningOnSteamDeck(): boolean
   /** @returns true if the floating keyboard was shown, otherwise, false */
-  export function showFloatingGamepadTextInput(keyboardMode: FloatingGamepadTextInputMode, x: number, y: number, width: number, height: number): Promise<boolean>
+  export function showFloatingGamepadTextInput(keyboardMode: FloatingGamepadTextInputMode, x: number, y: number, width: number, height: number, signal?: AbortSignal | undefined | null): Promise<boolean>
   /** @returns the entered text, or null if cancelled or could not show the input */
-  export function showGamepadTextInput(inputMode: GamepadTextInputMode, inputLineMode: GamepadTextInputLineMode, description: string, maxCharacters: number, existingText?: string | undefined | null): Promise<string | null>
+  export function showGamepadTextInput(inputMode: GamepadTextInputMode, inputLineMode: GamepadTextInputLineMode, description: string, maxCharacters: number, existingText?: string | undefined | null, signal?: AbortSignal | undefined | null): Promise<string | null>
 }
 
 export declare namespace workshop {
@@ -747,8 +1626,8 @@ export declare namespace workshop {
     creator?: number
     consumer?: number
   }
-  export function createItem(appId?: number | undefined | null): Promise<UgcResult>
-  export function deleteItem(itemId: bigint): Promise<void>
+  export function createItem(appId?: number | undefined | null, signal?: AbortSignal | undefined | null): Promise<UgcResult>
+  export function deleteItem(itemId: bigint, signal?: AbortSignal | undefined | null): Promise<void>
   /**
    * Download or update a workshop item.
    *
@@ -770,15 +1649,15 @@ export declare namespace workshop {
     current: bigint
     total: bigint
   }
-  export function getAllItems(page: number, queryType: UGCQueryType, itemType: UGCType, creatorAppId: number, consumerAppId: number, queryConfig?: WorkshopItemQueryConfig | undefined | null): Promise<WorkshopPaginatedResult>
-  export function getItem(item: bigint, queryConfig?: WorkshopItemQueryConfig | undefined | null): Promise<WorkshopItem | null>
-  export function getItems(items: Array<bigint>, queryConfig?: WorkshopItemQueryConfig | undefined | null): Promise<WorkshopItemsResult>
+  export function getAllItems(page: number, queryType: UGCQueryType, itemType: UGCType, creatorAppId: number, consumerAppId: number, queryConfig?: WorkshopItemQueryConfig | undefined | null, signal?: AbortSignal | undefined | null): Promise<WorkshopPaginatedResult>
+  export function getItem(item: bigint, queryConfig?: WorkshopItemQueryConfig | undefined | null, signal?: AbortSignal | undefined | null): Promise<WorkshopItem | null>
+  export function getItems(items: Array<bigint>, queryConfig?: WorkshopItemQueryConfig | undefined | null, signal?: AbortSignal | undefined | null): Promise<WorkshopItemsResult>
   /**
    * Get all subscribed workshop items.
    * @returns an array of subscribed workshop item ids
    */
   export function getSubscribedItems(): Array<bigint>
-  export function getUserItems(page: number, accountId: number, listType: UserListType, itemType: UGCType, sortOrder: UserListOrder, appIds: AppIDs, queryConfig?: WorkshopItemQueryConfig | undefined | null): Promise<WorkshopPaginatedResult>
+  export function getUserItems(page: number, accountId: number, listType: UserListType, itemType: UGCType, sortOrder: UserListOrder, appIds: AppIDs, queryConfig?: WorkshopItemQueryConfig | undefined | null, signal?: AbortSignal | undefined | null): Promise<WorkshopPaginatedResult>
   /**
    * Gets info about currently installed content on the disc for workshop item.
    *
@@ -807,7 +1686,7 @@ export declare namespace workshop {
    *
    * {@link https://partner.steamgames.com/doc/api/ISteamUGC#SubscribeItem}
    */
-  export function subscribe(itemId: bigint): Promise<void>
+  export function subscribe(itemId: bigint, signal?: AbortSignal | undefined | null): Promise<void>
   export const enum UgcItemVisibility {
     Public = 0,
     FriendsOnly = 1,
@@ -870,8 +1749,8 @@ export declare namespace workshop {
    *
    * {@link https://partner.steamgames.com/doc/api/ISteamUGC#UnsubscribeItem}
    */
-  export function unsubscribe(itemId: bigint): Promise<void>
-  export function updateItem(itemId: bigint, updateDetails: UgcUpdate, appId?: number | undefined | null): Promise<UgcResult>
+  export function unsubscribe(itemId: bigint, signal?: AbortSignal | undefined | null): Promise<void>
+  export function updateItem(itemId: bigint, updateDetails: UgcUpdate, appId?: number | undefined | null, signal?: AbortSignal | undefined | null): Promise<UgcResult>
   export function updateItemWithCallback(itemId: bigint, updateDetails: UgcUpdate, appId: number | undefined | null, successCallback: (data: UgcResult) => void, errorCallback: (err: any) => void, progressCallback?: (data: UpdateProgress) => void, progressCallbackIntervalMs?: number | undefined | null): void
   export interface UpdateProgress {
     status: UpdateStatus
@@ -1,6 +1,38 @@
-export function init(appId?: number): Omit<Client, "init" | "runCallbacks">;
-export function shutdown(): void;
+export function init(appIdOrOptions?: number | import("./client.d").InitOptions, options?: import("./client.d").InitOptions): Omit<Client, "init" | "initServer" | "runCallbacks" | "restartAppIfNecessary"> & { initReport: import("./client.d").InitReport };
+export function initServer(options: import("./client.d").ServerInitOptions): Omit<Client, "init" | "initServer" | "runCallbacks" | "restartAppIfNecessary">;
+export function shutdown(options?: import("./client.d").ShutdownOptions): void;
 export function restartAppIfNecessary(appId: number): boolean;
 export function electronEnableSteamOverlay(disableEachFrameInvalidation?: boolean): void;
 export type Client = typeof import("./client.d");
 export const SteamCallback: typeof import("./client.d").callback.SteamCallback;
+export const BackendKind: typeof import("./client.d").BackendKind;
+export const ServerMode: typeof import("./client.d").server.ServerMode;
+export const LogLevel: typeof import("./client.d").LogLevel;
+export function setLogHandler(handler: ((record: import("./client.d").LogRecord) => void) | null): void;
+export function setLogLevel(level: import("./client.d").LogLevel, target?: string): void;
+export type SteamworksErrorCode =
+    | "NOT_INITIALIZED"
+    | "TIMEOUT"
+    | "CALLBACK_DROPPED"
+    | "INVALID_PARAMETER"
+    | "INVALID_STATE"
+    | "NOT_FOUND"
+    | "LIMIT_EXCEEDED"
+    | "NO_CONNECTION"
+    | "NOT_LOGGED_ON"
+    | "ACCESS_DENIED"
+    | "BUSY"
+    | "CANCELLED"
+    | "IGNORED"
+    | "SERVICE_UNAVAILABLE"
+    | "AUTH_FAILED"
+    | "SHUT_DOWN"
+    | "ABORT_ERR"
+    | "PANIC"
+    | "IO_ERROR"
+    | "STEAM_ERROR"
+    | "FAILED";
+/** Every error thrown or rejected by the native module carries one of the stable codes above. */
+export interface SteamworksError extends Error {
+    code: SteamworksErrorCode;
+}
@@ -39,29 +39,67 @@ let runCallbacksInterval = undefined
 
 /**
  * Initialize the steam client or throw an error if it fails
- * @param {number} [appId] - App ID of the game to load, if undefined, will search for a steam_appid.txt file
- * @returns {Omit<Client, 'init' | 'runCallbacks'>}
+ * @param {number | import('./client.d').InitOptions} [appIdOrOptions] - App ID of the game to load, or the init options. If there's no app id, will search for a steam_appid.txt file
+ * @param {import('./client.d').InitOptions} [options] - Use `{ backend: BackendKind.Mock }` to run without Steam, `{ autoRunCallbacks: true }` to run callbacks on a background thread
+ * @returns {Omit<Client, 'init' | 'initServer' | 'runCallbacks' | 'restartAppIfNecessary'> & { initReport: import('./client.d').InitReport }}
 */
-module.exports.init = (appId) => {
-    const { init: internalInit, runCallbacks, restartAppIfNecessary, ...api } = nativeBinding
+module.exports.init = (appIdOrOptions, options) => {
+    const { init: internalInit, initServer, runCallbacks, restartAppIfNecessary, ...api } = nativeBinding
 
-    internalInit(appId)
+    const initOptions = typeof appIdOrOptions === 'object' && appIdOrOptions !== null
+        ? appIdOrOptions
+        : { ...options, appId: appIdOrOptions ?? options?.appId }
 
-    clearInterval(runCallbacksInterval)
-    runCallbacksInterval = setInterval(() => {
-        // console.log("Running callbacks")
-        runCallbacks()
-    }, 1000 / 120)
+    const initReport = internalInit(initOptions)
+    // Steam is relaunching the game, there's nothing to run callbacks for
+    if (!initReport.restarting) {
+        startRunningCallbacks(initOptions)
+    }
+
+    return { ...api, initReport }
+}
+
+/**
+ * Initialize a dedicated game server or throw an error if it fails
+ * @param {import('./client.d').ServerInitOptions} options
+ * @returns {Omit<Client, 'init' | 'initServer' | 'runCallbacks' | 'restartAppIfNecessary'>}
+*/
+module.exports.initServer = (options) => {
+    const { init, initServer: internalInitServer, runCallbacks, restartAppIfNecessary, ...api } = nativeBinding
+
+    internalInitServer(options)
+    startRunningCallbacks(options)
 
     return api
 }
 
+/** @param {{ autoRunCallbacks?: boolean } | undefined} options */
+function startRunningCallbacks(options) {
+    clearInterval(runCallbacksInterval)
+    runCallbacksInterval = undefined
+    // The native pump already runs callbacks off the JS thread
+    if (!options?.autoRunCallbacks) {
+        runCallbacksInterval = setInterval(() => {
+            // console.log("Running callbacks")
+            nativeBinding.runCallbacks()
+        }, 1000 / 120)
+    }
+}
+
 /**
- * @description Shuts down the steam client
+ * @description Shuts down the steam client and the game server, whichever is running.
+ * Pending promises reject with `SHUT_DOWN`.
+ * @param {import('./client.d').ShutdownOptions} [options] - What to tear down before the client is released, everything by default
  */
-module.exports.shutdown = () => { 
+module.exports.shutdown = (options) => { 
     clearInterval(runCallbacksInterval)
-    nativeBinding.shutdownClient()
+    for (const shutdown of [nativeBinding.shutdownServer, () => nativeBinding.shutdownClient(options)]) {
+        try {
+            shutdown()
+        } catch (e) {
+            if (e.code !== 'NOT_INITIALIZED') throw e
+        }
+    }
     runCallbacksInterval = undefined
 }
 
@@ -102,5 +140,32 @@ module.exports.electronEnableSteamOverlay = (disableEachFrameInvalidation) => {
     }
 }
 
+// Streams are their own async iterator, which is what makes them work in `for await`
+nativeBinding.callback.Stream.prototype[Symbol.asyncIterator] = function () {
+    return this
+}
+
 const SteamCallback = nativeBinding.callback.SteamCallback
-module.exports.SteamCallback = SteamCallback
\ No newline at end of file
+module.exports.SteamCallback = SteamCallback
+
+const BackendKind = nativeBinding.BackendKind
+module.exports.BackendKind = BackendKind
+
+const ServerMode = nativeBinding.server.ServerMode
+module.exports.ServerMode = ServerMode
+
+const LogLevel = nativeBinding.LogLevel
+module.exports.LogLevel = LogLevel
+
+/**
+ * Forwards log records to `handler` instead of printing them to stderr, works before `init`
+ * @param {((record: import('./client.d').LogRecord) => void) | null} handler - null to go back to stderr
+ */
+module.exports.setLogHandler = (handler) => nativeBinding.setLogHandler(handler)
+
+/**
+ * Sets the most verbose level that gets logged, works before `init`
+ * @param {import('./client.d').LogLevel} level
+ * @param {string} [target] - Only set the level of one module, e.g. "friends"
+ */
+module.exports.setLogLevel = (level, target) => nativeBinding.setLogLevel(level, target)
@@ -0,0 +1,256 @@
+//! `AbortSignal` support for the async exports.
+//!
+//! The signal is only reachable on the JS thread, so it's subscribed to while the arguments are
+//! converted and what the async part awaits is a channel the listener fires. The listener is taken
+//! off again once the operation settles, so one signal can be passed to any number of them.
+
+use std::ffi::c_void;
+use std::future::Future;
+use std::pin::pin;
+use std::ptr;
+use std::sync::Mutex;
+use std::task::Poll;
+
+use napi::bindgen_prelude::{FromNapiValue, TypeName, ValueType};
+use napi::{check_status, sys};
+use tokio::sync::oneshot;
+
+use crate::error::{Result, SteamworksError};
+
+/// A JS `AbortSignal`, taken as the last argument of every async export.
+pub struct AbortSignal {
+    // `None` when the signal was already aborted.
+    aborted: Option<oneshot::Receiver<()>>,
+    // Dropped with the signal when the operation settles.
+    _listener: Option<Listener>,
+}
+
+type Pending = Mutex<Option<oneshot::Sender<()>>>;
+
+/// Calls the signal's `removeEventListener` for the "abort" listener on the JS thread when
+/// dropped.
+struct Listener(sys::napi_threadsafe_function);
+
+// Threadsafe functions are made to be called and released from any thread.
+unsafe impl Send for Listener {}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        // Fails only when the env is shutting down, and the signal goes away with it.
+        unsafe {
+            sys::napi_call_threadsafe_function(
+                self.0,
+                ptr::null_mut(),
+                sys::ThreadsafeFunctionCallMode::nonblocking,
+            );
+            sys::napi_release_threadsafe_function(
+                self.0,
+                sys::ThreadsafeFunctionReleaseMode::release,
+            );
+        }
+    }
+}
+
+impl AbortSignal {
+    async fn aborted(self) {
+        match self.aborted {
+            None => {}
+            Some(rx) => {
+                // The listener went away with the signal, it can't abort anymore.
+                if rx.await.is_err() {
+                    std::future::pending::<()>().await;
+                }
+            }
+        }
+    }
+}
+
+impl TypeName for AbortSignal {
+    fn type_name() -> &'static str {
+        "AbortSignal"
+    }
+
+    fn value_type() -> ValueType {
+        ValueType::Object
+    }
+}
+
+impl FromNapiValue for AbortSignal {
+    unsafe fn from_napi_value(env: sys::napi_env, signal: sys::napi_value) -> napi::Result<Self> {
+        let mut aborted = ptr::null_mut();
+        check_status!(sys::napi_get_named_property(
+            env,
+            signal,
+            c"aborted".as_ptr(),
+            &mut aborted
+        ))?;
+        let mut is_aborted = false;
+        check_status!(sys::napi_get_value_bool(env, aborted, &mut is_aborted))?;
+        if is_aborted {
+            return Ok(Self {
+                aborted: None,
+                _listener: None,
+            });
+        }
+
+        let (tx, rx) = oneshot::channel();
+        let pending: *mut Pending = Box::into_raw(Box::new(Mutex::new(Some(tx))));
+        let mut listener = ptr::null_mut();
+        check_status!(sys::napi_create_function(
+            env,
+            c"onAbort".as_ptr(),
+            "onAbort".len() as isize,
+            Some(on_abort),
+            pending.cast(),
+            &mut listener,
+        ))
+        .inspect_err(|_| drop(Box::from_raw(pending)))?;
+        // Freed with the listener, which the signal holds on to until it's collected, aborted or the
+        // listener is removed.
+        check_status!(sys::napi_add_finalizer(
+            env,
+            listener,
+            pending.cast(),
+            Some(free_pending),
+            ptr::null_mut(),
+            ptr::null_mut(),
+        ))?;
+
+        let mut event = ptr::null_mut();
+        check_status!(sys::napi_create_string_utf8(
+            env,
+            c"abort".as_ptr(),
+            "abort".len() as isize,
+            &mut event
+        ))?;
+        let mut options = ptr::null_mut();
+        check_status!(sys::napi_create_object(env, &mut options))?;
+        let mut once = ptr::null_mut();
+        check_status!(sys::napi_get_boolean(env, true, &mut once))?;
+        check_status!(sys::napi_set_named_property(
+            env,
+            options,
+            c"once".as_ptr(),
+            once
+        ))?;
+
+        let mut add_event_listener = ptr::null_mut();
+        check_status!(sys::napi_get_named_property(
+            env,
+            signal,
+            c"addEventListener".as_ptr(),
+            &mut add_event_listener
+        ))?;
+        let args = [event, listener, options];
+        let mut result = ptr::null_mut();
+        check_status!(sys::napi_call_function(
+            env,
+            signal,
+            add_event_listener,
+            args.len(),
+            args.as_ptr(),
+            &mut result
+        ))?;
+
+        // `signal.removeEventListener.bind(signal, "abort", listener)`, to call from any thread.
+        let mut remove_event_listener = ptr::null_mut();
+        check_status!(sys::napi_get_named_property(
+            env,
+            signal,
+            c"removeEventListener".as_ptr(),
+            &mut remove_event_listener
+        ))?;
+        let mut bind = ptr::null_mut();
+        check_status!(sys::napi_get_named_property(
+            env,
+            remove_event_listener,
+            c"bind".as_ptr(),
+            &mut bind
+        ))?;
+        let args = [signal, event, listener];
+        let mut remove = ptr::null_mut();
+        check_status!(sys::napi_call_function(
+            env,
+            remove_event_listener,
+            bind,
+            args.len(),
+            args.as_ptr(),
+            &mut remove
+        ))?;
+        let mut name = ptr::null_mut();
+        check_status!(sys::napi_create_string_utf8(
+            env,
+            c"removeAbortListener".as_ptr(),
+            "removeAbortListener".len() as isize,
+            &mut name
+        ))?;
+        let mut remover = ptr::null_mut();
+        // Without a `call_js_cb`, `remove` is called with no arguments.
+        check_status!(sys::napi_create_threadsafe_function(
+            env,
+            remove,
+            ptr::null_mut(),
+            name,
+            0,
+            1,
+            ptr::null_mut(),
+            None,
+            ptr::null_mut(),
+            None,
+            &mut remover,
+        ))?;
+        let listener = Listener(remover);
+        // A pending operation already keeps the process alive, the listener shouldn't on its own.
+        check_status!(sys::napi_unref_threadsafe_function(env, remover))?;
+
+        Ok(Self {
+            aborted: Some(rx),
+            _listener: Some(listener),
+        })
+    }
+}
+
+unsafe extern "C" fn on_abort(
+    env: sys::napi_env,
+    info: sys::napi_callback_info,
+) -> sys::napi_value {
+    let mut data = ptr::null_mut();
+    sys::napi_get_cb_info(
+        env,
+        info,
+        ptr::null_mut(),
+        ptr::null_mut(),
+        ptr::null_mut(),
+        &mut data,
+    );
+    if let Some(pending) = (data as *const Pending).as_ref() {
+        if let Some(tx) = pending.lock().unwrap().take() {
+            let _ = tx.send(());
+        }
+    }
+    ptr::null_mut()
+}
+
+unsafe extern "C" fn free_pending(_env: sys::napi_env, data: *mut c_void, _hint: *mut c_void) {
+    drop(Box::from_raw(data as *mut Pending));
+}
+
+/// Runs `future` unless `signal` aborts first. The future is dropped then, which unregisters the
+/// callbacks it waited on, and a Steam call result that comes in later is thrown away.
+pub async fn abortable<T>(
+    signal: Option<AbortSignal>,
+    future: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    let Some(signal) = signal else {
+        return future.await;
+    };
+    let mut future = pin!(future);
+    let mut aborted = pin!(signal.aborted());
+    std::future::poll_fn(|cx| {
+        if aborted.as_mut().poll(cx).is_ready() {
+            return Poll::Ready(Err(SteamworksError::Aborted.into()));
+        }
+        future.as_mut().poll(cx)
+    })
+    .await
+}
@@ -2,44 +2,409 @@ use napi_derive::napi;
 
 #[napi]
 pub mod achievement {
+    use std::fs;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use napi::bindgen_prelude::{BigInt, Buffer};
+    use serde::Serialize;
+    use serde_json::json;
+    use tokio::sync::oneshot;
+
+    use crate::abort::AbortSignal;
+    use crate::api::callback::callback::SteamCallback;
+    use crate::api::callback::UserAchievementIconFetched;
+    use crate::error::{ErrorCode, Result, SteamworksError};
+
+    #[napi(object)]
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct AchievementInfo {
+        pub name: String,
+        /// In the user's language
+        pub display_name: String,
+        /// In the user's language
+        pub description: String,
+        /// Whether the achievement is hidden until it's unlocked
+        pub hidden: bool,
+        pub unlocked: bool,
+        /// Unix time in seconds, null if it isn't unlocked or Steam doesn't know when it was
+        pub unlock_time: Option<u32>,
+        /// The share of players that unlocked it, from 0 to 100, null if the percentages weren't
+        /// requested yet
+        pub global_percent: Option<f64>,
+    }
+
+    /// Whether another user has an achievement, see `getUserAchievement`.
+    #[napi(object)]
+    pub struct UserAchievement {
+        pub name: String,
+        pub unlocked: bool,
+        /// Unix time in seconds, null if it isn't unlocked or Steam doesn't know when it was
+        pub unlock_time: Option<u32>,
+    }
+
+    #[napi(object)]
+    pub struct AchievementIcon {
+        pub width: u32,
+        pub height: u32,
+        /// RGBA pixels row by row, or a PNG file if it was asked for
+        pub data: Buffer,
+    }
+
+    #[napi(object)]
+    pub struct ProgressBinding {
+        pub stat: String,
+        pub achievement: String,
+        /// The stat value the achievement unlocks at
+        pub max: u32,
+        /// The stat values to show the progress at. Default value is every quarter of `max`.
+        pub milestones: Option<Vec<u32>>,
+    }
+
+    fn not_found(achievement: &str) -> napi::Error<ErrorCode> {
+        SteamworksError::unknown_achievement(achievement).into()
+    }
+
+    #[napi(object)]
+    pub struct AchievementPercent {
+        pub name: String,
+        /// The share of players that unlocked it, from 0 to 100
+        pub percent: f64,
+        /// Whether the current user unlocked it
+        pub achieved: bool,
+    }
+
+    /// Unlocks the achievement and stores it right away, use `stats.transaction` to change
+    /// several at once.
+    ///
+    /// @throws NOT_FOUND if it isn't in the schema
+    #[napi]
+    pub fn activate(achievement: String) -> Result<bool> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            crate::schema::check_achievement(&achievement)?;
+            Ok(backend
+                .set_achievement(&achievement)
+                .and_then(|_| {
+                    crate::journal::achievement_written(&achievement, true);
+                    crate::journal::store(&*backend)
+                })
+                .is_ok())
+        })
+    }
+
+    /// @throws NOT_FOUND if it isn't in the schema
+    #[napi]
+    pub fn is_activated(achievement: String) -> Result<bool> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            crate::schema::check_achievement(&achievement)?;
+            Ok(backend.get_achievement(&achievement).unwrap_or(false))
+        })
+    }
+
+    /// Locks the achievement again and stores it right away, use `stats.transaction` to change
+    /// several at once.
+    ///
+    /// @throws NOT_FOUND if it isn't in the schema
     #[napi]
-    pub fn activate(achievement: String) -> bool {
-        let client = crate::client::get_client().unwrap();
-        client
-            .user_stats()
-            .achievement(&achievement)
-            .set()
-            .and_then(|_| client.user_stats().store_stats())
-            .is_ok()
+    pub fn clear(achievement: String) -> Result<bool> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            crate::schema::check_achievement(&achievement)?;
+            Ok(backend
+                .clear_achievement(&achievement)
+                .and_then(|_| {
+                    crate::journal::achievement_written(&achievement, false);
+                    crate::journal::store(&*backend)
+                })
+                .is_ok())
+        })
     }
 
     #[napi]
-    pub fn is_activated(achievement: String) -> bool {
-        let client = crate::client::get_client().unwrap();
-        client
-            .user_stats()
-            .achievement(&achievement)
-            .get()
-            .unwrap_or(false)
+    pub fn names() -> Result<Vec<String>> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            backend
+                .achievement_names()
+                .ok_or_else(|| SteamworksError::failed("Failed to get achievement names").into())
+        })
     }
 
+    /// Fetches the share of players that unlocked each achievement, which `getAchievedPercent`
+    /// and `getMostAchieved` read.
+    ///
+    /// @param timeoutSeconds - The number of seconds to wait for Steam. Default value is 10 seconds.
+    ///
+    /// {@link https://partner.steamgames.com/doc/api/ISteamUserStats#RequestGlobalAchievementPercentages}
     #[napi]
-    pub fn clear(achievement: String) -> bool {
-        let client = crate::client::get_client().unwrap();
-        client
-            .user_stats()
-            .achievement(&achievement)
-            .clear()
-            .and_then(|_| client.user_stats().store_stats())
-            .is_ok()
+    pub async fn request_global_achievement_percentages(
+        timeout_seconds: Option<u32>,
+        signal: Option<AbortSignal>,
+    ) -> Result<()> {
+        crate::panic::guard_async(crate::abort::abortable(signal, async move {
+            let backend = crate::client::get_backend()?;
+
+            let (tx, rx) = oneshot::channel();
+            backend.request_global_achievement_percentages(Box::new(|result| {
+                let _ = tx.send(result);
+            }));
+
+            let timeout = Duration::from_secs(u64::from(timeout_seconds.unwrap_or(10)));
+            crate::dispatch::receive(rx, timeout, "the global achievement percentages")
+                .await?
+                .map_err(SteamworksError::from)?;
+            Ok(())
+        }))
+        .await
+    }
+
+    /// @returns the share of players that unlocked the achievement, from 0 to 100, or null if
+    /// there's no such achievement or the percentages weren't requested yet
+    /// @throws NOT_FOUND if it isn't in the schema
+    #[napi]
+    pub fn get_achieved_percent(achievement: String) -> Result<Option<f64>> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            crate::schema::check_achievement(&achievement)?;
+            Ok(backend
+                .achievement_achieved_percent(&achievement)
+                .map(f64::from))
+        })
+    }
+
+    /// @returns every achievement, the most unlocked first, or an empty array if the percentages
+    /// weren't requested yet
+    #[napi]
+    pub fn get_most_achieved() -> Result<Vec<AchievementPercent>> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            Ok(backend
+                .most_achieved_achievements()
+                .into_iter()
+                .map(|(name, percent, achieved)| AchievementPercent {
+                    name,
+                    percent: f64::from(percent),
+                    achieved,
+                })
+                .collect())
+        })
+    }
+
+    /// @throws NOT_FOUND if there's no such achievement
+    #[napi]
+    pub fn get_info(achievement: String) -> Result<AchievementInfo> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            crate::schema::check_achievement(&achievement)?;
+            backend
+                .achievement_info(&achievement)
+                .ok_or_else(|| not_found(&achievement))
+        })
+    }
+
+    fn snapshot() -> Result<Vec<AchievementInfo>> {
+        let backend = crate::client::get_backend()?;
+        let names = backend
+            .achievement_names()
+            .ok_or_else(|| SteamworksError::failed("Failed to get achievement names"))?;
+        names
+            .into_iter()
+            .map(|name| {
+                backend.achievement_info(&name).ok_or_else(|| {
+                    SteamworksError::failed(format!("Failed to read the achievement {}", name))
+                        .into()
+                })
+            })
+            .collect()
+    }
+
+    /// Reads every achievement in one go, instead of `isActivated` and `getInfo` for each.
+    /// `globalPercent` is filled in after `requestGlobalAchievementPercentages`.
+    /// @throws FAILED if Steam listed an achievement it can't read
+    #[napi]
+    pub fn get_all() -> Result<Vec<AchievementInfo>> {
+        crate::panic::guard(snapshot)
+    }
+
+    /// Writes `getAll` to a JSON file, along with the app, the user and the time, e.g. to attach
+    /// to a support ticket about an achievement that didn't unlock.
+    #[napi]
+    pub fn export_all(path: String) -> Result<()> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            let achievements = snapshot()?;
+            let exported_at = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |since| since.as_secs());
+            let export = json!({
+                "version": 1,
+                "appId": backend.app_id().0,
+                // A string, JSON numbers can't hold every steam id.
+                "steamId": backend.steam_id().raw().to_string(),
+                "exportedAt": exported_at,
+                "achievements": achievements,
+            });
+            let content = serde_json::to_string_pretty(&export).map_err(|e| {
+                SteamworksError::failed(format!("Failed to serialize the achievements: {}", e))
+            })?;
+            fs::write(&path, content).map_err(SteamworksError::from)?;
+            log_info!("Exported {} achievements to {}", achievements.len(), path);
+            Ok(())
+        })
+    }
+
+    /// Reads whether another user has an achievement, after `stats.requestUserStats` for them.
+    ///
+    /// @returns null if there's no such achievement or the user's stats weren't requested yet
+    /// @throws NOT_FOUND if it isn't in the schema
+    ///
+    /// {@link https://partner.steamgames.com/doc/api/ISteamUserStats#GetUserAchievementAndUnlockTime}
+    #[napi]
+    pub fn get_user_achievement(
+        steam_id: BigInt,
+        achievement: String,
+    ) -> Result<Option<UserAchievement>> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            crate::schema::check_achievement(&achievement)?;
+            let steam_id = steamworks::SteamId::from_raw(steam_id.get_u64().1);
+            Ok(backend
+                .user_achievement(steam_id, &achievement)
+                .map(|(unlocked, unlock_time)| UserAchievement {
+                    name: achievement,
+                    unlocked,
+                    // Steam reports 0 for achievements unlocked before it kept track of the time.
+                    unlock_time: (unlocked && unlock_time != 0).then_some(unlock_time),
+                }))
+        })
+    }
+
+    /// Gets the icon for the achievement's current state, the locked one until it's unlocked.
+    ///
+    /// @param png - Whether to encode the icon as a PNG file. Default value is false.
+    /// @param timeoutSeconds - The number of seconds to wait for Steam to fetch the icon. Default value is 10 seconds.
+    /// @throws NOT_FOUND if there's no such achievement
+    /// @throws TIMEOUT if Steam didn't fetch it in time, also the case for achievements without an icon
+    ///
+    /// {@link https://partner.steamgames.com/doc/api/ISteamUserStats#GetAchievementIcon}
+    #[napi]
+    pub async fn get_icon(
+        achievement: String,
+        png: Option<bool>,
+        timeout_seconds: Option<u32>,
+        signal: Option<AbortSignal>,
+    ) -> Result<AchievementIcon> {
+        crate::panic::guard_async(crate::abort::abortable(signal, async move {
+            let backend = crate::client::get_backend()?;
+            crate::schema::check_achievement(&achievement)?;
+            if backend.achievement_info(&achievement).is_none() {
+                return Err(not_found(&achievement));
+            }
+
+            let name = achievement.clone();
+            let fetched = crate::dispatch::wait_for(
+                SteamCallback::UserAchievementIconFetched,
+                move |fetched: &UserAchievementIconFetched| {
+                    (fetched.achievement_name == name).then_some(())
+                },
+            )?;
+            let failed = || SteamworksError::failed("Failed to read the achievement icon");
+            let icon = match backend
+                .achievement_icon(&achievement)
+                .map_err(|()| failed())?
+            {
+                Some(icon) => icon,
+                None => {
+                    let timeout = Duration::from_secs(u64::from(timeout_seconds.unwrap_or(10)));
+                    fetched.wait(timeout).await?;
+                    backend
+                        .achievement_icon(&achievement)
+                        .ok()
+                        .flatten()
+                        .ok_or_else(failed)?
+                }
+            };
+
+            let (width, height, rgba) = icon;
+            let data = if png.unwrap_or(false) {
+                crate::png::encode_rgba(width, height, &rgba)
+            } else {
+                rgba
+            };
+            Ok(AchievementIcon {
+                width,
+                height,
+                data: data.into(),
+            })
+        }))
+        .await
+    }
+
+    /// Shows the user how far along an achievement is, with Steam's overlay toast.
+    ///
+    /// @returns false if there's no such achievement, it's already unlocked, or `current` isn't below `max`
+    /// @throws NOT_FOUND if it isn't in the schema
+    ///
+    /// {@link https://partner.steamgames.com/doc/api/ISteamUserStats#IndicateAchievementProgress}
+    #[napi]
+    pub fn indicate_achievement_progress(
+        achievement: String,
+        current: u32,
+        max: u32,
+    ) -> Result<bool> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            crate::schema::check_achievement(&achievement)?;
+            Ok(backend
+                .indicate_achievement_progress(&achievement, current, max)
+                .is_ok())
+        })
+    }
+
+    /// Ties a stat to an achievement's progress. Writing the stat with `stats.setInt` or
+    /// `stats.setFloat` past one of the milestones then shows the progress toast. Binding the same
+    /// stat and achievement again replaces the binding, or a `stats.defineRule` rule.
+    ///
+    /// @throws NOT_FOUND if there's no such stat or achievement
+    /// @throws INVALID_PARAMETER if `max` is 0
+    #[napi]
+    pub fn bind_progress(binding: ProgressBinding) -> Result<()> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            if binding.max == 0 {
+                return Err(napi::Error::new(
+                    ErrorCode::InvalidParameter,
+                    "The progress max has to be above 0",
+                ));
+            }
+            crate::schema::check_stat(&binding.stat, &[])?;
+            crate::schema::check_achievement(&binding.achievement)?;
+            if backend.stat_type(&binding.stat).is_none() {
+                return Err(SteamworksError::unknown_stat(&binding.stat).into());
+            }
+            if backend.achievement_info(&binding.achievement).is_none() {
+                return Err(not_found(&binding.achievement));
+            }
+
+            let milestones = binding
+                .milestones
+                .unwrap_or_else(|| crate::progress::default_milestones(binding.max));
+            crate::progress::bind(
+                &binding.stat,
+                &binding.achievement,
+                binding.max,
+                milestones,
+                false,
+            );
+            Ok(())
+        })
     }
 
+    /// @returns false if the stat and achievement weren't bound
     #[napi]
-    pub fn names() -> Vec<String> {
-        let client = crate::client::get_client().unwrap();
-        client
-            .user_stats()
-            .get_achievement_names()
-            .expect("Failed to get achievement names")
+    pub fn unbind_progress(stat: String, achievement: String) -> Result<bool> {
+        crate::panic::guard(|| Ok(crate::progress::unbind(&stat, &achievement)))
     }
 }
@@ -1,92 +1,151 @@
+use std::ffi::CStr;
+
 use super::localplayer::PlayerSteamId;
 use napi_derive::napi;
 
+/// The parameters of the `steam://run/<appid>//<params>` link the game was launched with, if any.
+pub(crate) fn launch_command_line() -> String {
+    let mut buffer = vec![0u8; 1024];
+    unsafe {
+        steamworks::sys::SteamAPI_ISteamApps_GetLaunchCommandLine(
+            steamworks::sys::SteamAPI_SteamApps_v008(),
+            buffer.as_mut_ptr().cast(),
+            buffer.len() as i32,
+        );
+    }
+    CStr::from_bytes_until_nul(&buffer)
+        .map(|command_line| command_line.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
 #[napi]
 pub mod apps {
     use super::PlayerSteamId;
     use steamworks::AppId;
 
+    use crate::error::Result;
+
+    #[napi]
+    pub fn is_subscribed_app(app_id: u32) -> Result<bool> {
+        crate::panic::guard(|| {
+            let client = crate::client::get_client()?;
+            Ok(client.apps().is_subscribed_app(AppId(app_id)))
+        })
+    }
     #[napi]
-    pub fn is_subscribed_app(app_id: u32) -> bool {
-        let client = crate::client::get_client().unwrap();
-        client.apps().is_subscribed_app(AppId(app_id))
+    pub fn is_app_installed(app_id: u32) -> Result<bool> {
+        crate::panic::guard(|| {
+            let client = crate::client::get_client()?;
+            Ok(client.apps().is_app_installed(AppId(app_id)))
+        })
     }
+
     #[napi]
-    pub fn is_app_installed(app_id: u32) -> bool {
-        let client = crate::client::get_client().unwrap();
-        client.apps().is_app_installed(AppId(app_id))
+    pub fn is_dlc_installed(app_id: u32) -> Result<bool> {
+        crate::panic::guard(|| {
+            let client = crate::client::get_client()?;
+            Ok(client.apps().is_dlc_installed(AppId(app_id)))
+        })
     }
 
     #[napi]
-    pub fn is_dlc_installed(app_id: u32) -> bool {
-        let client = crate::client::get_client().unwrap();
-        client.apps().is_dlc_installed(AppId(app_id))
+    pub fn is_subscribed_from_free_weekend() -> Result<bool> {
+        crate::panic::guard(|| {
+            let client = crate::client::get_client()?;
+            Ok(client.apps().is_subscribed_from_free_weekend())
+        })
     }
 
     #[napi]
-    pub fn is_subscribed_from_free_weekend() -> bool {
-        let client = crate::client::get_client().unwrap();
-        client.apps().is_subscribed_from_free_weekend()
+    pub fn is_vac_banned() -> Result<bool> {
+        crate::panic::guard(|| {
+            let client = crate::client::get_client()?;
+            Ok(client.apps().is_vac_banned())
+        })
     }
 
     #[napi]
-    pub fn is_vac_banned() -> bool {
-        let client = crate::client::get_client().unwrap();
-        client.apps().is_vac_banned()
+    pub fn is_cybercafe() -> Result<bool> {
+        crate::panic::guard(|| {
+            let client = crate::client::get_client()?;
+            Ok(client.apps().is_cybercafe())
+        })
     }
 
     #[napi]
-    pub fn is_cybercafe() -> bool {
-        let client = crate::client::get_client().unwrap();
-        client.apps().is_cybercafe()
+    pub fn is_low_violence() -> Result<bool> {
+        crate::panic::guard(|| {
+            let client = crate::client::get_client()?;
+            Ok(client.apps().is_low_violence())
+        })
     }
 
     #[napi]
-    pub fn is_low_violence() -> bool {
-        let client = crate::client::get_client().unwrap();
-        client.apps().is_low_violence()
+    pub fn is_subscribed() -> Result<bool> {
+        crate::panic::guard(|| {
+            let client = crate::client::get_client()?;
+            Ok(client.apps().is_subscribed())
+        })
     }
 
     #[napi]
-    pub fn is_subscribed() -> bool {
-        let client = crate::client::get_client().unwrap();
-        client.apps().is_subscribed()
+    pub fn app_build_id() -> Result<i32> {
+        crate::panic::guard(|| {
+            let client = crate::client::get_client()?;
+            Ok(client.apps().app_build_id())
+        })
     }
 
     #[napi]
-    pub fn app_build_id() -> i32 {
-        let client = crate::client::get_client().unwrap();
-        client.apps().app_build_id()
+    pub fn app_install_dir(app_id: u32) -> Result<String> {
+        crate::panic::guard(|| {
+            let client = crate::client::get_client()?;
+            Ok(client.apps().app_install_dir(AppId(app_id)))
+        })
     }
 
     #[napi]
-    pub fn app_install_dir(app_id: u32) -> String {
-        let client = crate::client::get_client().unwrap();
-        client.apps().app_install_dir(AppId(app_id))
+    pub fn app_owner() -> Result<PlayerSteamId> {
+        crate::panic::guard(|| {
+            let client = crate::client::get_client()?;
+            let steam_id = client.apps().app_owner();
+            Ok(PlayerSteamId::from_steamid(steam_id))
+        })
     }
 
     #[napi]
-    pub fn app_owner() -> PlayerSteamId {
-        let client = crate::client::get_client().unwrap();
-        let steam_id = client.apps().app_owner();
-        PlayerSteamId::from_steamid(steam_id)
+    pub fn available_game_languages() -> Result<Vec<String>> {
+        crate::panic::guard(|| {
+            let client = crate::client::get_client()?;
+            Ok(client.apps().available_game_languages())
+        })
     }
 
     #[napi]
-    pub fn available_game_languages() -> Vec<String> {
-        let client = crate::client::get_client().unwrap();
-        client.apps().available_game_languages()
+    pub fn current_game_language() -> Result<String> {
+        crate::panic::guard(|| {
+            let client = crate::client::get_client()?;
+            Ok(client.apps().current_game_language())
+        })
     }
 
     #[napi]
-    pub fn current_game_language() -> String {
-        let client = crate::client::get_client().unwrap();
-        client.apps().current_game_language()
+    pub fn current_beta_name() -> Result<Option<String>> {
+        crate::panic::guard(|| {
+            let client = crate::client::get_client()?;
+            Ok(client.apps().current_beta_name())
+        })
     }
 
+    /// Gets the parameters of the `steam://run/<appid>//<params>` link the game was launched with.
+    /// Listen to `SteamCallback.NewUrlLaunchParameters` for links opened while the game is running.
+    ///
+    /// {@link https://partner.steamgames.com/doc/api/ISteamApps#GetLaunchCommandLine}
     #[napi]
-    pub fn current_beta_name() -> Option<String> {
-        let client = crate::client::get_client().unwrap();
-        client.apps().current_beta_name()
+    pub fn get_launch_command_line() -> Result<String> {
+        crate::panic::guard(|| {
+            crate::client::get_client()?;
+            Ok(super::launch_command_line())
+        })
     }
 }
@@ -3,31 +3,37 @@ use napi_derive::napi;
 #[napi]
 pub mod auth {
     use std::net::SocketAddr;
+    use std::time::Duration;
 
-    use napi::bindgen_prelude::{BigInt, Buffer, Error};
-    use steamworks::{
-        networking_types::NetworkingIdentity, AuthSessionTicketResponse, AuthTicket, SteamId,
-        TicketForWebApiResponse,
-    };
+    use napi::bindgen_prelude::{BigInt, Buffer};
+    use steamworks::{networking_types::NetworkingIdentity, SteamId};
     use tokio::sync::oneshot;
 
+    use crate::abort::AbortSignal;
+    use crate::error::{Result, SteamworksError};
+
     #[napi]
     pub struct Ticket {
         pub(crate) data: Vec<u8>,
-        pub(crate) handle: AuthTicket,
+        /// The backend's handle for the ticket
+        pub(crate) handle: u32,
     }
 
     #[napi]
     impl Ticket {
         #[napi]
-        pub fn cancel(&mut self) {
-            let client = crate::client::get_client().unwrap();
-            client.user().cancel_authentication_ticket(self.handle);
+        pub fn cancel(&mut self) -> Result<()> {
+            crate::panic::guard(|| {
+                let backend = crate::client::get_backend()?;
+                backend.cancel_ticket(self.handle);
+                crate::resources::untrack_ticket(self.handle);
+                Ok(())
+            })
         }
 
         #[napi]
-        pub fn get_bytes(&self) -> Buffer {
-            self.data.clone().into()
+        pub fn get_bytes(&self) -> Result<Buffer> {
+            crate::panic::guard(|| Ok(self.data.clone().into()))
         }
     }
 
@@ -37,11 +43,15 @@ pub mod auth {
     pub async fn get_session_ticket_with_steam_id(
         steam_id64: BigInt,
         timeout_seconds: Option<u32>,
-    ) -> Result<Ticket, Error> {
-        get_session_ticket(
-            NetworkingIdentity::new_steam_id(SteamId::from_raw(steam_id64.get_u64().1)),
-            timeout_seconds,
-        )
+        signal: Option<AbortSignal>,
+    ) -> Result<Ticket> {
+        crate::panic::guard_async(crate::abort::abortable(signal, async move {
+            get_session_ticket(
+                NetworkingIdentity::new_steam_id(SteamId::from_raw(steam_id64.get_u64().1)),
+                timeout_seconds,
+            )
+            .await
+        }))
         .await
     }
 
@@ -51,11 +61,17 @@ pub mod auth {
     pub async fn get_session_ticket_with_ip(
         ip: String,
         timeout_seconds: Option<u32>,
-    ) -> Result<Ticket, Error> {
-        match ip.parse::<SocketAddr>() {
-            Ok(addr) => get_session_ticket(NetworkingIdentity::new_ip(addr), timeout_seconds).await,
-            Err(e) => Err(Error::from_reason(e.to_string())),
-        }
+        signal: Option<AbortSignal>,
+    ) -> Result<Ticket> {
+        crate::panic::guard_async(crate::abort::abortable(signal, async move {
+            match ip.parse::<SocketAddr>() {
+                Ok(addr) => {
+                    get_session_ticket(NetworkingIdentity::new_ip(addr), timeout_seconds).await
+                }
+                Err(e) => Err(SteamworksError::invalid_parameter(e.to_string()).into()),
+            }
+        }))
+        .await
     }
 
     /// @param networkIdentity - The identity of the remote system that will authenticate the ticket. If it is peer-to-peer then the user steam ID. If it is a game server, then the game server steam ID may be used if it was obtained from a trusted 3rd party, otherwise use the IP address. If it is a service, a string identifier of that service if one if provided.
@@ -63,116 +79,74 @@ pub mod auth {
     async fn get_session_ticket(
         network_identity: NetworkingIdentity,
         timeout_seconds: Option<u32>,
-    ) -> Result<Ticket, Error> {
-        let client = crate::client::get_client().unwrap();
-        let (tx, rx) = oneshot::channel();
-        let mut tx = Some(tx);
-
-        let (ticket_handle, ticket) = client
-            .user()
-            .authentication_session_ticket(network_identity);
-
-        let callback =
-            client.register_callback(move |session_ticket_response: AuthSessionTicketResponse| {
-                if session_ticket_response.ticket == ticket_handle {
-                    if let Some(tx) = tx.take() {
-                        tx.send(match session_ticket_response.result {
-                            Ok(()) => Ok(()),
-                            Err(e) => Err(Error::from_reason(e.to_string())),
-                        })
-                        .unwrap();
-                    }
-                }
-            });
+    ) -> Result<Ticket> {
+        let backend = crate::client::get_backend()?;
 
-        let mut ticket = Ticket {
-            data: ticket,
-            handle: ticket_handle,
-        };
-
-        let timeout_seconds = u64::from(timeout_seconds.unwrap_or(10));
-        let result =
-            tokio::time::timeout(std::time::Duration::from_secs(timeout_seconds), rx).await;
-
-        drop(callback);
-
-        match result {
-            Ok(result) => match result {
-                Ok(Ok(())) => Ok(ticket),
-                Ok(Err(e)) => {
-                    ticket.cancel();
-                    Err(e)
-                }
-                Err(e) => {
-                    ticket.cancel();
-                    Err(Error::from_reason(e.to_string()))
-                }
-            },
-            Err(_) => {
-                ticket.cancel();
-                Err(Error::from_reason(
-                    "Steam didn't validated the ticket in time.",
-                ))
-            }
-        }
+        let (tx, rx) = oneshot::channel();
+        let (handle, data) = backend.session_ticket(
+            network_identity,
+            Box::new(|result| {
+                let _ = tx.send(result);
+            }),
+        )?;
+        let pending = PendingTicket(Some(handle));
+
+        wait_for_ticket(rx, timeout_seconds).await?;
+        Ok(pending.hand_out(data))
     }
 
     #[napi]
     pub async fn get_auth_ticket_for_web_api(
         identity: String,
         timeout_seconds: Option<u32>,
-    ) -> Result<Ticket, Error> {
-        let client = crate::client::get_client().unwrap();
-        let (tx, rx) = oneshot::channel();
-        let mut tx = Some(tx);
-
-        let ticket_handle = client
-            .user()
-            .authentication_session_ticket_for_webapi(&identity);
-
-        let callback =
-            client.register_callback(move |ticket_for_webapi_response: TicketForWebApiResponse| {
-                if ticket_for_webapi_response.ticket_handle == ticket_handle {
-                    let mut ticket = ticket_for_webapi_response.ticket;
-                    ticket.truncate(ticket_for_webapi_response.ticket_len as usize);
-
-                    if let Some(tx) = tx.take() {
-                        tx.send(match ticket_for_webapi_response.result {
-                            Ok(()) => Ok(ticket),
-                            Err(e) => Err(Error::from_reason(e.to_string())),
-                        })
-                        .unwrap();
-                    }
-                }
-            });
+        signal: Option<AbortSignal>,
+    ) -> Result<Ticket> {
+        crate::panic::guard_async(crate::abort::abortable(signal, async move {
+            let backend = crate::client::get_backend()?;
+
+            let (tx, rx) = oneshot::channel();
+            let handle = backend.web_api_ticket(
+                &identity,
+                Box::new(|result| {
+                    let _ = tx.send(result);
+                }),
+            )?;
+            let pending = PendingTicket(Some(handle));
 
-        let timeout_seconds = u64::from(timeout_seconds.unwrap_or(10));
-        let result =
-            tokio::time::timeout(std::time::Duration::from_secs(timeout_seconds), rx).await;
+            let data = wait_for_ticket(rx, timeout_seconds).await?;
+            Ok(pending.hand_out(data))
+        }))
+        .await
+    }
 
-        drop(callback);
+    /// Cancels a ticket when dropped before it's handed out, e.g. when the future waiting on it
+    /// timed out or was aborted.
+    struct PendingTicket(Option<u32>);
 
-        match result {
-            Ok(result) => match result {
-                Ok(Ok(data)) => Ok(Ticket {
-                    handle: ticket_handle,
-                    data,
-                }),
-                Ok(Err(e)) => {
-                    client.user().cancel_authentication_ticket(ticket_handle);
-                    Err(e)
-                }
-                Err(e) => {
-                    client.user().cancel_authentication_ticket(ticket_handle);
-                    Err(Error::from_reason(e.to_string()))
+    impl PendingTicket {
+        fn hand_out(mut self, data: Vec<u8>) -> Ticket {
+            let handle = self.0.take().expect("a ticket is only handed out once");
+            crate::resources::track_ticket(handle);
+            Ticket { data, handle }
+        }
+    }
+
+    impl Drop for PendingTicket {
+        fn drop(&mut self) {
+            if let Some(handle) = self.0 {
+                log_debug!("Cancelling auth ticket {} that was given up on", handle);
+                if let Ok(backend) = crate::client::get_backend() {
+                    backend.cancel_ticket(handle);
                 }
-            },
-            Err(_) => {
-                client.user().cancel_authentication_ticket(ticket_handle);
-                Err(Error::from_reason(
-                    "Steam didn't validated the ticket in time.",
-                ))
             }
         }
     }
+
+    async fn wait_for_ticket<T>(
+        rx: oneshot::Receiver<Result<T, steamworks::SteamError>>,
+        timeout_seconds: Option<u32>,
+    ) -> Result<T, SteamworksError> {
+        let timeout = Duration::from_secs(u64::from(timeout_seconds.unwrap_or(10)));
+        Ok(crate::dispatch::receive(rx, timeout, "the auth ticket").await??)
+    }
 }
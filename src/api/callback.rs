@@ -1,28 +1,208 @@
+use std::ffi::c_void;
+
 use napi_derive::napi;
+use steamworks::SteamError;
+
+/// Posted when the game is already running and the user opens a `steam://run/<appid>//<params>` link.
+/// steamworks-rs doesn't wrap this one.
+pub struct NewUrlLaunchParameters {
+    pub command_line: String,
+}
+
+unsafe impl steamworks::Callback for NewUrlLaunchParameters {
+    // k_iSteamAppsCallbacks + 14
+    const ID: i32 = 1014;
+
+    unsafe fn from_raw(_: *mut c_void) -> Self {
+        // The callback has no fields, the parameters have to be asked for.
+        NewUrlLaunchParameters {
+            command_line: crate::api::apps::launch_command_line(),
+        }
+    }
+}
+
+/// Posted once a screenshot taken by the user or with `TriggerScreenshot` was written to disk.
+pub struct ScreenshotReady {
+    pub local_handle: u32,
+    pub result: Result<(), SteamError>,
+}
+
+unsafe impl steamworks::Callback for ScreenshotReady {
+    // k_iSteamScreenshotsCallbacks + 1
+    const ID: i32 = 2301;
+
+    unsafe fn from_raw(raw: *mut c_void) -> Self {
+        let raw = &*(raw as *mut steamworks::sys::ScreenshotReady_t);
+        ScreenshotReady {
+            local_handle: raw.m_hLocal,
+            result: match raw.m_eResult {
+                steamworks::sys::EResult::k_EResultOK => Ok(()),
+                e => Err(e.into()),
+            },
+        }
+    }
+}
+
+/// Posted once an achievement icon `GetAchievementIcon` didn't have yet was fetched.
+/// steamworks-rs doesn't wrap this one.
+pub struct UserAchievementIconFetched {
+    pub achievement_name: String,
+    pub achieved: bool,
+    pub icon_handle: i32,
+}
+
+unsafe impl steamworks::Callback for UserAchievementIconFetched {
+    // k_iSteamUserStatsCallbacks + 9
+    const ID: i32 = 1109;
+
+    unsafe fn from_raw(raw: *mut c_void) -> Self {
+        let raw = &*(raw as *mut steamworks::sys::UserAchievementIconFetched_t);
+        UserAchievementIconFetched {
+            achievement_name: std::ffi::CStr::from_ptr(raw.m_rgchAchievementName.as_ptr())
+                .to_string_lossy()
+                .into_owned(),
+            achieved: raw.m_bAchieved,
+            icon_handle: raw.m_nIconHandle,
+        }
+    }
+}
+
+/// Posted once the global stats `RequestGlobalStats` asked for came in.
+/// steamworks-rs doesn't wrap this one.
+pub struct GlobalStatsReceived {
+    pub game_id: u64,
+    pub result: Result<(), SteamError>,
+}
+
+unsafe impl steamworks::Callback for GlobalStatsReceived {
+    // k_iSteamUserStatsCallbacks + 12
+    const ID: i32 = 1112;
+
+    unsafe fn from_raw(raw: *mut c_void) -> Self {
+        let raw = &*(raw as *mut steamworks::sys::GlobalStatsReceived_t);
+        GlobalStatsReceived {
+            game_id: raw.m_nGameID,
+            result: match raw.m_eResult {
+                steamworks::sys::EResult::k_EResultOK => Ok(()),
+                e => Err(e.into()),
+            },
+        }
+    }
+}
+
+/// Posted once the entries `DownloadLeaderboardEntries` or `DownloadLeaderboardEntriesForUsers`
+/// asked for came in. steamworks-rs only answers its own downloads, without the attached UGC.
+pub struct LeaderboardScoresDownloaded {
+    pub leaderboard: u64,
+    /// Read right away, Steam frees them once the callbacks ran.
+    pub entries: Vec<crate::api::leaderboard::leaderboard::LeaderboardEntry>,
+}
+
+unsafe impl steamworks::Callback for LeaderboardScoresDownloaded {
+    // k_iSteamUserStatsCallbacks + 5
+    const ID: i32 = 1105;
+
+    unsafe fn from_raw(raw: *mut c_void) -> Self {
+        use crate::api::leaderboard::leaderboard::{LeaderboardEntry, MAX_DETAILS};
+        // `k_UGCHandleInvalid`
+        const UGC_HANDLE_INVALID: u64 = u64::MAX;
+
+        let raw = &*(raw as *mut steamworks::sys::LeaderboardScoresDownloaded_t);
+        let entries = (0..raw.m_cEntryCount)
+            .filter_map(|index| {
+                let mut entry: steamworks::sys::LeaderboardEntry_t = std::mem::zeroed();
+                let mut details = [0i32; MAX_DETAILS];
+                let read = steamworks::sys::SteamAPI_ISteamUserStats_GetDownloadedLeaderboardEntry(
+                    steamworks::sys::SteamAPI_SteamUserStats_v013(),
+                    raw.m_hSteamLeaderboardEntries,
+                    index,
+                    &mut entry,
+                    details.as_mut_ptr(),
+                    MAX_DETAILS as i32,
+                );
+                if !read {
+                    return None;
+                }
+                let steam_id =
+                    steamworks::SteamId::from_raw(entry.m_steamIDUser.m_steamid.m_unAll64Bits);
+                let details_count = (entry.m_cDetails.max(0) as usize).min(MAX_DETAILS);
+                let ugc = entry.m_hUGC;
+                Some(LeaderboardEntry {
+                    user: crate::api::localplayer::PlayerSteamId::from_steamid(steam_id),
+                    global_rank: entry.m_nGlobalRank,
+                    score: entry.m_nScore,
+                    details: details[..details_count].to_vec(),
+                    ugc_handle: (ugc != UGC_HANDLE_INVALID)
+                        .then(|| napi::bindgen_prelude::BigInt::from(ugc)),
+                })
+            })
+            .collect();
+        LeaderboardScoresDownloaded {
+            leaderboard: raw.m_hSteamLeaderboard,
+            entries,
+        }
+    }
+}
+
+/// Posted once `AttachLeaderboardUGC` went through or failed.
+/// steamworks-rs doesn't wrap this one.
+pub struct LeaderboardUgcSet {
+    pub leaderboard: u64,
+    pub result: Result<(), SteamError>,
+}
+
+unsafe impl steamworks::Callback for LeaderboardUgcSet {
+    // k_iSteamUserStatsCallbacks + 11
+    const ID: i32 = 1111;
+
+    unsafe fn from_raw(raw: *mut c_void) -> Self {
+        let raw = &*(raw as *mut steamworks::sys::LeaderboardUGCSet_t);
+        LeaderboardUgcSet {
+            leaderboard: raw.m_hSteamLeaderboard,
+            result: match raw.m_eResult {
+                steamworks::sys::EResult::k_EResultOK => Ok(()),
+                e => Err(e.into()),
+            },
+        }
+    }
+}
 
 #[napi]
 pub mod callback {
+    use std::path::Path;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
     use napi::{
         bindgen_prelude::Function,
         threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode},
     };
+    use serde_json::json;
+    use steamworks::{CallbackHandle, Client, SteamError};
+
+    use crate::abort::AbortSignal;
+    use crate::error::{ErrorCode, Result, SteamworksError};
 
     #[napi]
     pub struct Handle {
-        handle: Option<steamworks::CallbackHandle>,
+        subscription: Option<(SteamCallback, u64)>,
     }
 
     #[napi]
     impl Handle {
         #[napi]
-        pub fn disconnect(&mut self) {
-            if let Some(handle) = self.handle.take() {
-                drop(handle);
-            }
+        pub fn disconnect(&mut self) -> Result<()> {
+            crate::panic::guard(|| {
+                if let Some((kind, id)) = self.subscription.take() {
+                    crate::dispatch::unsubscribe(kind, id);
+                }
+                Ok(())
+            })
         }
     }
 
     #[napi]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     pub enum SteamCallback {
         PersonaStateChange,
         SteamServersConnected,
@@ -35,83 +215,544 @@ pub mod callback {
         GameLobbyJoinRequested,
         MicroTxnAuthorizationResponse,
         LobbyChatMessage,
+        UserStatsReceived,
+        UserStatsStored,
+        UserAchievementStored,
+        GameOverlayActivated,
+        DlcInstalled,
+        NewUrlLaunchParameters,
+        GameRichPresenceJoinRequested,
+        ScreenshotReady,
+        ValidateAuthTicketResponse,
+        TicketForWebApiResponse,
+        AuthSessionTicketResponse,
+        UserAchievementIconFetched,
+        GlobalStatsReceived,
+        LeaderboardScoresDownloaded,
+        LeaderboardUgcSet,
+    }
+
+    impl SteamCallback {
+        pub const ALL: [SteamCallback; 26] = [
+            SteamCallback::PersonaStateChange,
+            SteamCallback::SteamServersConnected,
+            SteamCallback::SteamServersDisconnected,
+            SteamCallback::SteamServerConnectFailure,
+            SteamCallback::LobbyDataUpdate,
+            SteamCallback::LobbyChatUpdate,
+            SteamCallback::P2PSessionRequest,
+            SteamCallback::P2PSessionConnectFail,
+            SteamCallback::GameLobbyJoinRequested,
+            SteamCallback::MicroTxnAuthorizationResponse,
+            SteamCallback::LobbyChatMessage,
+            SteamCallback::UserStatsReceived,
+            SteamCallback::UserStatsStored,
+            SteamCallback::UserAchievementStored,
+            SteamCallback::GameOverlayActivated,
+            SteamCallback::DlcInstalled,
+            SteamCallback::NewUrlLaunchParameters,
+            SteamCallback::GameRichPresenceJoinRequested,
+            SteamCallback::ScreenshotReady,
+            SteamCallback::ValidateAuthTicketResponse,
+            SteamCallback::TicketForWebApiResponse,
+            SteamCallback::AuthSessionTicketResponse,
+            SteamCallback::UserAchievementIconFetched,
+            SteamCallback::GlobalStatsReceived,
+            SteamCallback::LeaderboardScoresDownloaded,
+            SteamCallback::LeaderboardUgcSet,
+        ];
+
+        /// The name recordings refer to the callback by.
+        pub fn name(&self) -> &'static str {
+            match self {
+                SteamCallback::PersonaStateChange => "PersonaStateChange",
+                SteamCallback::SteamServersConnected => "SteamServersConnected",
+                SteamCallback::SteamServersDisconnected => "SteamServersDisconnected",
+                SteamCallback::SteamServerConnectFailure => "SteamServerConnectFailure",
+                SteamCallback::LobbyDataUpdate => "LobbyDataUpdate",
+                SteamCallback::LobbyChatUpdate => "LobbyChatUpdate",
+                SteamCallback::P2PSessionRequest => "P2PSessionRequest",
+                SteamCallback::P2PSessionConnectFail => "P2PSessionConnectFail",
+                SteamCallback::GameLobbyJoinRequested => "GameLobbyJoinRequested",
+                SteamCallback::MicroTxnAuthorizationResponse => "MicroTxnAuthorizationResponse",
+                SteamCallback::LobbyChatMessage => "LobbyChatMessage",
+                SteamCallback::UserStatsReceived => "UserStatsReceived",
+                SteamCallback::UserStatsStored => "UserStatsStored",
+                SteamCallback::UserAchievementStored => "UserAchievementStored",
+                SteamCallback::GameOverlayActivated => "GameOverlayActivated",
+                SteamCallback::DlcInstalled => "DlcInstalled",
+                SteamCallback::NewUrlLaunchParameters => "NewUrlLaunchParameters",
+                SteamCallback::GameRichPresenceJoinRequested => "GameRichPresenceJoinRequested",
+                SteamCallback::ScreenshotReady => "ScreenshotReady",
+                SteamCallback::ValidateAuthTicketResponse => "ValidateAuthTicketResponse",
+                SteamCallback::TicketForWebApiResponse => "TicketForWebApiResponse",
+                SteamCallback::AuthSessionTicketResponse => "AuthSessionTicketResponse",
+                SteamCallback::UserAchievementIconFetched => "UserAchievementIconFetched",
+                SteamCallback::GlobalStatsReceived => "GlobalStatsReceived",
+                SteamCallback::LeaderboardScoresDownloaded => "LeaderboardScoresDownloaded",
+                SteamCallback::LeaderboardUgcSet => "LeaderboardUgcSet",
+            }
+        }
+
+        pub fn from_name(name: &str) -> Option<Self> {
+            Self::ALL.into_iter().find(|kind| kind.name() == name)
+        }
     }
 
     #[napi(ts_generic_types = "C extends keyof import('./callbacks').CallbackReturns")]
     pub fn register(
         #[napi(ts_arg_type = "C")] steam_callback: SteamCallback,
         #[napi(ts_arg_type = "(value: import('./callbacks').CallbackReturns[C]) => void")] handler: Function<'static>,
-    ) -> Handle {
-        let threadsafe_handler: ThreadsafeFunction<
-            serde_json::Value,
-            napi::Unknown<'_>,
-            Vec<serde_json::Value>,
-            napi::Status,
-            false,
-        > = handler
-            .build_threadsafe_function::<serde_json::Value>()
-            .callee_handled::<false>()
-            .max_queue_size::<0>()
-            .build_callback(|ctx| Ok(vec![ctx.value]))
-            .unwrap();
-
-        let handle = match steam_callback {
+    ) -> Result<Handle> {
+        crate::panic::guard(|| {
+            let threadsafe_handler: Handler = handler
+                .build_threadsafe_function::<serde_json::Value>()
+                .callee_handled::<false>()
+                .max_queue_size::<0>()
+                .build_callback(|ctx| Ok(vec![ctx.value]))
+                .map_err(SteamworksError::from)?;
+
+            let id = crate::dispatch::subscribe(
+                steam_callback,
+                Arc::new(move |value: &serde_json::Value| {
+                    threadsafe_handler.call(value.clone(), ThreadsafeFunctionCallMode::Blocking);
+                }),
+            )?;
+
+            Ok(Handle {
+                subscription: Some((steam_callback, id)),
+            })
+        })
+    }
+
+    /// What a stream does with a callback when it's full.
+    #[napi]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum StreamPolicy {
+        /// Make room by dropping the oldest queued callback
+        DropOldest,
+        /// Hold up the callback pump until there's room, at most `blockTimeoutMs`, then drop the oldest.
+        /// Needs `autoRunCallbacks`. Callbacks that run anywhere else, e.g. after the pump was stopped,
+        /// drop the oldest right away, the JS thread couldn't drain the stream while it waits.
+        Block,
+        /// Replace the queued callback with the same `coalesceKey` in place, or drop the oldest if there's none
+        Coalesce,
+    }
+
+    #[napi(object)]
+    pub struct StreamOptions {
+        /// How many callbacks are queued before `policy` kicks in. Default value is 64.
+        pub capacity: Option<u32>,
+        /// Default value is `DropOldest`
+        pub policy: Option<StreamPolicy>,
+        /// The payload field `Coalesce` compares, e.g. "steam_id". The whole payload is compared without one.
+        pub coalesce_key: Option<String>,
+        /// How long `Block` holds up the pump. Default value is 1000 milliseconds.
+        pub block_timeout_ms: Option<u32>,
+    }
+
+    #[napi(object)]
+    pub struct StreamResult {
+        pub done: bool,
+        pub value: Option<serde_json::Value>,
+    }
+
+    /// An async iterator over the callbacks of one kind, see `stream`.
+    #[napi]
+    pub struct Stream {
+        stream: Arc<crate::stream::CallbackStream>,
+        subscription: Mutex<Option<crate::dispatch::Subscription>>,
+    }
+
+    #[napi]
+    impl Stream {
+        /// Resolves with the next callback, waiting for one if the queue is empty.
+        /// It's done once the stream is closed and drained, or the client shut down.
+        #[napi(ts_return_type = "Promise<IteratorResult<any, undefined>>")]
+        pub async fn next(&self, signal: Option<AbortSignal>) -> Result<StreamResult> {
+            let stream = self.stream.clone();
+            crate::panic::guard_async(crate::abort::abortable(signal, async move {
+                let value = stream.next().await;
+                Ok(StreamResult {
+                    done: value.is_none(),
+                    value,
+                })
+            }))
+            .await
+        }
+
+        /// Closes the stream, called when a `for await` loop is left early.
+        #[napi(js_name = "return")]
+        pub fn finish(&self) -> Result<StreamResult> {
+            crate::panic::guard(|| {
+                self.close()?;
+                Ok(StreamResult {
+                    done: true,
+                    value: None,
+                })
+            })
+        }
+
+        /// Stops listening. Callbacks already queued can still be read.
+        #[napi]
+        pub fn close(&self) -> Result<()> {
+            crate::panic::guard(|| {
+                self.subscription.lock().unwrap().take();
+                self.stream.close();
+                Ok(())
+            })
+        }
+
+        /// How many callbacks were dropped because the stream was full.
+        #[napi(getter)]
+        pub fn dropped(&self) -> Result<u32> {
+            crate::panic::guard(|| Ok(self.stream.dropped()))
+        }
+    }
+
+    /// Streams the callbacks of `steamCallback` through a bounded queue, for `for await` loops.
+    ///
+    /// {@link StreamPolicy}
+    #[napi(
+        ts_generic_types = "C extends keyof import('./callbacks').CallbackReturns",
+        ts_return_type = "Stream<C>"
+    )]
+    pub fn stream(
+        #[napi(ts_arg_type = "C")] steam_callback: SteamCallback,
+        options: Option<StreamOptions>,
+    ) -> Result<Stream> {
+        crate::panic::guard(|| {
+            let options = options.unwrap_or(StreamOptions {
+                capacity: None,
+                policy: None,
+                coalesce_key: None,
+                block_timeout_ms: None,
+            });
+            let policy = options.policy.unwrap_or(StreamPolicy::DropOldest);
+            if policy == StreamPolicy::Block && !crate::pump::is_running() {
+                return Err(napi::Error::new(
+                    ErrorCode::InvalidState,
+                    "Block needs the callback pump, init with autoRunCallbacks.",
+                ));
+            }
+            if options.capacity == Some(0) {
+                return Err(
+                    SteamworksError::invalid_parameter("The capacity must be at least 1.").into(),
+                );
+            }
+
+            let stream = Arc::new(crate::stream::CallbackStream::new(
+                options.capacity.unwrap_or(64) as usize,
+                policy,
+                options.coalesce_key,
+                Duration::from_millis(u64::from(options.block_timeout_ms.unwrap_or(1000))),
+            ));
+            let listener = stream.clone();
+            let subscription = crate::dispatch::Subscription::new(
+                steam_callback,
+                Arc::new(move |value: &serde_json::Value| listener.push(value)),
+            )?;
+            Ok(Stream {
+                stream,
+                subscription: Mutex::new(Some(subscription)),
+            })
+        })
+    }
+
+    /// Resolves with the next payload of `steamCallback` that `predicate` accepts, then stops listening.
+    ///
+    /// @param predicate - Called with each payload until it returns true. Without one, the first payload is taken.
+    /// @param timeoutMs - Rejects with TIMEOUT after this many milliseconds. Without one, it waits until shutdown.
+    #[napi(
+        ts_generic_types = "C extends keyof import('./callbacks').CallbackReturns",
+        ts_return_type = "Promise<import('./callbacks').CallbackReturns[C]>"
+    )]
+    pub async fn once(
+        #[napi(ts_arg_type = "C")] steam_callback: SteamCallback,
+        #[napi(
+            ts_arg_type = "((value: import('./callbacks').CallbackReturns[C]) => boolean) | undefined | null"
+        )]
+        predicate: Option<Predicate>,
+        timeout_ms: Option<u32>,
+        signal: Option<AbortSignal>,
+    ) -> Result<serde_json::Value> {
+        crate::panic::guard_async(crate::abort::abortable(signal, async move {
+            let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+            let _subscription = crate::dispatch::Subscription::new(
+                steam_callback,
+                Arc::new(move |value: &serde_json::Value| {
+                    let _ = tx.send(value.clone());
+                }),
+            )?;
+
+            let next = async {
+                while let Some(value) = rx.recv().await {
+                    let accepted = match &predicate {
+                        Some(predicate) => predicate
+                            .call_async(value.clone())
+                            .await
+                            .map_err(SteamworksError::from)?,
+                        None => true,
+                    };
+                    if accepted {
+                        return Ok(value);
+                    }
+                }
+                Err(SteamworksError::CallbackDropped)
+            };
+
+            let value = match timeout_ms {
+                Some(timeout_ms) => crate::resources::until_shutdown(tokio::time::timeout(
+                    Duration::from_millis(u64::from(timeout_ms)),
+                    next,
+                ))
+                .await?
+                .map_err(|_| {
+                    SteamworksError::timeout(format!(
+                        "Steam didn't answer {:?} in time.",
+                        steam_callback
+                    ))
+                })?,
+                None => crate::resources::until_shutdown(next).await?,
+            };
+            Ok(value?)
+        }))
+        .await
+    }
+
+    /// Writes every callback Steam dispatches from now on to `path`, one JSON object per line with
+    /// its kind, payload and time. Replaces a recording that's already running.
+    #[napi]
+    pub fn start_recording(path: String) -> Result<()> {
+        crate::panic::guard(|| Ok(crate::dispatch::start_recording(Path::new(&path))?))
+    }
+
+    /// @returns the number of callbacks recorded, or null if nothing was recording
+    #[napi]
+    pub fn stop_recording() -> Result<Option<u32>> {
+        crate::panic::guard(|| Ok(crate::dispatch::stop_recording()?))
+    }
+
+    #[napi]
+    pub fn is_recording() -> Result<bool> {
+        crate::panic::guard(|| Ok(crate::dispatch::is_recording()))
+    }
+
+    /// Feeds a recording made with `startRecording` to the registered handlers, without Steam
+    /// being involved. Works with the mock backend.
+    ///
+    /// @param realtime - Keep the recorded delays between callbacks. Default value is false, they're replayed back to back.
+    /// @returns the number of callbacks replayed
+    #[napi]
+    pub async fn replay(
+        path: String,
+        realtime: Option<bool>,
+        signal: Option<AbortSignal>,
+    ) -> Result<u32> {
+        crate::panic::guard_async(crate::abort::abortable(signal, async move {
+            crate::client::get_backend()?;
+            let records = crate::dispatch::read_recording(Path::new(&path))?;
+            Ok(crate::dispatch::replay(records, realtime.unwrap_or(false)).await?)
+        }))
+        .await
+    }
+
+    /// Registers `kind` with Steam, its payloads go to `dispatch::emit`.
+    pub(crate) fn register_steam(kind: SteamCallback, client: &Client) -> CallbackHandle {
+        match kind {
             SteamCallback::PersonaStateChange => {
-                register_callback::<steamworks::PersonaStateChange>(threadsafe_handler)
+                register_callback::<steamworks::PersonaStateChange>(client, kind)
             }
             SteamCallback::SteamServersConnected => {
-                register_callback::<steamworks::SteamServersConnected>(threadsafe_handler)
+                register_callback::<steamworks::SteamServersConnected>(client, kind)
             }
             SteamCallback::SteamServersDisconnected => {
-                register_callback::<steamworks::SteamServersDisconnected>(threadsafe_handler)
+                register_callback::<steamworks::SteamServersDisconnected>(client, kind)
             }
             SteamCallback::SteamServerConnectFailure => {
-                register_callback::<steamworks::SteamServerConnectFailure>(threadsafe_handler)
+                register_callback::<steamworks::SteamServerConnectFailure>(client, kind)
             }
             SteamCallback::LobbyDataUpdate => {
-                register_callback::<steamworks::LobbyDataUpdate>(threadsafe_handler)
+                register_callback::<steamworks::LobbyDataUpdate>(client, kind)
             }
             SteamCallback::LobbyChatUpdate => {
-                register_callback::<steamworks::LobbyChatUpdate>(threadsafe_handler)
+                register_callback::<steamworks::LobbyChatUpdate>(client, kind)
             }
             SteamCallback::P2PSessionRequest => {
-                register_callback::<steamworks::P2PSessionRequest>(threadsafe_handler)
+                register_callback::<steamworks::P2PSessionRequest>(client, kind)
             }
             SteamCallback::P2PSessionConnectFail => {
-                register_callback::<steamworks::P2PSessionConnectFail>(threadsafe_handler)
+                register_callback::<steamworks::P2PSessionConnectFail>(client, kind)
             }
             SteamCallback::GameLobbyJoinRequested => {
-                register_callback::<steamworks::GameLobbyJoinRequested>(threadsafe_handler)
+                register_callback::<steamworks::GameLobbyJoinRequested>(client, kind)
             }
             SteamCallback::MicroTxnAuthorizationResponse => {
-                register_callback::<steamworks::MicroTxnAuthorizationResponse>(threadsafe_handler)
+                register_callback::<steamworks::MicroTxnAuthorizationResponse>(client, kind)
             }
             SteamCallback::LobbyChatMessage => {
-                register_callback::<steamworks::LobbyChatMsg>(threadsafe_handler)
+                register_callback::<steamworks::LobbyChatMsg>(client, kind)
+            }
+            SteamCallback::UserStatsReceived => {
+                register_mapped(client, kind, |value: steamworks::UserStatsReceived| {
+                    with_result(
+                        json!({
+                            "steam_id": value.steam_id.raw(),
+                            "game_id": value.game_id.raw(),
+                        }),
+                        value.result,
+                    )
+                })
+            }
+            SteamCallback::UserStatsStored => {
+                register_mapped(client, kind, |value: steamworks::UserStatsStored| {
+                    with_result(json!({ "game_id": value.game_id.raw() }), value.result)
+                })
+            }
+            SteamCallback::UserAchievementStored => {
+                register_mapped(client, kind, |value: steamworks::UserAchievementStored| {
+                    json!({
+                        "game_id": value.game_id.raw(),
+                        "achievement_name": value.achievement_name,
+                        "current_progress": value.current_progress,
+                        "max_progress": value.max_progress,
+                    })
+                })
+            }
+            SteamCallback::GameOverlayActivated => register_mapped(
+                client,
+                kind,
+                |value: steamworks::GameOverlayActivated| json!({ "active": value.active }),
+            ),
+            SteamCallback::DlcInstalled => register_mapped(
+                client,
+                kind,
+                |value: steamworks::DlcInstalled| json!({ "app_id": value.app_id.0 }),
+            ),
+            SteamCallback::NewUrlLaunchParameters => register_mapped(
+                client,
+                kind,
+                |value: super::NewUrlLaunchParameters| json!({ "command_line": value.command_line }),
+            ),
+            SteamCallback::GameRichPresenceJoinRequested => register_mapped(
+                client,
+                kind,
+                |value: steamworks::GameRichPresenceJoinRequested| {
+                    json!({
+                        "friend_steam_id": value.friend_steam_id.raw(),
+                        "connect": value.connect,
+                    })
+                },
+            ),
+            SteamCallback::ScreenshotReady => {
+                register_mapped(client, kind, |value: super::ScreenshotReady| {
+                    with_result(json!({ "local_handle": value.local_handle }), value.result)
+                })
+            }
+            SteamCallback::ValidateAuthTicketResponse => register_mapped(
+                client,
+                kind,
+                |value: steamworks::ValidateAuthTicketResponse| {
+                    let mut payload = json!({
+                        "steam_id": value.steam_id.raw(),
+                        "owner_steam_id": value.owner_steam_id.raw(),
+                        "success": value.response.is_ok(),
+                    });
+                    if let Err(e) = value.response {
+                        payload["error"] = json!(e.to_string());
+                    }
+                    payload
+                },
+            ),
+            SteamCallback::TicketForWebApiResponse => register_mapped(
+                client,
+                kind,
+                |value: steamworks::TicketForWebApiResponse| {
+                    let mut ticket = value.ticket;
+                    ticket.truncate(value.ticket_len as usize);
+                    with_result(json!({ "ticket": ticket }), value.result)
+                },
+            ),
+            SteamCallback::AuthSessionTicketResponse => register_mapped(
+                client,
+                kind,
+                |value: steamworks::AuthSessionTicketResponse| with_result(json!({}), value.result),
+            ),
+            SteamCallback::UserAchievementIconFetched => {
+                register_mapped(client, kind, |value: super::UserAchievementIconFetched| {
+                    json!({
+                        "achievement_name": value.achievement_name,
+                        "achieved": value.achieved,
+                        "icon_handle": value.icon_handle,
+                    })
+                })
+            }
+            SteamCallback::GlobalStatsReceived => {
+                register_mapped(client, kind, |value: super::GlobalStatsReceived| {
+                    with_result(json!({ "game_id": value.game_id }), value.result)
+                })
+            }
+            SteamCallback::LeaderboardScoresDownloaded => {
+                register_mapped(client, kind, |value: super::LeaderboardScoresDownloaded| {
+                    json!({
+                        "leaderboard": value.leaderboard,
+                        "entry_count": value.entries.len(),
+                    })
+                })
+            }
+            SteamCallback::LeaderboardUgcSet => {
+                register_mapped(client, kind, |value: super::LeaderboardUgcSet| {
+                    with_result(json!({ "leaderboard": value.leaderboard }), value.result)
+                })
             }
-        };
-
-        Handle {
-            handle: Some(handle),
         }
     }
 
-    fn register_callback<C>(
-        threadsafe_handler: ThreadsafeFunction<
-            serde_json::Value,
-            napi::Unknown<'_>,
-            Vec<serde_json::Value>,
-            napi::Status,
-            false,
-        >,
-    ) -> steamworks::CallbackHandle
+    type Handler = ThreadsafeFunction<
+        serde_json::Value,
+        napi::Unknown<'static>,
+        Vec<serde_json::Value>,
+        napi::Status,
+        false,
+    >;
+
+    type Predicate =
+        ThreadsafeFunction<serde_json::Value, bool, serde_json::Value, napi::Status, false>;
+
+    fn register_callback<C>(client: &Client, kind: SteamCallback) -> CallbackHandle
     where
-        C: steamworks::Callback + serde::Serialize,
+        C: steamworks::Callback + serde::Serialize + 'static,
+    {
+        register_mapped(client, kind, |value: C| {
+            serde_json::to_value(&value).unwrap()
+        })
+    }
+
+    /// Registers a callback whose payload is built by hand, for callbacks that don't serialize
+    /// to something usable.
+    fn register_mapped<C>(
+        client: &Client,
+        kind: SteamCallback,
+        map: impl Fn(C) -> serde_json::Value + Send + 'static,
+    ) -> CallbackHandle
+    where
+        C: steamworks::Callback + 'static,
     {
-        let client = crate::client::get_client().unwrap();
         client.register_callback(move |value: C| {
-            let value = serde_json::to_value(&value).unwrap();
-            threadsafe_handler.call(value, ThreadsafeFunctionCallMode::Blocking);
+            crate::dispatch::emit_typed(kind, &value);
+            crate::dispatch::emit(kind, map(value));
         })
     }
+
+    /// Adds `success`, and `error` when it failed, to a payload.
+    fn with_result(
+        mut payload: serde_json::Value,
+        result: std::result::Result<(), SteamError>,
+    ) -> serde_json::Value {
+        payload["success"] = json!(result.is_ok());
+        if let Err(e) = result {
+            payload["error"] = json!(e.to_string());
+        }
+        payload
+    }
 }
@@ -2,9 +2,8 @@ use napi_derive::napi;
 
 #[napi]
 pub mod cloud {
-    use napi::bindgen_prelude::{BigInt, Error};
-    use std::io::Read;
-    use std::io::Write;
+    use crate::error::{Result, SteamworksError};
+    use napi::bindgen_prelude::BigInt;
 
     #[napi(object)]
     pub struct FileInfo {
@@ -13,75 +12,87 @@ pub mod cloud {
     }
 
     #[napi]
-    pub fn is_enabled_for_account() -> bool {
-        let client = crate::client::get_client().unwrap();
-        client.remote_storage().is_cloud_enabled_for_account()
+    pub fn is_enabled_for_account() -> Result<bool> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            Ok(backend.is_cloud_enabled_for_account())
+        })
     }
 
     #[napi]
-    pub fn is_enabled_for_app() -> bool {
-        let client = crate::client::get_client().unwrap();
-        client.remote_storage().is_cloud_enabled_for_app()
+    pub fn is_enabled_for_app() -> Result<bool> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            Ok(backend.is_cloud_enabled_for_app())
+        })
     }
 
     #[napi]
-    pub fn set_enabled_for_app(enabled: bool) {
-        let client = crate::client::get_client().unwrap();
-        client.remote_storage().set_cloud_enabled_for_app(enabled)
+    pub fn set_enabled_for_app(enabled: bool) -> Result<()> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            backend.set_cloud_enabled_for_app(enabled);
+            crate::ready::cloud_toggled(&*backend);
+            Ok(())
+        })
     }
 
     #[napi]
-    pub fn read_file(name: String) -> Result<String, Error> {
-        let client = crate::client::get_client().unwrap();
-        let mut buf: String = String::new();
-        let size = client
-            .remote_storage()
-            .file(&name)
-            .read()
-            .read_to_string(&mut buf);
+    pub fn read_file(name: String) -> Result<String> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            let content = backend.read_file(&name).and_then(|buf| {
+                String::from_utf8(buf)
+                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+            });
 
-        match size {
-            Ok(_) => Ok(buf),
-            Err(e) => Err(Error::from_reason(format!("Failed to read file: {}", e))),
-        }
+            match content {
+                Ok(buf) => Ok(buf),
+                Err(e) => Err(SteamworksError::Io(std::io::Error::new(
+                    e.kind(),
+                    format!("Failed to read file: {}", e),
+                ))
+                .into()),
+            }
+        })
     }
 
     #[napi]
-    pub fn write_file(name: String, content: String) -> bool {
-        let client = crate::client::get_client().unwrap();
-        let file = client.remote_storage().file(&name);
-
-        let buf = content.as_bytes();
-        file.write().write_all(buf).is_ok()
+    pub fn write_file(name: String, content: String) -> Result<bool> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            Ok(backend.write_file(&name, content.as_bytes()).is_ok())
+        })
     }
 
     #[napi]
-    pub fn delete_file(name: String) -> bool {
-        let client = crate::client::get_client().unwrap();
-        let file = client.remote_storage().file(&name);
-
-        file.delete()
+    pub fn delete_file(name: String) -> Result<bool> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            Ok(backend.delete_file(&name))
+        })
     }
 
     #[napi]
-    pub fn file_exists(name: String) -> bool {
-        let client = crate::client::get_client().unwrap();
-        let file = client.remote_storage().file(&name);
-
-        file.exists()
+    pub fn file_exists(name: String) -> Result<bool> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            Ok(backend.file_exists(&name))
+        })
     }
 
     #[napi]
-    pub fn list_files() -> Vec<FileInfo> {
-        let client = crate::client::get_client().unwrap();
-        client
-            .remote_storage()
-            .files()
-            .into_iter()
-            .map(|identity| FileInfo {
-                name: identity.name,
-                size: BigInt::from(identity.size),
-            })
-            .collect()
+    pub fn list_files() -> Result<Vec<FileInfo>> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            Ok(backend
+                .list_files()
+                .into_iter()
+                .map(|(name, size)| FileInfo {
+                    name,
+                    size: BigInt::from(size),
+                })
+                .collect())
+        })
     }
 }
@@ -7,6 +7,8 @@ pub mod input {
     use napi::bindgen_prelude::{BigInt, Function};
     use steamworks::FloatingGamepadTextInputMode;
 
+    use crate::error::{Result, SteamworksError};
+
     #[napi(string_enum)]
     pub enum InputType {
         Unknown,
@@ -48,24 +50,51 @@ pub mod input {
         }
     }
 
+    impl From<InputType> for steamworks::InputType {
+        fn from(input_type: InputType) -> steamworks::InputType {
+            match input_type {
+                InputType::Unknown => steamworks::InputType::Unknown,
+                InputType::SteamController => steamworks::InputType::SteamController,
+                InputType::XBox360Controller => steamworks::InputType::XBox360Controller,
+                InputType::XBoxOneController => steamworks::InputType::XBoxOneController,
+                InputType::GenericGamepad => steamworks::InputType::GenericGamepad,
+                InputType::PS4Controller => steamworks::InputType::PS4Controller,
+                InputType::AppleMFiController => steamworks::InputType::AppleMFiController,
+                InputType::AndroidController => steamworks::InputType::AndroidController,
+                InputType::SwitchJoyConPair => steamworks::InputType::SwitchJoyConPair,
+                InputType::SwitchJoyConSingle => steamworks::InputType::SwitchJoyConSingle,
+                InputType::SwitchProController => steamworks::InputType::SwitchProController,
+                InputType::MobileTouch => steamworks::InputType::MobileTouch,
+                InputType::PS3Controller => steamworks::InputType::PS3Controller,
+                InputType::PS5Controller => steamworks::InputType::PS5Controller,
+                InputType::SteamDeckController => steamworks::InputType::SteamDeckController,
+            }
+        }
+    }
+
     #[napi]
-    pub fn get_action_handle(action_name: String) -> BigInt {
-        let client = crate::client::get_client().unwrap();
-        BigInt::from(client.input().get_action_set_handle(&action_name))
+    pub fn get_action_handle(action_name: String) -> Result<BigInt> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            Ok(BigInt::from(backend.action_set_handle(&action_name)))
+        })
     }
 
     #[napi]
-    pub fn get_file_path_for_action(action_handle: InputActionOrigins) -> String {
-        let client = crate::client::get_client().unwrap();
-        client
-            .input()
-            .get_glyph_for_action_origin(action_handle.into())
+    pub fn get_file_path_for_action(action_handle: InputActionOrigins) -> Result<String> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            Ok(backend.action_origin_glyph(action_handle.into()))
+        })
     }
 
     #[napi]
-    pub fn run_frame() {
-        let client = crate::client::get_client().unwrap();
-        client.input().run_frame();
+    pub fn run_frame() -> Result<()> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            backend.input_run_frame();
+            Ok(())
+        })
     }
 
     #[napi]
@@ -76,57 +105,60 @@ pub mod input {
     #[napi]
     impl Controller {
         #[napi]
-        pub fn activate_action_set(&self, action_set_handle: BigInt) -> bool {
-            let client = crate::client::get_client().unwrap();
-            client
-                .input()
-                .activate_action_set_handle(self.handle.get_u64().1, action_set_handle.get_u64().1);
-            self.get_current_active_action_set().get_u64().1 == action_set_handle.get_u64().1
+        pub fn activate_action_set(&self, action_set_handle: BigInt) -> Result<bool> {
+            crate::panic::guard(|| {
+                let backend = crate::client::get_backend()?;
+                backend.activate_action_set(self.handle.get_u64().1, action_set_handle.get_u64().1);
+                Ok(self.get_current_active_action_set()?.get_u64().1
+                    == action_set_handle.get_u64().1)
+            })
         }
 
         #[napi]
-        pub fn is_digital_action_pressed(&self, action_handle: BigInt) -> bool {
-            let client = crate::client::get_client().unwrap();
-            client
-                .input()
-                .get_digital_action_data(self.handle.get_u64().1, action_handle.get_u64().1)
-                .bState
+        pub fn is_digital_action_pressed(&self, action_handle: BigInt) -> Result<bool> {
+            crate::panic::guard(|| {
+                let backend = crate::client::get_backend()?;
+                Ok(backend
+                    .digital_action_pressed(self.handle.get_u64().1, action_handle.get_u64().1))
+            })
         }
 
         #[napi]
-        pub fn get_analog_action_vector(&self, action_handle: BigInt) -> AnalogActionVector {
-            let client = crate::client::get_client().unwrap();
-            let data = client
-                .input()
-                .get_analog_action_data(self.handle.get_u64().1, action_handle.get_u64().1);
-            AnalogActionVector {
-                x: data.x as f64,
-                y: data.y as f64,
-            }
+        pub fn get_analog_action_vector(
+            &self,
+            action_handle: BigInt,
+        ) -> Result<AnalogActionVector> {
+            crate::panic::guard(|| {
+                let backend = crate::client::get_backend()?;
+                let (x, y) = backend
+                    .analog_action_vector(self.handle.get_u64().1, action_handle.get_u64().1);
+                Ok(AnalogActionVector {
+                    x: x as f64,
+                    y: y as f64,
+                })
+            })
         }
 
         #[napi]
-        pub fn get_type(&self) -> InputType {
-            let client = crate::client::get_client().unwrap();
-            client
-                .input()
-                .get_input_type_for_handle(self.handle.get_u64().1)
-                .into()
+        pub fn get_type(&self) -> Result<InputType> {
+            crate::panic::guard(|| {
+                let backend = crate::client::get_backend()?;
+                Ok(backend.input_type(self.handle.get_u64().1).into())
+            })
         }
 
         #[napi]
-        pub fn get_handle(&self) -> BigInt {
-            self.handle.clone()
+        pub fn get_handle(&self) -> Result<BigInt> {
+            crate::panic::guard(|| Ok(self.handle.clone()))
         }
 
         /// Gets controller's motion sensors
         #[napi]
-        pub fn get_motion_data(&self) -> MotionData {
-            let client = crate::client::get_client().unwrap();
-            return client
-                .input()
-                .get_motion_data(self.handle.get_u64().1)
-                .into();
+        pub fn get_motion_data(&self) -> Result<MotionData> {
+            crate::panic::guard(|| {
+                let backend = crate::client::get_backend()?;
+                Ok(backend.motion_data(self.handle.get_u64().1))
+            })
         }
 
         /// Triggers a vibration event
@@ -137,16 +169,16 @@ pub mod input {
             &self,
             left_speed_micro_second: u16,
             right_speed_micro_second: u16,
-        ) {
-            unsafe {
-                let x = steamworks::sys::SteamAPI_SteamInput_v006();
-                steamworks::sys::SteamAPI_ISteamInput_TriggerVibration(
-                    x,
+        ) -> Result<()> {
+            crate::panic::guard(|| {
+                let backend = crate::client::get_backend()?;
+                backend.trigger_vibration(
                     self.handle.get_u64().1,
                     left_speed_micro_second,
                     right_speed_micro_second,
                 );
-            }
+                Ok(())
+            })
         }
 
         #[napi]
@@ -154,14 +186,16 @@ pub mod input {
             &self,
             action_set_handle: BigInt,
             analog_action_handle: BigInt,
-        ) -> Vec<InputActionOrigins> {
-            let client = crate::client::get_client().unwrap();
-            let out = client.input().get_analog_action_origins(
-                self.handle.get_u64().1,
-                action_set_handle.get_u64().1,
-                analog_action_handle.get_u64().1,
-            );
-            out.iter().map(|i| InputActionOrigins::from(*i)).collect()
+        ) -> Result<Vec<InputActionOrigins>> {
+            crate::panic::guard(|| {
+                let backend = crate::client::get_backend()?;
+                let out = backend.analog_action_origins(
+                    self.handle.get_u64().1,
+                    action_set_handle.get_u64().1,
+                    analog_action_handle.get_u64().1,
+                );
+                Ok(out.iter().map(|i| InputActionOrigins::from(*i)).collect())
+            })
         }
 
         #[napi]
@@ -169,26 +203,24 @@ pub mod input {
             &self,
             action_set_handle: BigInt,
             digital_action_handle: BigInt,
-        ) -> Vec<InputActionOrigins> {
-            let client = crate::client::get_client().unwrap();
-            let out = client.input().get_digital_action_origins(
-                self.handle.get_u64().1,
-                action_set_handle.get_u64().1,
-                digital_action_handle.get_u64().1,
-            );
-            out.iter().map(|i| InputActionOrigins::from(*i)).collect()
+        ) -> Result<Vec<InputActionOrigins>> {
+            crate::panic::guard(|| {
+                let backend = crate::client::get_backend()?;
+                let out = backend.digital_action_origins(
+                    self.handle.get_u64().1,
+                    action_set_handle.get_u64().1,
+                    digital_action_handle.get_u64().1,
+                );
+                Ok(out.iter().map(|i| InputActionOrigins::from(*i)).collect())
+            })
         }
 
         #[napi]
-        pub fn get_current_active_action_set(&self) -> BigInt {
-            unsafe {
-                let x = steamworks::sys::SteamAPI_SteamInput_v006();
-                steamworks::sys::SteamAPI_ISteamInput_GetCurrentActionSet(
-                    x,
-                    self.handle.get_u64().1,
-                )
-                .into()
-            }
+        pub fn get_current_active_action_set(&self) -> Result<BigInt> {
+            crate::panic::guard(|| {
+                let backend = crate::client::get_backend()?;
+                Ok(backend.current_action_set(self.handle.get_u64().1).into())
+            })
         }
     }
 
@@ -702,41 +734,51 @@ pub mod input {
     }
 
     #[napi]
-    pub fn init() {
-        let client = crate::client::get_client().unwrap();
-        client.input().init(false);
+    pub fn init() -> Result<()> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            backend.input_init();
+            Ok(())
+        })
     }
 
     #[napi]
-    pub fn get_controllers() -> Vec<Controller> {
-        let client = crate::client::get_client().unwrap();
-        client
-            .input()
-            .get_connected_controllers()
-            .into_iter()
-            .filter(|identity| identity != &0)
-            .map(|identity| Controller {
-                handle: BigInt::from(identity),
-            })
-            .collect()
+    pub fn get_controllers() -> Result<Vec<Controller>> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            Ok(backend
+                .connected_controllers()
+                .into_iter()
+                .filter(|identity| identity != &0)
+                .map(|identity| Controller {
+                    handle: BigInt::from(identity),
+                })
+                .collect())
+        })
     }
 
     #[napi]
-    pub fn get_action_set(action_set_name: String) -> BigInt {
-        let client = crate::client::get_client().unwrap();
-        BigInt::from(client.input().get_action_set_handle(&action_set_name))
+    pub fn get_action_set(action_set_name: String) -> Result<BigInt> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            Ok(BigInt::from(backend.action_set_handle(&action_set_name)))
+        })
     }
 
     #[napi]
-    pub fn get_digital_action(action_name: String) -> BigInt {
-        let client = crate::client::get_client().unwrap();
-        BigInt::from(client.input().get_digital_action_handle(&action_name))
+    pub fn get_digital_action(action_name: String) -> Result<BigInt> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            Ok(BigInt::from(backend.digital_action_handle(&action_name)))
+        })
     }
 
     #[napi]
-    pub fn get_analog_action(action_name: String) -> BigInt {
-        let client = crate::client::get_client().unwrap();
-        BigInt::from(client.input().get_analog_action_handle(&action_name))
+    pub fn get_analog_action(action_name: String) -> Result<BigInt> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            Ok(BigInt::from(backend.analog_action_handle(&action_name)))
+        })
     }
 
     #[napi]
@@ -765,22 +807,28 @@ pub mod input {
     }
 
     #[napi]
-    pub fn shutdown() {
-        let client = crate::client::get_client().unwrap();
-        client.input().shutdown()
+    pub fn shutdown() -> Result<()> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            backend.input_shutdown();
+            Ok(())
+        })
     }
 
     #[napi]
-    pub fn set_input_action_manifest_file_path(path: String) -> napi::Result<bool> {
-        let path = CString::new(path).map_err(|a| napi::Error::from_reason(a.to_string()))?;
-        unsafe {
-            let x = steamworks::sys::SteamAPI_SteamInput_v006();
-            return Ok(
-                steamworks::sys::SteamAPI_ISteamInput_SetInputActionManifestFilePath(
-                    x,
-                    path.as_ptr(),
-                ),
-            );
-        }
+    pub fn set_input_action_manifest_file_path(path: String) -> Result<bool> {
+        crate::panic::guard(|| {
+            let path = CString::new(path)
+                .map_err(|a| SteamworksError::invalid_parameter(a.to_string()))?;
+            unsafe {
+                let x = steamworks::sys::SteamAPI_SteamInput_v006();
+                return Ok(
+                    steamworks::sys::SteamAPI_ISteamInput_SetInputActionManifestFilePath(
+                        x,
+                        path.as_ptr(),
+                    ),
+                );
+            }
+        })
     }
 }
@@ -0,0 +1,374 @@
+use napi_derive::napi;
+
+#[napi]
+pub mod leaderboard {
+    use std::time::Duration;
+
+    use napi::bindgen_prelude::BigInt;
+    use steamworks::SteamId;
+    use tokio::sync::oneshot;
+
+    use crate::abort::AbortSignal;
+    use crate::api::localplayer::PlayerSteamId;
+    use crate::error::{ErrorCode, Result, SteamworksError};
+
+    /// Steam keeps at most this many detail ints per entry.
+    pub const MAX_DETAILS: usize = 64;
+
+    #[napi]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum SortMethod {
+        /// The lowest score ranks first, e.g. for the fastest time
+        Ascending,
+        /// The highest score ranks first
+        Descending,
+    }
+
+    #[napi]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum DisplayType {
+        Numeric,
+        TimeSeconds,
+        TimeMilliSeconds,
+    }
+
+    #[napi]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum UploadScoreMethod {
+        /// Keep the user's current score if it's better than the uploaded one
+        KeepBest,
+        /// Always replace the user's score
+        ForceUpdate,
+    }
+
+    /// Which entries to download.
+    pub enum EntriesRequest {
+        /// Ranks `start` to `end`, both included and counted from 1
+        Global {
+            start: i32,
+            end: i32,
+        },
+        /// From `start` to `end` around the user, e.g. -4 to 5
+        AroundUser {
+            start: i32,
+            end: i32,
+        },
+        Friends,
+        Users(Vec<SteamId>),
+    }
+
+    #[napi(object)]
+    #[derive(Clone)]
+    pub struct LeaderboardEntry {
+        pub user: PlayerSteamId,
+        pub global_rank: i32,
+        pub score: i32,
+        pub details: Vec<i32>,
+        /// The UGC attached to the entry with `attachUgc`, e.g. a replay
+        pub ugc_handle: Option<BigInt>,
+    }
+
+    #[napi(object)]
+    pub struct ScoreUploaded {
+        pub score: i32,
+        /// false if the user's score was better and was kept
+        pub score_changed: bool,
+        pub global_rank_new: i32,
+        /// 0 if the user had no score before
+        pub global_rank_previous: i32,
+    }
+
+    #[napi]
+    pub struct Leaderboard {
+        handle: u64,
+    }
+
+    fn timeout(timeout_seconds: Option<u32>) -> Duration {
+        Duration::from_secs(u64::from(timeout_seconds.unwrap_or(10)))
+    }
+
+    async fn download(
+        handle: u64,
+        request: EntriesRequest,
+        timeout_seconds: Option<u32>,
+    ) -> Result<Vec<LeaderboardEntry>> {
+        let backend = crate::client::get_backend()?;
+
+        let (tx, rx) = oneshot::channel();
+        let _waiting = backend.download_leaderboard_entries(
+            handle,
+            request,
+            Box::new(|result| {
+                let _ = tx.send(result);
+            }),
+        )?;
+
+        let entries =
+            crate::dispatch::receive(rx, timeout(timeout_seconds), "the leaderboard entries")
+                .await?
+                .map_err(SteamworksError::from)?;
+        Ok(entries)
+    }
+
+    #[napi]
+    impl Leaderboard {
+        #[napi(getter)]
+        pub fn handle(&self) -> Result<BigInt> {
+            crate::panic::guard(|| Ok(self.handle.into()))
+        }
+
+        #[napi]
+        pub fn get_name(&self) -> Result<String> {
+            crate::panic::guard(|| {
+                let backend = crate::client::get_backend()?;
+                Ok(backend.leaderboard_name(self.handle))
+            })
+        }
+
+        #[napi]
+        pub fn get_entry_count(&self) -> Result<i32> {
+            crate::panic::guard(|| {
+                let backend = crate::client::get_backend()?;
+                Ok(backend.leaderboard_entry_count(self.handle))
+            })
+        }
+
+        #[napi]
+        pub fn get_sort_method(&self) -> Result<Option<SortMethod>> {
+            crate::panic::guard(|| {
+                let backend = crate::client::get_backend()?;
+                Ok(backend.leaderboard_sort_method(self.handle))
+            })
+        }
+
+        #[napi]
+        pub fn get_display_type(&self) -> Result<Option<DisplayType>> {
+            crate::panic::guard(|| {
+                let backend = crate::client::get_backend()?;
+                Ok(backend.leaderboard_display_type(self.handle))
+            })
+        }
+
+        /// @param method - Default value is `KeepBest`
+        /// @param details - Up to 64 ints to keep with the score, e.g. how the run went
+        /// @param timeoutSeconds - The number of seconds to wait for Steam. Default value is 10 seconds.
+        /// @throws INVALID_PARAMETER if there are more than 64 details
+        ///
+        /// {@link https://partner.steamgames.com/doc/api/ISteamUserStats#UploadLeaderboardScore}
+        #[napi]
+        pub async fn upload_score(
+            &self,
+            score: i32,
+            method: Option<UploadScoreMethod>,
+            details: Option<Vec<i32>>,
+            timeout_seconds: Option<u32>,
+            signal: Option<AbortSignal>,
+        ) -> Result<ScoreUploaded> {
+            let handle = self.handle;
+            crate::panic::guard_async(crate::abort::abortable(signal, async move {
+                let backend = crate::client::get_backend()?;
+                let details = details.unwrap_or_default();
+                if details.len() > MAX_DETAILS {
+                    return Err(napi::Error::new(
+                        ErrorCode::InvalidParameter,
+                        format!("A score takes up to {} details", MAX_DETAILS),
+                    ));
+                }
+
+                let (tx, rx) = oneshot::channel();
+                backend.upload_leaderboard_score(
+                    handle,
+                    method.unwrap_or(UploadScoreMethod::KeepBest),
+                    score,
+                    &details,
+                    Box::new(|result| {
+                        let _ = tx.send(result);
+                    }),
+                );
+
+                let uploaded =
+                    crate::dispatch::receive(rx, timeout(timeout_seconds), "the score upload")
+                        .await?
+                        .map_err(SteamworksError::from)?;
+                Ok(uploaded)
+            }))
+            .await
+        }
+
+        /// Downloads the entries ranked `start` to `end`, both included and counted from 1.
+        /// @param timeoutSeconds - The number of seconds to wait for Steam. Default value is 10 seconds.
+        #[napi]
+        pub async fn download_global(
+            &self,
+            start: i32,
+            end: i32,
+            timeout_seconds: Option<u32>,
+            signal: Option<AbortSignal>,
+        ) -> Result<Vec<LeaderboardEntry>> {
+            let request = EntriesRequest::Global { start, end };
+            crate::panic::guard_async(crate::abort::abortable(
+                signal,
+                download(self.handle, request, timeout_seconds),
+            ))
+            .await
+        }
+
+        /// Downloads the entries around the user's, e.g. `-4` to `5` for the 4 above and the 5 below.
+        /// Empty if the user has no score.
+        /// @param timeoutSeconds - The number of seconds to wait for Steam. Default value is 10 seconds.
+        #[napi]
+        pub async fn download_around_user(
+            &self,
+            start: i32,
+            end: i32,
+            timeout_seconds: Option<u32>,
+            signal: Option<AbortSignal>,
+        ) -> Result<Vec<LeaderboardEntry>> {
+            let request = EntriesRequest::AroundUser { start, end };
+            crate::panic::guard_async(crate::abort::abortable(
+                signal,
+                download(self.handle, request, timeout_seconds),
+            ))
+            .await
+        }
+
+        /// Downloads the entries of the user and their friends.
+        /// @param timeoutSeconds - The number of seconds to wait for Steam. Default value is 10 seconds.
+        #[napi]
+        pub async fn download_friends(
+            &self,
+            timeout_seconds: Option<u32>,
+            signal: Option<AbortSignal>,
+        ) -> Result<Vec<LeaderboardEntry>> {
+            crate::panic::guard_async(crate::abort::abortable(
+                signal,
+                download(self.handle, EntriesRequest::Friends, timeout_seconds),
+            ))
+            .await
+        }
+
+        /// Downloads the entries of the given users, the ones without a score are left out.
+        /// @param timeoutSeconds - The number of seconds to wait for Steam. Default value is 10 seconds.
+        /// @throws INVALID_PARAMETER if there are more than 100 users
+        #[napi]
+        pub async fn download_users(
+            &self,
+            steam_ids: Vec<BigInt>,
+            timeout_seconds: Option<u32>,
+            signal: Option<AbortSignal>,
+        ) -> Result<Vec<LeaderboardEntry>> {
+            let handle = self.handle;
+            crate::panic::guard_async(crate::abort::abortable(signal, async move {
+                // Steam's limit for a single request.
+                if steam_ids.len() > 100 {
+                    return Err(napi::Error::new(
+                        ErrorCode::InvalidParameter,
+                        "Entries can be downloaded for up to 100 users at once",
+                    ));
+                }
+                let users = steam_ids
+                    .iter()
+                    .map(|steam_id| SteamId::from_raw(steam_id.get_u64().1))
+                    .collect();
+                download(handle, EntriesRequest::Users(users), timeout_seconds).await
+            }))
+            .await
+        }
+
+        /// Attaches UGC to the user's entry, e.g. a replay shared with `ISteamRemoteStorage::FileShare`.
+        /// @param timeoutSeconds - The number of seconds to wait for Steam. Default value is 10 seconds.
+        ///
+        /// {@link https://partner.steamgames.com/doc/api/ISteamUserStats#AttachLeaderboardUGC}
+        #[napi]
+        pub async fn attach_ugc(
+            &self,
+            ugc_handle: BigInt,
+            timeout_seconds: Option<u32>,
+            signal: Option<AbortSignal>,
+        ) -> Result<()> {
+            let handle = self.handle;
+            crate::panic::guard_async(crate::abort::abortable(signal, async move {
+                let backend = crate::client::get_backend()?;
+
+                let (tx, rx) = oneshot::channel();
+                let _waiting = backend.attach_leaderboard_ugc(
+                    handle,
+                    ugc_handle.get_u64().1,
+                    Box::new(|result| {
+                        let _ = tx.send(result);
+                    }),
+                )?;
+
+                crate::dispatch::receive(rx, timeout(timeout_seconds), "the attached UGC")
+                    .await?
+                    .map_err(SteamworksError::from)?;
+                Ok(())
+            }))
+            .await
+        }
+    }
+
+    async fn find_handle(
+        name: String,
+        create: Option<(SortMethod, DisplayType)>,
+        timeout_seconds: Option<u32>,
+    ) -> Result<Option<Leaderboard>> {
+        let backend = crate::client::get_backend()?;
+
+        let (tx, rx) = oneshot::channel();
+        backend.find_leaderboard(
+            &name,
+            create,
+            Box::new(|result| {
+                let _ = tx.send(result);
+            }),
+        );
+
+        let handle = crate::dispatch::receive(rx, timeout(timeout_seconds), "the leaderboard")
+            .await?
+            .map_err(SteamworksError::from)?;
+        Ok(handle.map(|handle| Leaderboard { handle }))
+    }
+
+    /// @param timeoutSeconds - The number of seconds to wait for Steam. Default value is 10 seconds.
+    /// @returns null if there's no such leaderboard
+    ///
+    /// {@link https://partner.steamgames.com/doc/api/ISteamUserStats#FindLeaderboard}
+    #[napi]
+    pub async fn find(
+        name: String,
+        timeout_seconds: Option<u32>,
+        signal: Option<AbortSignal>,
+    ) -> Result<Option<Leaderboard>> {
+        crate::panic::guard_async(crate::abort::abortable(
+            signal,
+            find_handle(name, None, timeout_seconds),
+        ))
+        .await
+    }
+
+    /// Finds a leaderboard, creating it with the given sort method and display type if it doesn't
+    /// exist yet. Those are ignored for an existing one.
+    /// @param timeoutSeconds - The number of seconds to wait for Steam. Default value is 10 seconds.
+    ///
+    /// {@link https://partner.steamgames.com/doc/api/ISteamUserStats#FindOrCreateLeaderboard}
+    #[napi]
+    pub async fn find_or_create(
+        name: String,
+        sort_method: SortMethod,
+        display_type: DisplayType,
+        timeout_seconds: Option<u32>,
+        signal: Option<AbortSignal>,
+    ) -> Result<Leaderboard> {
+        crate::panic::guard_async(crate::abort::abortable(signal, async move {
+            let what = name.clone();
+            find_handle(name, Some((sort_method, display_type)), timeout_seconds)
+                .await?
+                .ok_or_else(|| {
+                    SteamworksError::failed(format!("Steam didn't create the leaderboard {}", what))
+                        .into()
+                })
+        }))
+        .await
+    }
+}
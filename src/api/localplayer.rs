@@ -2,7 +2,7 @@ use napi::bindgen_prelude::BigInt;
 use napi_derive::napi;
 use steamworks::SteamId;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[napi(object)]
 pub struct PlayerSteamId {
     pub steam_id64: BigInt,
@@ -23,36 +23,48 @@ impl PlayerSteamId {
 #[napi]
 pub mod localplayer {
     use super::PlayerSteamId;
+    use crate::error::Result;
 
     #[napi]
-    pub fn get_steam_id() -> PlayerSteamId {
-        let client = crate::client::get_client().unwrap();
-        let steam_id = client.user().steam_id();
-        PlayerSteamId::from_steamid(steam_id)
+    pub fn get_steam_id() -> Result<PlayerSteamId> {
+        crate::panic::guard(|| {
+            let client = crate::client::get_client()?;
+            let steam_id = client.user().steam_id();
+            Ok(PlayerSteamId::from_steamid(steam_id))
+        })
     }
 
     #[napi]
-    pub fn get_name() -> String {
-        let client = crate::client::get_client().unwrap();
-        client.friends().name()
+    pub fn get_name() -> Result<String> {
+        crate::panic::guard(|| {
+            let client = crate::client::get_client()?;
+            Ok(client.friends().name())
+        })
     }
 
     #[napi]
-    pub fn get_level() -> u32 {
-        let client = crate::client::get_client().unwrap();
-        client.user().level()
+    pub fn get_level() -> Result<u32> {
+        crate::panic::guard(|| {
+            let client = crate::client::get_client()?;
+            Ok(client.user().level())
+        })
     }
 
     /// @returns the 2 digit ISO 3166-1-alpha-2 format country code which client is running in, e.g. "US" or "UK".
     #[napi]
-    pub fn get_ip_country() -> String {
-        let client = crate::client::get_client().unwrap();
-        client.utils().ip_country()
+    pub fn get_ip_country() -> Result<String> {
+        crate::panic::guard(|| {
+            let client = crate::client::get_client()?;
+            Ok(client.utils().ip_country())
+        })
     }
 
     #[napi]
-    pub fn set_rich_presence(key: String, value: Option<String>) {
-        let client = crate::client::get_client().unwrap();
-        client.friends().set_rich_presence(&key, value.as_deref());
+    pub fn set_rich_presence(key: String, value: Option<String>) -> Result<()> {
+        crate::panic::guard(|| {
+            let client = crate::client::get_client()?;
+            client.friends().set_rich_presence(&key, value.as_deref());
+            Ok(())
+        })
     }
 }
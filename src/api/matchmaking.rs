@@ -2,10 +2,13 @@ use napi_derive::napi;
 
 #[napi]
 pub mod matchmaking {
+    use crate::abort::AbortSignal;
     use crate::api::localplayer::PlayerSteamId;
-    use napi::bindgen_prelude::{BigInt, Error};
+    use crate::error::{Result, SteamworksError};
+    use napi::bindgen_prelude::BigInt;
     use std::collections::HashMap;
-    use steamworks::{LobbyId, SteamError};
+    use std::time::Duration;
+    use steamworks::LobbyId;
     use tokio::sync::oneshot;
 
     #[napi]
@@ -25,213 +28,263 @@ pub mod matchmaking {
     #[napi]
     impl Lobby {
         #[napi]
-        pub fn get_lobby_entry(&self, chat_id: i32) -> String {
-            let client = crate::client::get_client().unwrap();
-            let mut buffer = vec![0u8; 4096];
-            client
-                .matchmaking()
-                .get_lobby_chat_entry(self.lobby_id, chat_id, &mut buffer);
-            String::from_utf8_lossy(&buffer)
-                .trim_end_matches("\0")
-                .to_string()
+        pub fn get_lobby_entry(&self, chat_id: i32) -> Result<String> {
+            crate::panic::guard(|| {
+                let backend = crate::client::get_backend()?;
+                let buffer = backend.lobby_chat_entry(self.lobby_id, chat_id);
+                Ok(String::from_utf8_lossy(&buffer)
+                    .trim_end_matches("\0")
+                    .to_string())
+            })
         }
 
         #[napi]
-        pub fn send_lobby_message(&self, message: String) -> Result<(), napi::Error> {
-            let client = crate::client::get_client().unwrap();
-            client
-                .matchmaking()
-                .send_lobby_chat_message(self.lobby_id, message.as_bytes())
-                .map_err(|_| napi::Error::from_reason("Failed to send lobby message"))
+        pub fn send_lobby_message(&self, message: String) -> Result<()> {
+            crate::panic::guard(|| {
+                let backend = crate::client::get_backend()?;
+                backend
+                    .send_lobby_chat_message(self.lobby_id, message.as_bytes())
+                    .map_err(|e| SteamworksError::from(e).into())
+            })
         }
 
         #[napi]
-        pub async fn join(&self) -> Result<Lobby, Error> {
-            join_lobby(self.id.into()).await
+        pub async fn join(&self, signal: Option<AbortSignal>) -> Result<Lobby> {
+            crate::panic::guard_async(crate::abort::abortable(signal, async move {
+                join_lobby(self.id.into(), None, None).await
+            }))
+            .await
         }
 
         #[napi]
-        pub fn leave(&self) {
-            let client = crate::client::get_client().unwrap();
-            client.matchmaking().leave_lobby(self.lobby_id);
+        pub fn leave(&self) -> Result<()> {
+            crate::panic::guard(|| {
+                let backend = crate::client::get_backend()?;
+                backend.leave_lobby(self.lobby_id);
+                crate::resources::untrack_lobby(self.lobby_id);
+                Ok(())
+            })
         }
 
         #[napi]
-        pub fn open_invite_dialog(&self) {
-            let client = crate::client::get_client().unwrap();
-            client.friends().activate_invite_dialog(self.lobby_id);
+        pub fn open_invite_dialog(&self) -> Result<()> {
+            crate::panic::guard(|| {
+                let client = crate::client::get_client()?;
+                client.friends().activate_invite_dialog(self.lobby_id);
+                Ok(())
+            })
         }
 
         #[napi]
-        pub fn get_member_count(&self) -> usize {
-            let client = crate::client::get_client().unwrap();
-            client.matchmaking().lobby_member_count(self.lobby_id)
+        pub fn get_member_count(&self) -> Result<usize> {
+            crate::panic::guard(|| {
+                let backend = crate::client::get_backend()?;
+                Ok(backend.lobby_member_count(self.lobby_id))
+            })
         }
 
         #[napi]
-        pub fn get_member_limit(&self) -> Option<usize> {
-            let client = crate::client::get_client().unwrap();
-            client.matchmaking().lobby_member_limit(self.lobby_id)
+        pub fn get_member_limit(&self) -> Result<Option<usize>> {
+            crate::panic::guard(|| {
+                let backend = crate::client::get_backend()?;
+                Ok(backend.lobby_member_limit(self.lobby_id))
+            })
         }
 
         #[napi]
-        pub fn get_members(&self) -> Vec<PlayerSteamId> {
-            let client = crate::client::get_client().unwrap();
-            client
-                .matchmaking()
-                .lobby_members(self.lobby_id)
-                .into_iter()
-                .map(PlayerSteamId::from_steamid)
-                .collect()
+        pub fn get_members(&self) -> Result<Vec<PlayerSteamId>> {
+            crate::panic::guard(|| {
+                let backend = crate::client::get_backend()?;
+                Ok(backend
+                    .lobby_members(self.lobby_id)
+                    .into_iter()
+                    .map(PlayerSteamId::from_steamid)
+                    .collect())
+            })
         }
 
         #[napi]
-        pub fn get_owner(&self) -> PlayerSteamId {
-            let client = crate::client::get_client().unwrap();
-            PlayerSteamId::from_steamid(client.matchmaking().lobby_owner(self.lobby_id))
+        pub fn get_owner(&self) -> Result<PlayerSteamId> {
+            crate::panic::guard(|| {
+                let backend = crate::client::get_backend()?;
+                Ok(PlayerSteamId::from_steamid(
+                    backend.lobby_owner(self.lobby_id),
+                ))
+            })
         }
 
         #[napi]
-        pub fn set_joinable(&self, joinable: bool) -> bool {
-            let client = crate::client::get_client().unwrap();
-            client
-                .matchmaking()
-                .set_lobby_joinable(self.lobby_id, joinable)
+        pub fn set_joinable(&self, joinable: bool) -> Result<bool> {
+            crate::panic::guard(|| {
+                let backend = crate::client::get_backend()?;
+                Ok(backend.set_lobby_joinable(self.lobby_id, joinable))
+            })
         }
 
         #[napi]
-        pub fn get_data(&self, key: String) -> Option<String> {
-            let client = crate::client::get_client().unwrap();
-            client
-                .matchmaking()
-                .lobby_data(self.lobby_id, &key)
-                .map(|s| s.to_string())
+        pub fn get_data(&self, key: String) -> Result<Option<String>> {
+            crate::panic::guard(|| {
+                let backend = crate::client::get_backend()?;
+                Ok(backend.lobby_data(self.lobby_id, &key))
+            })
         }
 
         #[napi]
-        pub fn set_data(&self, key: String, value: String) -> bool {
-            let client = crate::client::get_client().unwrap();
-            client
-                .matchmaking()
-                .set_lobby_data(self.lobby_id, &key, &value)
+        pub fn set_data(&self, key: String, value: String) -> Result<bool> {
+            crate::panic::guard(|| {
+                let backend = crate::client::get_backend()?;
+                Ok(backend.set_lobby_data(self.lobby_id, &key, &value))
+            })
         }
 
         #[napi]
-        pub fn delete_data(&self, key: String) -> bool {
-            let client = crate::client::get_client().unwrap();
-            client.matchmaking().delete_lobby_data(self.lobby_id, &key)
+        pub fn delete_data(&self, key: String) -> Result<bool> {
+            crate::panic::guard(|| {
+                let backend = crate::client::get_backend()?;
+                Ok(backend.delete_lobby_data(self.lobby_id, &key))
+            })
         }
 
         /// Get an object containing all the lobby data
         #[napi]
-        pub fn get_full_data(&self) -> HashMap<String, String> {
-            let client = crate::client::get_client().unwrap();
-
-            let mut data = HashMap::new();
-
-            let count = client.matchmaking().lobby_data_count(self.lobby_id);
-            for i in 0..count {
-                let maybe_lobby_data = client.matchmaking().lobby_data_by_index(self.lobby_id, i);
-
-                if let Some((key, value)) = maybe_lobby_data {
-                    data.insert(key, value);
-                }
-            }
-
-            data
+        pub fn get_full_data(&self) -> Result<HashMap<String, String>> {
+            crate::panic::guard(|| {
+                let backend = crate::client::get_backend()?;
+                Ok(backend.lobby_full_data(self.lobby_id).into_iter().collect())
+            })
         }
 
         /// Merge current lobby data with provided data in a single batch
         /// @returns true if all data was set successfully
         #[napi]
-        pub fn merge_full_data(&self, data: HashMap<String, String>) -> bool {
-            let matchmaking = crate::client::get_client().unwrap().matchmaking();
-            data.iter()
-                .all(|(key, value)| matchmaking.set_lobby_data(self.lobby_id, key, value))
+        pub fn merge_full_data(&self, data: HashMap<String, String>) -> Result<bool> {
+            crate::panic::guard(|| {
+                let backend = crate::client::get_backend()?;
+                Ok(data
+                    .iter()
+                    .all(|(key, value)| backend.set_lobby_data(self.lobby_id, key, value)))
+            })
         }
 
         #[napi(getter)]
-        pub fn id(&self) -> BigInt {
-            self.id.into()
+        pub fn id(&self) -> Result<BigInt> {
+            crate::panic::guard(|| Ok(self.id.into()))
         }
 
         #[napi(getter)]
-        pub fn id_as_u64(&self) -> u64 {
-            self.id
+        pub fn id_as_u64(&self) -> Result<u64> {
+            crate::panic::guard(|| Ok(self.id))
         }
     }
 
+    /// @param timeoutSeconds - The number of seconds to wait for Steam to create the lobby. Default value is 10 seconds.
     #[napi]
-    pub async fn create_lobby(lobby_type: LobbyType, max_members: u32) -> Result<Lobby, Error> {
-        let client = crate::client::get_client().unwrap();
-
-        let (tx, rx) = oneshot::channel();
-
-        client.matchmaking().create_lobby(
-            match lobby_type {
-                LobbyType::Private => steamworks::LobbyType::Private,
-                LobbyType::FriendsOnly => steamworks::LobbyType::FriendsOnly,
-                LobbyType::Public => steamworks::LobbyType::Public,
-                LobbyType::Invisible => steamworks::LobbyType::Invisible,
-            },
-            max_members,
-            |result| {
-                tx.send(result).unwrap();
-            },
-        );
-
-        rx.await
-            .unwrap()
-            .map(|lobby_id| Lobby {
+    pub async fn create_lobby(
+        lobby_type: LobbyType,
+        max_members: u32,
+        timeout_seconds: Option<u32>,
+        signal: Option<AbortSignal>,
+    ) -> Result<Lobby> {
+        crate::panic::guard_async(crate::abort::abortable(signal, async move {
+            let backend = crate::client::get_backend()?;
+
+            let (tx, rx) = oneshot::channel();
+
+            backend.create_lobby(
+                match lobby_type {
+                    LobbyType::Private => steamworks::LobbyType::Private,
+                    LobbyType::FriendsOnly => steamworks::LobbyType::FriendsOnly,
+                    LobbyType::Public => steamworks::LobbyType::Public,
+                    LobbyType::Invisible => steamworks::LobbyType::Invisible,
+                },
+                max_members,
+                Box::new(|result| {
+                    if let Err(Ok(lobby_id)) = tx.send(result) {
+                        leave_abandoned(lobby_id);
+                    }
+                }),
+            );
+
+            let lobby_id = crate::dispatch::receive(rx, timeout(timeout_seconds), "the lobby")
+                .await?
+                .map_err(SteamworksError::from)?;
+            crate::resources::track_lobby(lobby_id);
+            Ok(Lobby {
                 id: lobby_id.raw(),
                 lobby_id,
             })
-            .map_err(|e| Error::from_reason(e.to_string()))
+        }))
+        .await
     }
 
+    /// @param timeoutSeconds - The number of seconds to wait for Steam to join the lobby. Default value is 10 seconds.
     #[napi]
-    pub async fn join_lobby(lobby_id: BigInt) -> Result<Lobby, Error> {
-        let client = crate::client::get_client().unwrap();
-
-        let (tx, rx) = oneshot::channel();
-
-        client.matchmaking().join_lobby(
-            steamworks::LobbyId::from_raw(lobby_id.get_u64().1),
-            |result| {
-                tx.send(result).unwrap();
-            },
-        );
-
-        rx.await
-            .unwrap()
-            .map(|lobby_id| Lobby {
+    pub async fn join_lobby(
+        lobby_id: BigInt,
+        timeout_seconds: Option<u32>,
+        signal: Option<AbortSignal>,
+    ) -> Result<Lobby> {
+        crate::panic::guard_async(crate::abort::abortable(signal, async move {
+            let backend = crate::client::get_backend()?;
+
+            let (tx, rx) = oneshot::channel();
+
+            backend.join_lobby(
+                steamworks::LobbyId::from_raw(lobby_id.get_u64().1),
+                Box::new(|result| {
+                    if let Err(Ok(lobby_id)) = tx.send(result) {
+                        leave_abandoned(lobby_id);
+                    }
+                }),
+            );
+
+            let lobby_id = crate::dispatch::receive(rx, timeout(timeout_seconds), "the lobby")
+                .await?
+                .map_err(|_| SteamworksError::failed("Failed to join lobby"))?;
+            crate::resources::track_lobby(lobby_id);
+            Ok(Lobby {
                 id: lobby_id.raw(),
                 lobby_id,
             })
-            .map_err(|_| Error::from_reason("Failed to join lobby".to_string()))
+        }))
+        .await
     }
 
-    #[napi]
-    pub async fn get_lobbies() -> Result<Vec<Lobby>, Error> {
-        let client = crate::client::get_client().unwrap();
-
-        let (tx, rx) = oneshot::channel();
+    fn timeout(timeout_seconds: Option<u32>) -> Duration {
+        Duration::from_secs(u64::from(timeout_seconds.unwrap_or(10)))
+    }
 
-        client.matchmaking().request_lobby_list(|lobbies| {
-            tx.send(lobbies).unwrap();
-        });
+    /// Leaves a lobby Steam only came back with after the promise gave up on it.
+    fn leave_abandoned(lobby_id: LobbyId) {
+        log_debug!("Leaving lobby {} that was given up on", lobby_id.raw());
+        if let Ok(backend) = crate::client::get_backend() {
+            backend.leave_lobby(lobby_id);
+        }
+    }
 
-        rx.await
-            .unwrap()
-            .map(|lobbies| {
-                lobbies
-                    .iter()
-                    .map(|lobby_id| Lobby {
-                        id: lobby_id.raw(),
-                        lobby_id: *lobby_id,
-                    })
-                    .collect()
-            })
-            .map_err(|e| Error::from_reason(e.to_string()))
+    #[napi]
+    pub async fn get_lobbies(signal: Option<AbortSignal>) -> Result<Vec<Lobby>> {
+        crate::panic::guard_async(crate::abort::abortable(signal, async move {
+            let backend = crate::client::get_backend()?;
+
+            let (tx, rx) = oneshot::channel();
+
+            backend.request_lobby_list(Box::new(|lobbies| {
+                let _ = tx.send(lobbies);
+            }));
+
+            let lobbies = crate::resources::until_shutdown(rx)
+                .await?
+                .map_err(SteamworksError::from)?
+                .map_err(SteamworksError::from)?;
+            Ok(lobbies
+                .iter()
+                .map(|lobby_id| Lobby {
+                    id: lobby_id.raw(),
+                    lobby_id: *lobby_id,
+                })
+                .collect())
+        }))
+        .await
     }
 }
@@ -0,0 +1,289 @@
+use napi_derive::napi;
+
+/// Controls for the in-memory backend, only usable after `init` with `BackendKind.Mock`.
+#[napi]
+pub mod mock {
+    use std::sync::Arc;
+
+    use napi::bindgen_prelude::{BigInt, Buffer};
+
+    use crate::api::callback::callback::SteamCallback;
+    use crate::api::input::input::InputType;
+    use crate::api::stats::stats::StatType;
+    use crate::backend::{MockBackend, SteamBackend};
+    use crate::error::{ErrorCode, Result};
+
+    fn with_mock<T>(f: impl FnOnce(&MockBackend) -> T) -> Result<T> {
+        let backend: Arc<dyn SteamBackend> = crate::client::get_backend()?;
+        backend.as_mock().map(f).ok_or_else(|| {
+            napi::Error::new(ErrorCode::InvalidState, "The mock backend is not active.")
+        })
+    }
+
+    /// Defines a stat so it can be read and written through the `stats` module.
+    /// @param statType - Default value is `Int`
+    #[napi]
+    pub fn define_stat(name: String, value: f64, stat_type: Option<StatType>) -> Result<()> {
+        crate::panic::guard(|| {
+            with_mock(|mock| mock.define_stat(&name, stat_type.unwrap_or(StatType::Int), value))
+        })
+    }
+
+    /// Defines an achievement so it can be used through the `achievement` module.
+    #[napi]
+    pub fn define_achievement(name: String, achieved: Option<bool>) -> Result<()> {
+        crate::panic::guard(|| {
+            with_mock(|mock| mock.define_achievement(&name, achieved.unwrap_or(false)))
+        })
+    }
+
+    /// Sets the localized strings `achievement.getInfo` returns.
+    /// @returns false if the achievement isn't defined
+    #[napi]
+    pub fn define_achievement_info(
+        name: String,
+        display_name: String,
+        description: String,
+        hidden: Option<bool>,
+    ) -> Result<bool> {
+        crate::panic::guard(|| {
+            with_mock(|mock| {
+                mock.define_achievement_info(
+                    &name,
+                    &display_name,
+                    &description,
+                    hidden.unwrap_or(false),
+                )
+            })
+        })
+    }
+
+    /// Sets the icon `achievement.getIcon` returns.
+    /// @param rgba - `width * height` RGBA pixels, row by row
+    /// @returns false if the achievement isn't defined
+    /// @throws INVALID_PARAMETER if the size doesn't match the pixels
+    #[napi]
+    pub fn define_achievement_icon(
+        name: String,
+        width: u32,
+        height: u32,
+        rgba: Buffer,
+    ) -> Result<bool> {
+        crate::panic::guard(|| {
+            let size = width as usize * height as usize * 4;
+            if rgba.len() != size {
+                return Err(napi::Error::new(
+                    ErrorCode::InvalidParameter,
+                    format!("A {}x{} icon takes {} bytes", width, height, size),
+                ));
+            }
+            with_mock(|mock| mock.define_achievement_icon(&name, width, height, rgba.to_vec()))
+        })
+    }
+
+    /// Sets the share of players that unlocked an achievement, served once
+    /// `achievement.requestGlobalAchievementPercentages` was called.
+    /// @param percent - From 0 to 100
+    #[napi]
+    pub fn define_achievement_percent(name: String, percent: f64) -> Result<()> {
+        crate::panic::guard(|| {
+            with_mock(|mock| mock.define_achievement_percent(&name, percent as f32))
+        })
+    }
+
+    /// Defines a global stat, served once `stats.requestGlobalStats` was called.
+    /// @param history - The daily totals, today first
+    /// @param statType - Default value is `Int`
+    #[napi]
+    pub fn define_global_stat(
+        name: String,
+        total: f64,
+        history: Option<Vec<f64>>,
+        stat_type: Option<StatType>,
+    ) -> Result<()> {
+        crate::panic::guard(|| {
+            with_mock(|mock| {
+                mock.define_global_stat(
+                    &name,
+                    stat_type.unwrap_or(StatType::Int),
+                    total,
+                    &history.unwrap_or_default(),
+                )
+            })
+        })
+    }
+
+    /// Sets a stat of another user, read with `stats.getUserStatInt` and `stats.getUserStatFloat`
+    /// once their stats were requested. The stat has to be defined with `defineStat` as well.
+    #[napi]
+    pub fn define_user_stat(steam_id: BigInt, name: String, value: f64) -> Result<()> {
+        crate::panic::guard(|| {
+            with_mock(|mock| {
+                mock.define_user_stat(
+                    steamworks::SteamId::from_raw(steam_id.get_u64().1),
+                    &name,
+                    value,
+                )
+            })
+        })
+    }
+
+    /// Sets whether another user has an achievement, read with `achievement.getUserAchievement`
+    /// once their stats were requested. The achievement has to be defined with `defineAchievement`
+    /// as well.
+    /// @param unlockTime - In seconds since the epoch. Default value is now.
+    #[napi]
+    pub fn define_user_achievement(
+        steam_id: BigInt,
+        name: String,
+        achieved: bool,
+        unlock_time: Option<u32>,
+    ) -> Result<()> {
+        crate::panic::guard(|| {
+            with_mock(|mock| {
+                mock.define_user_achievement(
+                    steamworks::SteamId::from_raw(steam_id.get_u64().1),
+                    &name,
+                    achieved,
+                    unlock_time,
+                )
+            })
+        })
+    }
+
+    /// Sets the name `friends.requestUserInformation` gives for another user, who's "[unknown]"
+    /// until then.
+    #[napi]
+    pub fn define_user_name(steam_id: BigInt, name: String) -> Result<()> {
+        crate::panic::guard(|| {
+            with_mock(|mock| {
+                mock.define_user_name(steamworks::SteamId::from_raw(steam_id.get_u64().1), &name)
+            })
+        })
+    }
+
+    /// Adds another user's score to a leaderboard, replacing their earlier one. The leaderboard
+    /// is created, sorted descending, if it doesn't exist yet.
+    #[napi]
+    pub fn add_leaderboard_score(
+        leaderboard: String,
+        steam_id: BigInt,
+        score: i32,
+        details: Option<Vec<i32>>,
+    ) -> Result<()> {
+        crate::panic::guard(|| {
+            with_mock(|mock| {
+                mock.add_leaderboard_score(
+                    &leaderboard,
+                    steamworks::SteamId::from_raw(steam_id.get_u64().1),
+                    score,
+                    details.unwrap_or_default(),
+                )
+            })
+        })
+    }
+
+    /// Plugs in a fake controller.
+    /// @returns the handle of the new controller
+    #[napi]
+    pub fn add_controller(input_type: InputType) -> Result<BigInt> {
+        crate::panic::guard(|| {
+            with_mock(|mock| BigInt::from(mock.add_controller(input_type.into())))
+        })
+    }
+
+    /// @returns true if the controller existed
+    #[napi]
+    pub fn remove_controller(handle: BigInt) -> Result<bool> {
+        crate::panic::guard(|| with_mock(|mock| mock.remove_controller(handle.get_u64().1)))
+    }
+
+    /// @returns true if the controller exists
+    #[napi]
+    pub fn set_digital_action(handle: BigInt, action_name: String, pressed: bool) -> Result<bool> {
+        crate::panic::guard(|| {
+            with_mock(|mock| mock.set_digital_action(handle.get_u64().1, &action_name, pressed))
+        })
+    }
+
+    /// @returns true if the controller exists
+    #[napi]
+    pub fn set_analog_action(handle: BigInt, action_name: String, x: f64, y: f64) -> Result<bool> {
+        crate::panic::guard(|| {
+            with_mock(|mock| {
+                mock.set_analog_action(handle.get_u64().1, &action_name, x as f32, y as f32)
+            })
+        })
+    }
+
+    /// @returns the last `[current, max]` progress indicated for the achievement, or null if there was none
+    #[napi]
+    pub fn get_achievement_progress(name: String) -> Result<Option<Vec<u32>>> {
+        crate::panic::guard(|| {
+            with_mock(|mock| {
+                mock.achievement_progress(&name)
+                    .map(|(current, max)| vec![current, max])
+            })
+        })
+    }
+
+    /// @returns the last `[left, right]` vibration speeds sent to the controller, or null if it doesn't exist
+    #[napi]
+    pub fn get_vibration(handle: BigInt) -> Result<Option<Vec<u32>>> {
+        crate::panic::guard(|| {
+            with_mock(|mock| {
+                mock.vibration(handle.get_u64().1)
+                    .map(|(left, right)| vec![left as u32, right as u32])
+            })
+        })
+    }
+
+    /// @returns how many times the stats were stored since the last `reset`
+    #[napi]
+    pub fn get_store_count() -> Result<u32> {
+        crate::panic::guard(|| with_mock(|mock| mock.store_count()))
+    }
+
+    /// @returns how many auth tickets were issued and not cancelled
+    #[napi]
+    pub fn get_ticket_count() -> Result<u32> {
+        crate::panic::guard(|| with_mock(|mock| mock.ticket_count()))
+    }
+
+    /// Holds back Steam's answer on auth tickets, e.g. to abort a request while it waits. The held
+    /// ones are answered once it's turned off.
+    #[napi]
+    pub fn set_hold_tickets(hold: bool) -> Result<()> {
+        crate::panic::guard(|| with_mock(|mock| mock.set_hold_tickets(hold)))
+    }
+
+    /// Logs another user in, e.g. to test that what's kept for one user isn't used for another.
+    /// Everything else stays as it is.
+    #[napi]
+    pub fn set_steam_id(steam_id64: BigInt) -> Result<()> {
+        crate::panic::guard(|| {
+            with_mock(|mock| {
+                mock.set_steam_id(steamworks::SteamId::from_raw(steam_id64.get_u64().1))
+            })
+        })
+    }
+
+    /// Makes storing the stats fail, e.g. to test that a `stats.transaction` rolls back.
+    #[napi]
+    pub fn set_store_fails(fails: bool) -> Result<()> {
+        crate::panic::guard(|| with_mock(|mock| mock.set_store_fails(fails)))
+    }
+
+    /// Posts a callback the next time callbacks run, from the pump or `runCallbacks`, the way
+    /// Steam would.
+    #[napi]
+    pub fn queue_callback(steam_callback: SteamCallback, payload: serde_json::Value) -> Result<()> {
+        crate::panic::guard(|| with_mock(|mock| mock.queue_callback(steam_callback, payload)))
+    }
+
+    /// Drops every stat, achievement, leaderboard, file, lobby, workshop item and controller.
+    #[napi]
+    pub fn reset() -> Result<()> {
+        crate::panic::guard(|| with_mock(|mock| mock.reset()))
+    }
+}
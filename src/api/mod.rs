@@ -5,12 +5,16 @@ pub mod callback;
 pub mod cloud;
 pub mod friends;
 pub mod input;
+pub mod leaderboard;
 pub mod localplayer;
 pub mod matchmaking;
+pub mod mock;
 pub mod networking;
 pub mod overlay;
+pub mod server;
 pub mod socket;
 pub mod stats;
+pub mod stats_transaction;
 pub mod utils;
 pub mod workshop;
 pub mod workshop_item;
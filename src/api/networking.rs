@@ -2,13 +2,11 @@ use napi_derive::napi;
 
 #[napi]
 pub mod networking {
-    use napi::{
-        bindgen_prelude::{BigInt, Buffer},
-        Error,
-    };
+    use napi::bindgen_prelude::{BigInt, Buffer};
     use steamworks::SteamId;
 
     use crate::api::localplayer::PlayerSteamId;
+    use crate::error::{Result, SteamworksError};
 
     #[napi(object)]
     pub struct P2PPacket {
@@ -37,60 +35,60 @@ pub mod networking {
     }
 
     #[napi]
-    pub fn send_p2p_packet(
-        steam_id64: BigInt,
-        send_type: SendType,
-        data: Buffer,
-    ) -> Result<bool, Error> {
-        let client = crate::client::get_client().unwrap();
-        let result = client.networking().send_p2p_packet(
-            SteamId::from_raw(steam_id64.get_u64().1),
-            match send_type {
-                SendType::Unreliable => steamworks::SendType::Unreliable,
-                SendType::UnreliableNoDelay => steamworks::SendType::UnreliableNoDelay,
-                SendType::Reliable => steamworks::SendType::Reliable,
-                SendType::ReliableWithBuffering => steamworks::SendType::ReliableWithBuffering,
-            },
-            &data,
-        );
-        Ok(result)
+    pub fn send_p2p_packet(steam_id64: BigInt, send_type: SendType, data: Buffer) -> Result<bool> {
+        crate::panic::guard(|| {
+            let client = crate::client::get_client()?;
+            let result = client.networking().send_p2p_packet(
+                SteamId::from_raw(steam_id64.get_u64().1),
+                match send_type {
+                    SendType::Unreliable => steamworks::SendType::Unreliable,
+                    SendType::UnreliableNoDelay => steamworks::SendType::UnreliableNoDelay,
+                    SendType::Reliable => steamworks::SendType::Reliable,
+                    SendType::ReliableWithBuffering => steamworks::SendType::ReliableWithBuffering,
+                },
+                &data,
+            );
+            Ok(result)
+        })
     }
 
     #[napi]
-    pub fn is_p2p_packet_available() -> i32 {
-        let client = crate::client::get_client().unwrap();
-        client
-            .networking()
-            .is_p2p_packet_available()
-            .unwrap_or_default() as i32
+    pub fn is_p2p_packet_available() -> Result<i32> {
+        crate::panic::guard(|| {
+            let client = crate::client::get_client()?;
+            Ok(client
+                .networking()
+                .is_p2p_packet_available()
+                .unwrap_or_default() as i32)
+        })
     }
 
     #[napi]
-    pub fn read_p2p_packet(size: i32) -> Result<P2PPacket, Error> {
-        let client = crate::client::get_client().unwrap();
-        let mut buffer = vec![0; size as usize];
+    pub fn read_p2p_packet(size: i32) -> Result<P2PPacket> {
+        crate::panic::guard(|| {
+            let client = crate::client::get_client()?;
+            let mut buffer = vec![0; size as usize];
 
-        client
-            .networking()
-            .read_p2p_packet(&mut buffer)
-            .map(|(steam_id, read_size)| P2PPacket {
-                data: buffer.into(),
-                size: read_size as i32,
-                steam_id: PlayerSteamId::from_steamid(steam_id),
-            })
-            .ok_or_else(|| {
-                Error::new(
-                    napi::Status::GenericFailure,
-                    "No packet available".to_string(),
-                )
-            })
+            client
+                .networking()
+                .read_p2p_packet(&mut buffer)
+                .map(|(steam_id, read_size)| P2PPacket {
+                    data: buffer.into(),
+                    size: read_size as i32,
+                    steam_id: PlayerSteamId::from_steamid(steam_id),
+                })
+                .ok_or_else(|| SteamworksError::not_found("No packet available").into())
+        })
     }
 
     #[napi]
-    pub fn accept_p2p_session(steam_id64: BigInt) {
-        let client = crate::client::get_client().unwrap();
-        client
-            .networking()
-            .accept_p2p_session(SteamId::from_raw(steam_id64.get_u64().1));
+    pub fn accept_p2p_session(steam_id64: BigInt) -> Result<()> {
+        crate::panic::guard(|| {
+            let client = crate::client::get_client()?;
+            client
+                .networking()
+                .accept_p2p_session(SteamId::from_raw(steam_id64.get_u64().1));
+            Ok(())
+        })
     }
 }
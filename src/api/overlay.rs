@@ -6,6 +6,8 @@ pub mod overlay {
     use std::fmt;
     use steamworks::OverlayToStoreFlag;
 
+    use crate::error::Result;
+
     #[napi]
     pub enum Dialog {
         Friends,
@@ -39,44 +41,59 @@ pub mod overlay {
     }
 
     #[napi]
-    pub fn activate_dialog(dialog: Dialog) {
-        let client = crate::client::get_client().unwrap();
-        client.friends().activate_game_overlay(&dialog.to_string())
+    pub fn activate_dialog(dialog: Dialog) -> Result<()> {
+        crate::panic::guard(|| {
+            let client = crate::client::get_client()?;
+            client.friends().activate_game_overlay(&dialog.to_string());
+            Ok(())
+        })
     }
 
     #[napi]
-    pub fn activate_dialog_to_user(dialog: Dialog, steam_id64: BigInt) {
-        let client = crate::client::get_client().unwrap();
-        client.friends().activate_game_overlay_to_user(
-            &dialog.to_string(),
-            steamworks::SteamId::from_raw(steam_id64.get_u64().1),
-        )
+    pub fn activate_dialog_to_user(dialog: Dialog, steam_id64: BigInt) -> Result<()> {
+        crate::panic::guard(|| {
+            let client = crate::client::get_client()?;
+            client.friends().activate_game_overlay_to_user(
+                &dialog.to_string(),
+                steamworks::SteamId::from_raw(steam_id64.get_u64().1),
+            );
+            Ok(())
+        })
     }
 
     #[napi]
-    pub fn activate_invite_dialog(lobby_id: BigInt) {
-        let client = crate::client::get_client().unwrap();
-        client
-            .friends()
-            .activate_invite_dialog(steamworks::LobbyId::from_raw(lobby_id.get_u64().1))
+    pub fn activate_invite_dialog(lobby_id: BigInt) -> Result<()> {
+        crate::panic::guard(|| {
+            let client = crate::client::get_client()?;
+            client
+                .friends()
+                .activate_invite_dialog(steamworks::LobbyId::from_raw(lobby_id.get_u64().1));
+            Ok(())
+        })
     }
 
     #[napi]
-    pub fn activate_to_web_page(url: String) {
-        let client = crate::client::get_client().unwrap();
-        client.friends().activate_game_overlay_to_web_page(&url)
+    pub fn activate_to_web_page(url: String) -> Result<()> {
+        crate::panic::guard(|| {
+            let client = crate::client::get_client()?;
+            client.friends().activate_game_overlay_to_web_page(&url);
+            Ok(())
+        })
     }
 
     #[napi]
-    pub fn activate_to_store(app_id: u32, flag: StoreFlag) {
-        let client = crate::client::get_client().unwrap();
-        client.friends().activate_game_overlay_to_store(
-            steamworks::AppId(app_id),
-            match flag {
-                StoreFlag::None => OverlayToStoreFlag::None,
-                StoreFlag::AddToCart => OverlayToStoreFlag::AddToCart,
-                StoreFlag::AddToCartAndShow => OverlayToStoreFlag::AddToCartAndShow,
-            },
-        )
+    pub fn activate_to_store(app_id: u32, flag: StoreFlag) -> Result<()> {
+        crate::panic::guard(|| {
+            let client = crate::client::get_client()?;
+            client.friends().activate_game_overlay_to_store(
+                steamworks::AppId(app_id),
+                match flag {
+                    StoreFlag::None => OverlayToStoreFlag::None,
+                    StoreFlag::AddToCart => OverlayToStoreFlag::AddToCart,
+                    StoreFlag::AddToCartAndShow => OverlayToStoreFlag::AddToCartAndShow,
+                },
+            );
+            Ok(())
+        })
     }
 }
@@ -0,0 +1,378 @@
+use napi_derive::napi;
+
+#[napi]
+pub mod server {
+    use std::ffi::CString;
+
+    use napi::bindgen_prelude::{BigInt, Buffer};
+    use steamworks::SteamId;
+
+    use crate::abort::AbortSignal;
+    use crate::api::localplayer::PlayerSteamId;
+    use crate::error::{Result, SteamworksError};
+
+    #[napi]
+    pub enum ServerMode {
+        /// Don't authenticate user logins and don't list on the server list.
+        NoAuthentication,
+        /// Authenticate users, list on the server list, don't run VAC on clients that connect.
+        Authentication,
+        /// Authenticate users, list on the server list and VAC protect clients.
+        AuthenticationAndSecure,
+    }
+
+    impl From<ServerMode> for steamworks::ServerMode {
+        fn from(mode: ServerMode) -> Self {
+            match mode {
+                ServerMode::NoAuthentication => steamworks::ServerMode::NoAuthentication,
+                ServerMode::Authentication => steamworks::ServerMode::Authentication,
+                ServerMode::AuthenticationAndSecure => {
+                    steamworks::ServerMode::AuthenticationAndSecure
+                }
+            }
+        }
+    }
+
+    #[napi(object)]
+    pub struct ServerPlayer {
+        pub steam_id: PlayerSteamId,
+        /// The account that owns the game, differs from `steamId` when it's borrowed through Family Sharing
+        pub owner_steam_id: Option<PlayerSteamId>,
+        pub name: Option<String>,
+        pub score: u32,
+        /// Whether Steam has validated the player's auth ticket
+        pub authenticated: bool,
+    }
+
+    impl From<crate::server::ServerPlayer> for ServerPlayer {
+        fn from(player: crate::server::ServerPlayer) -> Self {
+            Self {
+                steam_id: PlayerSteamId::from_steamid(player.steam_id),
+                owner_steam_id: player.owner_steam_id.map(PlayerSteamId::from_steamid),
+                name: player.name,
+                score: player.score,
+                authenticated: player.authenticated,
+            }
+        }
+    }
+
+    fn raw_server() -> Result<*mut steamworks::sys::ISteamGameServer> {
+        crate::server::get_server()?;
+        Ok(unsafe { steamworks::sys::SteamAPI_SteamGameServer_v015() })
+    }
+
+    fn c_string(value: String) -> Result<CString> {
+        CString::new(value).map_err(|e| SteamworksError::invalid_parameter(e.to_string()).into())
+    }
+
+    /// Logs on to a generic, anonymous account.
+    #[napi]
+    pub fn log_on_anonymous() -> Result<()> {
+        crate::panic::guard(|| {
+            let server = crate::server::get_server()?;
+            server.log_on_anonymous();
+            Ok(())
+        })
+    }
+
+    /// Logs on to a persistent game server account.
+    ///
+    /// {@link https://partner.steamgames.com/doc/api/ISteamGameServer#LogOn}
+    #[napi]
+    pub fn log_on(token: String) -> Result<()> {
+        crate::panic::guard(|| {
+            let server = raw_server()?;
+            let token = c_string(token)?;
+            unsafe { steamworks::sys::SteamAPI_ISteamGameServer_LogOn(server, token.as_ptr()) };
+            Ok(())
+        })
+    }
+
+    #[napi]
+    pub fn log_off() -> Result<()> {
+        crate::panic::guard(|| {
+            let server = raw_server()?;
+            unsafe { steamworks::sys::SteamAPI_ISteamGameServer_LogOff(server) };
+            Ok(())
+        })
+    }
+
+    #[napi]
+    pub fn is_logged_on() -> Result<bool> {
+        crate::panic::guard(|| {
+            let server = raw_server()?;
+            Ok(unsafe { steamworks::sys::SteamAPI_ISteamGameServer_BLoggedOn(server) })
+        })
+    }
+
+    /// @returns true if the server is VAC secure, only valid after Steam connected
+    #[napi]
+    pub fn is_secure() -> Result<bool> {
+        crate::panic::guard(|| {
+            let server = raw_server()?;
+            Ok(unsafe { steamworks::sys::SteamAPI_ISteamGameServer_BSecure(server) })
+        })
+    }
+
+    /// @returns the steam id of the server, only valid after logging on
+    #[napi]
+    pub fn get_steam_id() -> Result<PlayerSteamId> {
+        crate::panic::guard(|| {
+            let server = crate::server::get_server()?;
+            Ok(PlayerSteamId::from_steamid(server.steam_id()))
+        })
+    }
+
+    #[napi]
+    pub fn set_server_name(name: String) -> Result<()> {
+        crate::panic::guard(|| {
+            let server = crate::server::get_server()?;
+            server.set_server_name(&name);
+            Ok(())
+        })
+    }
+
+    #[napi]
+    pub fn set_map_name(map_name: String) -> Result<()> {
+        crate::panic::guard(|| {
+            let server = crate::server::get_server()?;
+            server.set_map_name(&map_name);
+            Ok(())
+        })
+    }
+
+    #[napi]
+    pub fn set_max_players(count: u32) -> Result<()> {
+        crate::panic::guard(|| {
+            let server = crate::server::get_server()?;
+            server.set_max_players(count as i32);
+            Ok(())
+        })
+    }
+
+    #[napi]
+    pub fn set_bot_player_count(count: u32) -> Result<()> {
+        crate::panic::guard(|| {
+            let server = crate::server::get_server()?;
+            server.set_bot_player_count(count as i32);
+            Ok(())
+        })
+    }
+
+    /// Sets the game product identifier, usually the app id as a string.
+    #[napi]
+    pub fn set_product(product: String) -> Result<()> {
+        crate::panic::guard(|| {
+            let server = crate::server::get_server()?;
+            server.set_product(&product);
+            Ok(())
+        })
+    }
+
+    #[napi]
+    pub fn set_game_description(description: String) -> Result<()> {
+        crate::panic::guard(|| {
+            let server = crate::server::get_server()?;
+            server.set_game_description(&description);
+            Ok(())
+        })
+    }
+
+    #[napi]
+    pub fn set_mod_dir(mod_dir: String) -> Result<()> {
+        crate::panic::guard(|| {
+            let server = crate::server::get_server()?;
+            server.set_mod_dir(&mod_dir);
+            Ok(())
+        })
+    }
+
+    #[napi]
+    pub fn set_dedicated_server(dedicated: bool) -> Result<()> {
+        crate::panic::guard(|| {
+            let server = crate::server::get_server()?;
+            server.set_dedicated_server(dedicated);
+            Ok(())
+        })
+    }
+
+    /// Sets the comma separated tags clients can filter the server list with.
+    #[napi]
+    pub fn set_game_tags(tags: String) -> Result<()> {
+        crate::panic::guard(|| {
+            let server = crate::server::get_server()?;
+            server.set_game_tags(&tags);
+            Ok(())
+        })
+    }
+
+    #[napi]
+    pub fn set_game_data(data: String) -> Result<()> {
+        crate::panic::guard(|| {
+            let server = crate::server::get_server()?;
+            server.set_game_data(&data);
+            Ok(())
+        })
+    }
+
+    #[napi]
+    pub fn set_password_protected(password_protected: bool) -> Result<()> {
+        crate::panic::guard(|| {
+            let server = raw_server()?;
+            unsafe {
+                steamworks::sys::SteamAPI_ISteamGameServer_SetPasswordProtected(
+                    server,
+                    password_protected,
+                )
+            };
+            Ok(())
+        })
+    }
+
+    /// Adds a rule shown in the server browser's details.
+    #[napi]
+    pub fn set_key_value(key: String, value: String) -> Result<()> {
+        crate::panic::guard(|| {
+            let server = raw_server()?;
+            let key = c_string(key)?;
+            let value = c_string(value)?;
+            unsafe {
+                steamworks::sys::SteamAPI_ISteamGameServer_SetKeyValue(
+                    server,
+                    key.as_ptr(),
+                    value.as_ptr(),
+                )
+            };
+            Ok(())
+        })
+    }
+
+    #[napi]
+    pub fn clear_all_key_values() -> Result<()> {
+        crate::panic::guard(|| {
+            let server = raw_server()?;
+            unsafe { steamworks::sys::SteamAPI_ISteamGameServer_ClearAllKeyValues(server) };
+            Ok(())
+        })
+    }
+
+    /// Turns heartbeats to the master server on or off, which is what lists the server on the server browser.
+    ///
+    /// {@link https://partner.steamgames.com/doc/api/ISteamGameServer#SetAdvertiseServerActive}
+    #[napi]
+    pub fn set_advertise_server_active(active: bool) -> Result<()> {
+        crate::panic::guard(|| {
+            let server = raw_server()?;
+            unsafe {
+                steamworks::sys::SteamAPI_ISteamGameServer_SetAdvertiseServerActive(server, active)
+            };
+            Ok(())
+        })
+    }
+
+    /// Validates a joining player's auth ticket, obtained on their side with `auth.getSessionTicketWithSteamId`,
+    /// and adds them to the player list.
+    /// If Steam refuses the ticket, the player is removed again and the promise rejects with `AUTH_FAILED`.
+    ///
+    /// @param timeoutSeconds - The number of seconds to wait for Steam to validate the ticket. Default value is 10 seconds.
+    ///
+    /// {@link https://partner.steamgames.com/doc/api/ISteamGameServer#BeginAuthSession}
+    #[napi]
+    pub async fn authenticate_player(
+        steam_id64: BigInt,
+        ticket: Buffer,
+        timeout_seconds: Option<u32>,
+        signal: Option<AbortSignal>,
+    ) -> Result<ServerPlayer> {
+        crate::panic::guard_async(crate::abort::abortable(signal, async move {
+            let steam_id = SteamId::from_raw(steam_id64.get_u64().1);
+            let rx = crate::server::begin_player_session(steam_id, &ticket)?;
+
+            let timeout_seconds = u64::from(timeout_seconds.unwrap_or(10));
+            let result =
+                tokio::time::timeout(std::time::Duration::from_secs(timeout_seconds), rx).await;
+
+            match result {
+                Ok(Ok(Ok(_))) => crate::server::get_player(steam_id)
+                    .map(ServerPlayer::from)
+                    .ok_or_else(|| {
+                        SteamworksError::not_found("The player has already left.").into()
+                    }),
+                Ok(Ok(Err(reason))) => Err(SteamworksError::AuthFailed(reason).into()),
+                Ok(Err(e)) => Err(SteamworksError::from(e).into()),
+                Err(_) => {
+                    crate::server::end_player_session(steam_id)?;
+                    Err(
+                        SteamworksError::timeout("Steam didn't validate the ticket in time.")
+                            .into(),
+                    )
+                }
+            }
+        }))
+        .await
+    }
+
+    /// Ends the player's auth session and removes them from the player list.
+    /// @returns true if the player was on the list
+    ///
+    /// {@link https://partner.steamgames.com/doc/api/ISteamGameServer#EndAuthSession}
+    #[napi]
+    pub fn player_left(steam_id64: BigInt) -> Result<bool> {
+        crate::panic::guard(|| {
+            Ok(crate::server::end_player_session(SteamId::from_raw(
+                steam_id64.get_u64().1,
+            ))?)
+        })
+    }
+
+    /// Updates the name and score shown for the player in the server browser.
+    /// @returns false if the player isn't on the list
+    ///
+    /// {@link https://partner.steamgames.com/doc/api/ISteamGameServer#BUpdateUserData}
+    #[napi]
+    pub fn update_player(steam_id64: BigInt, name: String, score: u32) -> Result<bool> {
+        crate::panic::guard(|| {
+            let server = raw_server()?;
+            let steam_id = SteamId::from_raw(steam_id64.get_u64().1);
+            let c_name = c_string(name.clone())?;
+
+            if !crate::server::update_player(steam_id, |player| {
+                player.name = Some(name);
+                player.score = score;
+            }) {
+                return Ok(false);
+            }
+
+            Ok(unsafe {
+                steamworks::sys::SteamAPI_ISteamGameServer_BUpdateUserData(
+                    server,
+                    steam_id.raw(),
+                    c_name.as_ptr(),
+                    score,
+                )
+            })
+        })
+    }
+
+    #[napi]
+    pub fn get_player(steam_id64: BigInt) -> Result<Option<ServerPlayer>> {
+        crate::panic::guard(|| {
+            crate::server::get_server()?;
+            Ok(
+                crate::server::get_player(SteamId::from_raw(steam_id64.get_u64().1))
+                    .map(ServerPlayer::from),
+            )
+        })
+    }
+
+    #[napi]
+    pub fn get_players() -> Result<Vec<ServerPlayer>> {
+        crate::panic::guard(|| {
+            crate::server::get_server()?;
+            Ok(crate::server::get_players()
+                .into_iter()
+                .map(ServerPlayer::from)
+                .collect())
+        })
+    }
+}
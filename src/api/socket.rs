@@ -2,9 +2,10 @@
 pub mod socket {
     use napi::bindgen_prelude::{BigInt, Buffer};
     use napi_derive::napi;
-    use steamworks::{
-        self, networking_sockets::NetConnection, networking_types::NetworkingIdentity,
-    };
+    use steamworks::{self, networking_types::NetworkingIdentity};
+
+    use crate::error::{ErrorCode, Result, SteamworksError};
+    use crate::resources::SharedConnection;
 
     #[napi]
     pub const UNRELIABLE: i32 = steamworks::networking_types::SendFlags::UNRELIABLE.bits();
@@ -32,64 +33,89 @@ pub mod socket {
 
     #[napi]
     pub struct Socket {
-        connection: NetConnection,
+        connection: SharedConnection,
     }
 
     #[napi]
-    pub fn connect_p2p(host: BigInt, port: i32) -> Result<Socket, napi::Error> {
-        let client = crate::client::get_client().unwrap();
-        client
-            .networking_sockets()
-            .connect_p2p(
-                NetworkingIdentity::new_steam_id(steamworks::SteamId::from_raw(host.get_u64().1)),
-                port,
-                vec![],
-            )
-            .map_err(|_| napi::Error::from_reason("Failed to connect..."))
-            .map(|connection| Socket { connection })
+    pub fn connect_p2p(host: BigInt, port: i32) -> Result<Socket> {
+        crate::panic::guard(|| {
+            let client = crate::client::get_client()?;
+            client
+                .networking_sockets()
+                .connect_p2p(
+                    NetworkingIdentity::new_steam_id(steamworks::SteamId::from_raw(host.get_u64().1)),
+                    port,
+                    vec![],
+                )
+                .map_err(|_| SteamworksError::failed("Failed to connect...").into())
+                .map(|connection| Socket {
+                    connection: crate::resources::track_socket(connection),
+                })
+        })
+    }
+
+    fn closed() -> napi::Error<ErrorCode> {
+        napi::Error::new(ErrorCode::InvalidState, "The socket is closed")
     }
 
     #[napi]
     impl Socket {
         #[napi]
-        pub fn send_message(&self, data: &[u8], send_flags: i32) -> Result<(), napi::Error> {
-            match self.connection.send_message(data, steamworks::networking_types::SendFlags::from_bits_truncate(send_flags)) {
-                Ok(_) => { Ok(()) }
-                Err(e) => {
-                    match e {
-                        steamworks::SteamError::InvalidParameter => {
-                            Err(napi::Error::from_reason("Message is too big"))
-                        },
-                        steamworks::SteamError::InvalidState => {
-                            Err(napi::Error::from_reason("Connection is not in a valid state to send messages"))
-                        },
-                        steamworks::SteamError::NoConnection => {
-                            Err(napi::Error::from_reason("No connection exists"))
-                        },
-                        steamworks::SteamError::Ignored => {
-                            Err(napi::Error::from_reason("NO_DELAY flag was set and the message was dropped because we were not ready to send it"))
-                        },
-                        steamworks::SteamError::LimitExceeded => {
-                            Err(napi::Error::from_reason("Too much data was in queue to be sent"))
-                        }
-                        _ => {
-                            Err(napi::Error::from_reason("An unknown error occurred while sending message"))
+        pub fn send_message(&self, data: &[u8], send_flags: i32) -> Result<()> {
+            crate::panic::guard(|| {
+                let connection = self.connection.lock().unwrap();
+                let connection = connection.as_ref().ok_or_else(closed)?;
+                match connection.send_message(data, steamworks::networking_types::SendFlags::from_bits_truncate(send_flags)) {
+                    Ok(_) => { Ok(()) }
+                    Err(e) => {
+                        match e {
+                            steamworks::SteamError::InvalidParameter => {
+                                Err(napi::Error::new(ErrorCode::from(&e), "Message is too big"))
+                            },
+                            steamworks::SteamError::InvalidState => {
+                                Err(napi::Error::new(ErrorCode::from(&e), "Connection is not in a valid state to send messages"))
+                            },
+                            steamworks::SteamError::NoConnection => {
+                                Err(napi::Error::new(ErrorCode::from(&e), "No connection exists"))
+                            },
+                            steamworks::SteamError::Ignored => {
+                                Err(napi::Error::new(ErrorCode::from(&e), "NO_DELAY flag was set and the message was dropped because we were not ready to send it"))
+                            },
+                            steamworks::SteamError::LimitExceeded => {
+                                Err(napi::Error::new(ErrorCode::from(&e), "Too much data was in queue to be sent"))
+                            }
+                            _ => {
+                                Err(napi::Error::new(ErrorCode::from(&e), "An unknown error occurred while sending message"))
+                            }
                         }
                     }
                 }
-            }
+            })
         }
 
         /// Please poll this method regularly to receive messages.
         #[napi]
-        pub fn receive_message(&mut self, max_message: u32) -> Result<Vec<Buffer>, napi::Error> {
-            let mut buffers = Vec::new();
-            for a in self.connection.receive_messages(max_message as usize).map_err(|_| {
-                napi::Error::from_reason("Invalid handle")
-            })? {
-                buffers.push(a.data().into());
-            }
-            Ok(buffers)
+        pub fn receive_message(&mut self, max_message: u32) -> Result<Vec<Buffer>> {
+            crate::panic::guard(|| {
+                let mut connection = self.connection.lock().unwrap();
+                let connection = connection.as_mut().ok_or_else(closed)?;
+                let mut buffers = Vec::new();
+                for a in connection.receive_messages(max_message as usize).map_err(|_| {
+                    SteamworksError::invalid_parameter("Invalid handle")
+                })? {
+                    buffers.push(a.data().into());
+                }
+                Ok(buffers)
+            })
+        }
+
+        /// @returns false if the socket was already closed, e.g. by `shutdown`
+        #[napi]
+        pub fn close(&self) -> Result<bool> {
+            crate::panic::guard(|| {
+                // Dropping the connection closes it.
+                Ok(self.connection.lock().unwrap().take().is_some())
+            })
         }
 
         
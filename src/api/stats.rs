@@ -2,30 +2,415 @@ use napi_derive::napi;
 
 #[napi]
 pub mod stats {
+    use std::time::Duration;
+
+    use napi::bindgen_prelude::BigInt;
+    use tokio::sync::oneshot;
+
+    use crate::abort::AbortSignal;
+    use crate::error::{ErrorCode, Result, SteamworksError};
+
+    /// Steam keeps at most this many days of global stat history.
+    const MAX_HISTORY_DAYS: u32 = 60;
+
+    /// How a stat is declared in the app's Steamworks settings.
+    #[napi]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum StatType {
+        Int,
+        Float,
+        /// A float averaged over time, written with `updateAvgRateStat`
+        AvgRate,
+    }
+
+    #[napi(object)]
+    pub struct StatValue {
+        pub name: String,
+        /// Steam can't tell an `AvgRate` stat from a `Float` one, both come back as `Float` there
+        #[napi(js_name = "type")]
+        pub stat_type: StatType,
+        pub value: f64,
+    }
+
+    /// The error for a stat read or written with the wrong type.
+    pub(crate) fn mismatch(name: &str, stat_type: StatType) -> napi::Error<ErrorCode> {
+        let accessors = match stat_type {
+            StatType::Int => "getInt and setInt",
+            StatType::Float => "getFloat and setFloat",
+            StatType::AvgRate => "getFloat and updateAvgRateStat",
+        };
+        napi::Error::new(
+            ErrorCode::InvalidParameter,
+            format!(
+                "{} is a {:?} stat, use {} for it",
+                name, stat_type, accessors
+            ),
+        )
+    }
+
+    /// @returns null if there's no such stat
+    /// @throws INVALID_PARAMETER if it isn't an int stat
+    /// @throws NOT_FOUND if it isn't in the schema
+    #[napi]
+    pub fn get_int(name: String) -> Result<Option<i32>> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            crate::schema::check_stat(&name, &[StatType::Int])?;
+            if let Ok(value) = backend.get_stat_i32(&name) {
+                return Ok(Some(value));
+            }
+            match backend.stat_type(&name) {
+                Some(stat_type) => Err(mismatch(&name, stat_type)),
+                None => Ok(None),
+            }
+        })
+    }
+
+    /// @returns false if there's no such stat
+    /// @throws INVALID_PARAMETER if it isn't an int stat
+    /// @throws NOT_FOUND if it isn't in the schema
+    #[napi]
+    pub fn set_int(name: String, value: i32) -> Result<bool> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            crate::schema::check_stat(&name, &[StatType::Int])?;
+            let old = backend.get_stat_i32(&name);
+            if backend.set_stat_i32(&name, value).is_ok() {
+                crate::journal::int_written(&name, value);
+                if let Ok(old) = old {
+                    crate::progress::stat_changed(&*backend, &name, old.into(), value.into());
+                }
+                return Ok(true);
+            }
+            match backend.stat_type(&name) {
+                Some(StatType::Int) | None => Ok(false),
+                Some(stat_type) => Err(mismatch(&name, stat_type)),
+            }
+        })
+    }
+
+    /// @returns null if there's no such stat
+    /// @throws INVALID_PARAMETER if it's an int stat
+    /// @throws NOT_FOUND if it isn't in the schema
+    #[napi]
+    pub fn get_float(name: String) -> Result<Option<f64>> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            crate::schema::check_stat(&name, &[StatType::Float, StatType::AvgRate])?;
+            if let Ok(value) = backend.get_stat_f32(&name) {
+                return Ok(Some(f64::from(value)));
+            }
+            match backend.stat_type(&name) {
+                Some(stat_type) => Err(mismatch(&name, stat_type)),
+                None => Ok(None),
+            }
+        })
+    }
+
+    /// @returns false if there's no such stat
+    /// @throws INVALID_PARAMETER if it isn't a float stat
+    /// @throws NOT_FOUND if it isn't in the schema
+    #[napi]
+    pub fn set_float(name: String, value: f64) -> Result<bool> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            crate::schema::check_stat(&name, &[StatType::Float])?;
+            let old = backend.get_stat_f32(&name);
+            if backend.set_stat_f32(&name, value as f32).is_ok() {
+                crate::journal::float_written(&name, value as f32);
+                if let Ok(old) = old {
+                    crate::progress::stat_changed(&*backend, &name, old.into(), value);
+                }
+                return Ok(true);
+            }
+            match backend.stat_type(&name) {
+                Some(StatType::Float) | None => Ok(false),
+                Some(stat_type) => Err(mismatch(&name, stat_type)),
+            }
+        })
+    }
+
+    /// Adds a session to an average rate stat, e.g. the points earned per hour.
+    /// Sessions aren't kept in `InitOptions.statJournal`, replaying one could count it twice.
+    ///
+    /// @param countThisSession - What was counted during the session, e.g. the points earned
+    /// @param sessionLength - How long the session was, in the unit the stat's window is set in
+    /// @returns false if there's no such stat, or it isn't an average rate stat
+    /// @throws NOT_FOUND if it isn't in the schema
+    ///
+    /// {@link https://partner.steamgames.com/doc/api/ISteamUserStats#UpdateAvgRateStat}
+    #[napi]
+    pub fn update_avg_rate_stat(
+        name: String,
+        count_this_session: f64,
+        session_length: f64,
+    ) -> Result<bool> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            crate::schema::check_stat(&name, &[StatType::AvgRate])?;
+            Ok(backend
+                .update_avg_rate_stat(&name, count_this_session as f32, session_length)
+                .is_ok())
+        })
+    }
+
+    /// Reads a stat whatever its type.
+    /// @throws NOT_FOUND if there's no such stat
+    #[napi]
+    pub fn get(name: String) -> Result<StatValue> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            crate::schema::check_stat(&name, &[])?;
+            let stat_type = backend
+                .stat_type(&name)
+                .ok_or_else(|| SteamworksError::unknown_stat(&name))?;
+            let value = match stat_type {
+                StatType::Int => backend.get_stat_i32(&name).map(f64::from),
+                StatType::Float | StatType::AvgRate => backend.get_stat_f32(&name).map(f64::from),
+            }
+            .map_err(|()| SteamworksError::unknown_stat(&name))?;
+            Ok(StatValue {
+                name,
+                stat_type,
+                value,
+            })
+        })
+    }
+
+    /// @returns false if Steam couldn't store the stats, `InitOptions.statJournal` keeps them
+    /// for later then
+    #[napi]
+    pub fn store() -> Result<bool> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            Ok(crate::journal::store(&*backend).is_ok())
+        })
+    }
+
+    #[napi]
+    pub fn reset_all(achievements_too: bool) -> Result<bool> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            let reset = backend.reset_all_stats(achievements_too).is_ok();
+            if reset {
+                crate::journal::clear();
+            }
+            Ok(reset)
+        })
+    }
+
+    #[napi(object)]
+    pub struct StatRule {
+        pub stat: String,
+        /// The stat value the achievement unlocks at
+        pub threshold: u32,
+        pub achievement: String,
+        /// The stat values to show the progress at. Default value is every quarter of `threshold`.
+        pub progress_milestones: Option<Vec<u32>>,
+    }
+
+    /// Unlocks an achievement once a stat reaches a threshold. Every `setInt`, `setFloat` and
+    /// committed `transaction` checks the rules on the stat, shows the progress toast at the
+    /// milestones it went past, and unlocks the achievements it completed. The unlocks are
+    /// stored together a second later, or when the client shuts down.
+    ///
+    /// The rule is checked right away too, for a stat that's already there. It replaces a rule or
+    /// `achievement.bindProgress` binding between the same stat and achievement.
+    ///
+    /// @throws NOT_FOUND if there's no such stat or achievement
+    /// @throws INVALID_PARAMETER if `threshold` is 0
+    #[napi]
+    pub fn define_rule(rule: StatRule) -> Result<()> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            if rule.threshold == 0 {
+                return Err(napi::Error::new(
+                    ErrorCode::InvalidParameter,
+                    "The rule's threshold has to be above 0",
+                ));
+            }
+            crate::schema::check_stat(&rule.stat, &[])?;
+            crate::schema::check_achievement(&rule.achievement)?;
+            let stat_type = backend
+                .stat_type(&rule.stat)
+                .ok_or_else(|| SteamworksError::unknown_stat(&rule.stat))?;
+            if backend.get_achievement(&rule.achievement).is_err() {
+                return Err(SteamworksError::unknown_achievement(&rule.achievement).into());
+            }
+
+            let milestones = rule
+                .progress_milestones
+                .unwrap_or_else(|| crate::progress::default_milestones(rule.threshold));
+            crate::progress::bind(
+                &rule.stat,
+                &rule.achievement,
+                rule.threshold,
+                milestones,
+                true,
+            );
+            let value = match stat_type {
+                StatType::Int => backend.get_stat_i32(&rule.stat).map(f64::from),
+                StatType::Float | StatType::AvgRate => {
+                    backend.get_stat_f32(&rule.stat).map(f64::from)
+                }
+            };
+            if let Ok(value) = value {
+                crate::progress::stat_changed(&*backend, &rule.stat, value, value);
+            }
+            Ok(())
+        })
+    }
+
+    /// Same as `achievement.unbindProgress`.
+    /// @returns false if there was no rule between the stat and achievement
+    #[napi]
+    pub fn remove_rule(stat: String, achievement: String) -> Result<bool> {
+        crate::panic::guard(|| Ok(crate::progress::unbind(&stat, &achievement)))
+    }
+
+    /// Fetches another user's stats and achievements, which `getUserStatInt`, `getUserStatFloat`
+    /// and `achievement.getUserAchievement` read. Resolves once Steam posted `UserStatsReceived`
+    /// for the user.
+    ///
+    /// @param timeoutSeconds - The number of seconds to wait for Steam. Default value is 10 seconds.
+    /// @throws if Steam has no stats for the user, e.g. when they don't own the app
+    ///
+    /// {@link https://partner.steamgames.com/doc/api/ISteamUserStats#RequestUserStats}
+    #[napi]
+    pub async fn request_user_stats(
+        steam_id: BigInt,
+        timeout_seconds: Option<u32>,
+        signal: Option<AbortSignal>,
+    ) -> Result<()> {
+        crate::panic::guard_async(crate::abort::abortable(signal, async move {
+            let backend = crate::client::get_backend()?;
+            let steam_id = steamworks::SteamId::from_raw(steam_id.get_u64().1);
+
+            let (tx, rx) = oneshot::channel();
+            let _waiting = backend.request_user_stats_for(
+                steam_id,
+                Box::new(|result| {
+                    let _ = tx.send(result);
+                }),
+            )?;
+
+            let timeout = Duration::from_secs(u64::from(timeout_seconds.unwrap_or(10)));
+            crate::dispatch::receive(rx, timeout, "the user's stats")
+                .await?
+                .map_err(SteamworksError::from)?;
+            Ok(())
+        }))
+        .await
+    }
+
+    /// @returns null if there's no such int stat or the user's stats weren't requested yet
+    /// @throws NOT_FOUND if it isn't in the schema
+    #[napi]
+    pub fn get_user_stat_int(steam_id: BigInt, name: String) -> Result<Option<i32>> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            crate::schema::check_stat(&name, &[StatType::Int])?;
+            let steam_id = steamworks::SteamId::from_raw(steam_id.get_u64().1);
+            Ok(backend.user_stat_i32(steam_id, &name).ok())
+        })
+    }
+
+    /// @returns null if there's no such float stat or the user's stats weren't requested yet
+    /// @throws NOT_FOUND if it isn't in the schema
+    #[napi]
+    pub fn get_user_stat_float(steam_id: BigInt, name: String) -> Result<Option<f64>> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            crate::schema::check_stat(&name, &[StatType::Float, StatType::AvgRate])?;
+            let steam_id = steamworks::SteamId::from_raw(steam_id.get_u64().1);
+            Ok(backend.user_stat_f32(steam_id, &name).ok().map(f64::from))
+        })
+    }
+
+    /// Fetches the stats totalled over every player, which the `getGlobal*` functions read.
+    /// Only stats flagged as aggregated in the app's Steamworks settings are included.
+    ///
+    /// @param historyDays - How many days of daily totals to fetch as well, up to 60. Default value is 0.
+    /// @param timeoutSeconds - The number of seconds to wait for Steam. Default value is 10 seconds.
+    ///
+    /// {@link https://partner.steamgames.com/doc/api/ISteamUserStats#RequestGlobalStats}
+    #[napi]
+    pub async fn request_global_stats(
+        history_days: Option<u32>,
+        timeout_seconds: Option<u32>,
+        signal: Option<AbortSignal>,
+    ) -> Result<()> {
+        crate::panic::guard_async(crate::abort::abortable(signal, async move {
+            let backend = crate::client::get_backend()?;
+
+            let (tx, rx) = oneshot::channel();
+            let _waiting = backend.request_global_stats(
+                history_days.unwrap_or(0).min(MAX_HISTORY_DAYS),
+                Box::new(|result| {
+                    let _ = tx.send(result);
+                }),
+            )?;
+
+            let timeout = Duration::from_secs(u64::from(timeout_seconds.unwrap_or(10)));
+            crate::dispatch::receive(rx, timeout, "the global stats")
+                .await?
+                .map_err(SteamworksError::from)?;
+            Ok(())
+        }))
+        .await
+    }
+
+    /// @returns the total over every player, or null if it isn't an aggregated int stat or the
+    /// global stats weren't requested yet
+    /// @throws NOT_FOUND if it isn't in the schema
     #[napi]
-    pub fn get_int(name: String) -> Option<i32> {
-        let client = crate::client::get_client().unwrap();
-        client.user_stats().get_stat_i32(&name).ok()
+    pub fn get_global_int(name: String) -> Result<Option<BigInt>> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            crate::schema::check_stat(&name, &[StatType::Int])?;
+            Ok(backend.global_stat_i64(&name).map(BigInt::from))
+        })
     }
 
+    /// @returns the total over every player, or null if it isn't an aggregated float stat or the
+    /// global stats weren't requested yet
+    /// @throws NOT_FOUND if it isn't in the schema
     #[napi]
-    pub fn set_int(name: String, value: i32) -> bool {
-        let client = crate::client::get_client().unwrap();
-        client.user_stats().set_stat_i32(&name, value).is_ok()
+    pub fn get_global_float(name: String) -> Result<Option<f64>> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            crate::schema::check_stat(&name, &[StatType::Float, StatType::AvgRate])?;
+            Ok(backend.global_stat_f64(&name))
+        })
     }
 
+    /// @param days - Default value is every day that was requested
+    /// @returns the daily totals over every player, today first, or null if it isn't an
+    /// aggregated int stat or no history was requested
+    /// @throws NOT_FOUND if it isn't in the schema
     #[napi]
-    pub fn store() -> bool {
-        let client = crate::client::get_client().unwrap();
-        client.user_stats().store_stats().is_ok()
+    pub fn get_global_int_history(name: String, days: Option<u32>) -> Result<Option<Vec<BigInt>>> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            crate::schema::check_stat(&name, &[StatType::Int])?;
+            let days = days.unwrap_or(MAX_HISTORY_DAYS).min(MAX_HISTORY_DAYS) as usize;
+            Ok(backend
+                .global_stat_history_i64(&name, days)
+                .map(|history| history.into_iter().map(BigInt::from).collect()))
+        })
     }
 
+    /// @param days - Default value is every day that was requested
+    /// @returns the daily totals over every player, today first, or null if it isn't an
+    /// aggregated float stat or no history was requested
+    /// @throws NOT_FOUND if it isn't in the schema
     #[napi]
-    pub fn reset_all(achievements_too: bool) -> bool {
-        let client = crate::client::get_client().unwrap();
-        client
-            .user_stats()
-            .reset_all_stats(achievements_too)
-            .is_ok()
+    pub fn get_global_float_history(name: String, days: Option<u32>) -> Result<Option<Vec<f64>>> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            crate::schema::check_stat(&name, &[StatType::Float, StatType::AvgRate])?;
+            let days = days.unwrap_or(MAX_HISTORY_DAYS).min(MAX_HISTORY_DAYS) as usize;
+            Ok(backend.global_stat_history_f64(&name, days))
+        })
     }
 }
@@ -0,0 +1,281 @@
+use napi_derive::napi;
+
+#[napi]
+pub mod stats {
+    use std::sync::{Arc, Mutex};
+
+    use napi::bindgen_prelude::Function;
+
+    use crate::api::stats::stats::{mismatch, StatType};
+    use crate::backend::SteamBackend;
+    use crate::error::{ErrorCode, Result, SteamworksError};
+
+    #[napi]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum TransactionChange {
+        SetInt,
+        SetFloat,
+        Unlock,
+        Clear,
+    }
+
+    #[napi(object)]
+    pub struct TransactionItem {
+        /// The stat or achievement
+        pub name: String,
+        pub change: TransactionChange,
+        /// Whether the change went through locally, it's undone again if the transaction was
+        /// rolled back
+        pub applied: bool,
+    }
+
+    #[napi(object)]
+    pub struct TransactionResult {
+        /// Whether every change was applied and stored
+        pub committed: bool,
+        /// Whether the applied changes were undone because one of them, or the store, failed
+        pub rolled_back: bool,
+        /// In the order the changes were staged
+        pub items: Vec<TransactionItem>,
+    }
+
+    enum Change {
+        SetInt(i32),
+        SetFloat(f64),
+        Unlock,
+        Clear,
+    }
+
+    struct Staged {
+        name: String,
+        change: Change,
+    }
+
+    /// What restores the local state from before an applied change.
+    enum Undo {
+        Int(i32),
+        Float(f32),
+        Achievement(bool),
+    }
+
+    /// The changes staged so far, `None` once the staging callback returned.
+    type StagedChanges = Arc<Mutex<Option<Vec<Staged>>>>;
+
+    /// Collects the changes of a `transaction`, only usable inside its callback.
+    #[napi]
+    pub struct Transaction {
+        staged: StagedChanges,
+    }
+
+    impl Transaction {
+        fn stage(&self, name: String, change: Change) -> Result<()> {
+            let mut staged = self.staged.lock().unwrap();
+            let staged = staged.as_mut().ok_or_else(|| {
+                napi::Error::new(
+                    ErrorCode::InvalidState,
+                    "The transaction is over, changes can only be staged inside its callback",
+                )
+            })?;
+            staged.push(Staged { name, change });
+            Ok(())
+        }
+    }
+
+    fn check_stat(backend: &dyn SteamBackend, name: &str, expected: StatType) -> Result<()> {
+        crate::schema::check_stat(name, &[expected])?;
+        match backend.stat_type(name) {
+            Some(stat_type) if stat_type == expected => Ok(()),
+            Some(stat_type) => Err(mismatch(name, stat_type)),
+            None => Err(SteamworksError::unknown_stat(name).into()),
+        }
+    }
+
+    fn check_achievement(backend: &dyn SteamBackend, name: &str) -> Result<()> {
+        crate::schema::check_achievement(name)?;
+        match backend.get_achievement(name) {
+            Ok(_) => Ok(()),
+            Err(()) => Err(SteamworksError::unknown_achievement(name).into()),
+        }
+    }
+
+    #[napi]
+    impl Transaction {
+        /// @throws NOT_FOUND if there's no such stat
+        /// @throws INVALID_PARAMETER if it isn't an int stat
+        #[napi]
+        pub fn set_int(&self, name: String, value: i32) -> Result<()> {
+            crate::panic::guard(|| {
+                let backend = crate::client::get_backend()?;
+                check_stat(&*backend, &name, StatType::Int)?;
+                self.stage(name, Change::SetInt(value))
+            })
+        }
+
+        /// @throws NOT_FOUND if there's no such stat
+        /// @throws INVALID_PARAMETER if it isn't a float stat
+        #[napi]
+        pub fn set_float(&self, name: String, value: f64) -> Result<()> {
+            crate::panic::guard(|| {
+                let backend = crate::client::get_backend()?;
+                check_stat(&*backend, &name, StatType::Float)?;
+                self.stage(name, Change::SetFloat(value))
+            })
+        }
+
+        /// @throws NOT_FOUND if there's no such achievement
+        #[napi]
+        pub fn unlock(&self, achievement: String) -> Result<()> {
+            crate::panic::guard(|| {
+                let backend = crate::client::get_backend()?;
+                check_achievement(&*backend, &achievement)?;
+                self.stage(achievement, Change::Unlock)
+            })
+        }
+
+        /// @throws NOT_FOUND if there's no such achievement
+        #[napi]
+        pub fn clear(&self, achievement: String) -> Result<()> {
+            crate::panic::guard(|| {
+                let backend = crate::client::get_backend()?;
+                check_achievement(&*backend, &achievement)?;
+                self.stage(achievement, Change::Clear)
+            })
+        }
+    }
+
+    /// Applies a change, returning what undoes it.
+    fn apply(backend: &dyn SteamBackend, staged: &Staged) -> Option<Undo> {
+        let name = staged.name.as_str();
+        match staged.change {
+            Change::SetInt(value) => {
+                let old = backend.get_stat_i32(name).ok()?;
+                backend.set_stat_i32(name, value).ok()?;
+                Some(Undo::Int(old))
+            }
+            Change::SetFloat(value) => {
+                let old = backend.get_stat_f32(name).ok()?;
+                backend.set_stat_f32(name, value as f32).ok()?;
+                Some(Undo::Float(old))
+            }
+            Change::Unlock => {
+                let old = backend.get_achievement(name).ok()?;
+                backend.set_achievement(name).ok()?;
+                Some(Undo::Achievement(old))
+            }
+            Change::Clear => {
+                let old = backend.get_achievement(name).ok()?;
+                backend.clear_achievement(name).ok()?;
+                Some(Undo::Achievement(old))
+            }
+        }
+    }
+
+    fn roll_back(backend: &dyn SteamBackend, name: &str, undo: Undo) {
+        let undone = match undo {
+            Undo::Int(value) => backend.set_stat_i32(name, value),
+            Undo::Float(value) => backend.set_stat_f32(name, value),
+            Undo::Achievement(true) => backend.set_achievement(name),
+            Undo::Achievement(false) => backend.clear_achievement(name),
+        };
+        if undone.is_err() {
+            log_warn!("Failed to roll {} back", name);
+        }
+    }
+
+    /// Changes several stats and achievements at once, with a single `store` instead of one per
+    /// change like `achievement.activate` does, which Steam rate limits.
+    ///
+    /// `stage` gets a `Transaction` to stage the changes on, the names are checked right away.
+    /// Once it returns the changes are applied in order and stored. If one of them or the store
+    /// fails, the ones already applied are undone, so the local state is the same as before. A
+    /// transaction that's rolled back isn't kept in `InitOptions.statJournal` either, nor does it
+    /// touch what's in it.
+    ///
+    /// @throws whatever `stage` throws, nothing is applied then
+    #[napi]
+    pub fn transaction(
+        #[napi(ts_arg_type = "(tx: Transaction) => void")] stage: Function<'_, Transaction, ()>,
+    ) -> Result<TransactionResult> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+
+            let staged: StagedChanges = Arc::new(Mutex::new(Some(Vec::new())));
+            let staging = stage.call(Transaction {
+                staged: staged.clone(),
+            });
+            // Closed before anything else, the transaction may have escaped the callback.
+            let staged = staged.lock().unwrap().take().unwrap_or_default();
+            staging.map_err(SteamworksError::from)?;
+
+            let mut items = Vec::with_capacity(staged.len());
+            let mut applied = Vec::new();
+            for staged in &staged {
+                let undo = apply(&*backend, staged);
+                items.push(TransactionItem {
+                    name: staged.name.clone(),
+                    change: match staged.change {
+                        Change::SetInt(_) => TransactionChange::SetInt,
+                        Change::SetFloat(_) => TransactionChange::SetFloat,
+                        Change::Unlock => TransactionChange::Unlock,
+                        Change::Clear => TransactionChange::Clear,
+                    },
+                    applied: undo.is_some(),
+                });
+                if let Some(undo) = undo {
+                    applied.push((staged, undo));
+                }
+            }
+
+            // Stored around the journal, a rolled back transaction leaves it as it was.
+            let all_applied = applied.len() == staged.len();
+            let committed = all_applied && (staged.is_empty() || backend.store_stats().is_ok());
+            if !committed {
+                log_debug!("Rolling back a transaction of {} changes", staged.len());
+                for (staged, undo) in applied.into_iter().rev() {
+                    roll_back(&*backend, &staged.name, undo);
+                }
+                return Ok(TransactionResult {
+                    committed,
+                    rolled_back: true,
+                    items,
+                });
+            }
+
+            if !staged.is_empty() {
+                for (staged, _) in &applied {
+                    let name = staged.name.as_str();
+                    match staged.change {
+                        Change::SetInt(value) => crate::journal::int_written(name, value),
+                        Change::SetFloat(value) => {
+                            crate::journal::float_written(name, value as f32)
+                        }
+                        Change::Unlock => crate::journal::achievement_written(name, true),
+                        Change::Clear => crate::journal::achievement_written(name, false),
+                    }
+                }
+                // Stored along with every write since the last store.
+                crate::journal::stored();
+            }
+
+            for (staged, undo) in applied {
+                match (&staged.change, undo) {
+                    (Change::SetInt(value), Undo::Int(old)) => crate::progress::stat_changed(
+                        &*backend,
+                        &staged.name,
+                        old.into(),
+                        (*value).into(),
+                    ),
+                    (Change::SetFloat(value), Undo::Float(old)) => {
+                        crate::progress::stat_changed(&*backend, &staged.name, old.into(), *value)
+                    }
+                    _ => {}
+                }
+            }
+            Ok(TransactionResult {
+                committed,
+                rolled_back: false,
+                items,
+            })
+        })
+    }
+}
@@ -10,22 +10,31 @@ pub mod utils {
     use steamworks::GamepadTextInputMode as kGamepadTextInputMode;
     use tokio::sync::oneshot;
 
+    use crate::abort::AbortSignal;
+    use crate::error::{Result, SteamworksError};
+
     #[napi]
-    pub fn get_app_id() -> u32 {
-        let client = crate::client::get_client().unwrap();
-        client.utils().app_id().0
+    pub fn get_app_id() -> Result<u32> {
+        crate::panic::guard(|| {
+            let client = crate::client::get_client()?;
+            Ok(client.utils().app_id().0)
+        })
     }
 
     #[napi]
-    pub fn get_server_real_time() -> u32 {
-        let client = crate::client::get_client().unwrap();
-        client.utils().get_server_real_time()
+    pub fn get_server_real_time() -> Result<u32> {
+        crate::panic::guard(|| {
+            let client = crate::client::get_client()?;
+            Ok(client.utils().get_server_real_time())
+        })
     }
 
     #[napi]
-    pub fn is_steam_running_on_steam_deck() -> bool {
-        let client = crate::client::get_client().unwrap();
-        client.utils().is_steam_running_on_steam_deck()
+    pub fn is_steam_running_on_steam_deck() -> Result<bool> {
+        crate::panic::guard(|| {
+            let client = crate::client::get_client()?;
+            Ok(client.utils().is_steam_running_on_steam_deck())
+        })
     }
 
     #[napi]
@@ -51,7 +60,7 @@ pub mod utils {
     }
     impl<T> Drop for DropDetector<T> {
         fn drop(&mut self) {
-            println!("DropDetector dropped");
+            log_trace!("DropDetector dropped");
         }
     }
     impl<T> DerefMut for DropDetector<T> {
@@ -68,45 +77,50 @@ pub mod utils {
         description: String,
         max_characters: u32,
         existing_text: Option<String>,
-    ) -> Option<String> {
-        let client = crate::client::get_client().unwrap();
-
-        let (tx, rx) = oneshot::channel();
-        let mut tx = Some(tx);
-
-        let (opened, _cb) = client.utils().show_gamepad_text_input(
-            match input_mode {
-                GamepadTextInputMode::Normal => kGamepadTextInputMode::Normal,
-                GamepadTextInputMode::Password => kGamepadTextInputMode::Password,
-            },
-            match input_line_mode {
-                GamepadTextInputLineMode::SingleLine => kGamepadTextInputLineMode::SingleLine,
-                GamepadTextInputLineMode::MultipleLines => kGamepadTextInputLineMode::MultipleLines,
-            },
-            &description,
-            max_characters,
-            existing_text.as_deref(),
-            move |dismissed_data| {
-                println!("Gamepad text input dismissed");
-                if let Some(tx) = tx.take() {
-                    println!("Sending entered text back");
-                    let text = client
-                        .utils()
-                        .get_entered_gamepad_text_input(&dismissed_data);
-                    println!("Sending entered text back: {:#?}", text);
-                    tx.send(text).unwrap();
-                    println!("Sent entered text back");
-                }
-            },
-        );
-        println!("show_gamepad_text_input opened: {}", opened);
-        if opened {
-            let a = rx.await.ok().flatten();
-            println!("show_gamepad_text_input result: {:#?}", a);
-            a
-        } else {
-            None
-        }
+        signal: Option<AbortSignal>,
+    ) -> Result<Option<String>> {
+        crate::panic::guard_async(crate::abort::abortable(signal, async move {
+            let client = crate::client::get_client()?;
+
+            let (tx, rx) = oneshot::channel();
+            let mut tx = Some(tx);
+
+            let (opened, _cb) = client.utils().show_gamepad_text_input(
+                match input_mode {
+                    GamepadTextInputMode::Normal => kGamepadTextInputMode::Normal,
+                    GamepadTextInputMode::Password => kGamepadTextInputMode::Password,
+                },
+                match input_line_mode {
+                    GamepadTextInputLineMode::SingleLine => kGamepadTextInputLineMode::SingleLine,
+                    GamepadTextInputLineMode::MultipleLines => {
+                        kGamepadTextInputLineMode::MultipleLines
+                    }
+                },
+                &description,
+                max_characters,
+                existing_text.as_deref(),
+                move |dismissed_data| {
+                    log_debug!("Gamepad text input dismissed");
+                    if let Some(tx) = tx.take() {
+                        let text = client
+                            .utils()
+                            .get_entered_gamepad_text_input(&dismissed_data);
+                        // The text itself stays out of the logs, it can be a password.
+                        log_trace!("Sending entered text back, entered: {}", text.is_some());
+                        let _ = tx.send(text);
+                    }
+                },
+            );
+            log_debug!("show_gamepad_text_input opened: {}", opened);
+            if opened {
+                let a = crate::resources::until_shutdown(rx).await?.ok().flatten();
+                log_debug!("show_gamepad_text_input cancelled: {}", a.is_none());
+                Ok(a)
+            } else {
+                Ok(None)
+            }
+        }))
+        .await
     }
 
     #[napi]
@@ -125,38 +139,44 @@ pub mod utils {
         y: i32,
         width: i32,
         height: i32,
-    ) -> bool {
-        let client = crate::client::get_client().unwrap();
-
-        let (tx, rx) = oneshot::channel();
-        let mut tx = Some(tx);
-
-        let (opened, _cb) = client.utils().show_floating_gamepad_text_input(
-            match keyboard_mode {
-                FloatingGamepadTextInputMode::SingleLine => {
-                    kFloatingGamepadTextInputMode::SingleLine
-                }
-                FloatingGamepadTextInputMode::MultipleLines => {
-                    kFloatingGamepadTextInputMode::MultipleLines
-                }
-                FloatingGamepadTextInputMode::Email => kFloatingGamepadTextInputMode::Email,
-                FloatingGamepadTextInputMode::Numeric => kFloatingGamepadTextInputMode::Numeric,
-            },
-            x,
-            y,
-            width,
-            height,
-            move || {
-                if let Some(tx) = tx.take() {
-                    tx.send(true).unwrap();
-                }
-            },
-        );
-
-        if opened {
-            rx.await.unwrap()
-        } else {
-            false
-        }
+        signal: Option<AbortSignal>,
+    ) -> Result<bool> {
+        crate::panic::guard_async(crate::abort::abortable(signal, async move {
+            let client = crate::client::get_client()?;
+
+            let (tx, rx) = oneshot::channel();
+            let mut tx = Some(tx);
+
+            let (opened, _cb) = client.utils().show_floating_gamepad_text_input(
+                match keyboard_mode {
+                    FloatingGamepadTextInputMode::SingleLine => {
+                        kFloatingGamepadTextInputMode::SingleLine
+                    }
+                    FloatingGamepadTextInputMode::MultipleLines => {
+                        kFloatingGamepadTextInputMode::MultipleLines
+                    }
+                    FloatingGamepadTextInputMode::Email => kFloatingGamepadTextInputMode::Email,
+                    FloatingGamepadTextInputMode::Numeric => kFloatingGamepadTextInputMode::Numeric,
+                },
+                x,
+                y,
+                width,
+                height,
+                move || {
+                    if let Some(tx) = tx.take() {
+                        let _ = tx.send(true);
+                    }
+                },
+            );
+
+            if opened {
+                Ok(crate::resources::until_shutdown(rx)
+                    .await?
+                    .map_err(SteamworksError::from)?)
+            } else {
+                Ok(false)
+            }
+        }))
+        .await
     }
 }
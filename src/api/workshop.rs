@@ -2,12 +2,15 @@ use napi_derive::napi;
 
 #[napi]
 pub mod workshop {
-    use napi::bindgen_prelude::{BigInt, Error};
+    use napi::bindgen_prelude::BigInt;
     use napi::threadsafe_function::ThreadsafeFunctionCallMode;
     use std::path::Path;
-    use steamworks::{FileType, PublishedFileId, UpdateHandle};
+    use steamworks::{PublishedFileId, UpdateHandle};
     use tokio::sync::oneshot;
 
+    use crate::abort::AbortSignal;
+    use crate::error::{ErrorCode, Result, SteamworksError};
+
     #[napi(object)]
     pub struct UgcResult {
         pub item_id: BigInt,
@@ -140,28 +143,37 @@ pub mod workshop {
     }
 
     #[napi]
-    pub async fn create_item(app_id: Option<u32>) -> Result<UgcResult, Error> {
-        let client = crate::client::get_client().unwrap();
-        let app_id = app_id
-            .map(steamworks::AppId)
-            .unwrap_or_else(|| client.utils().app_id());
-
-        let (tx, rx) = oneshot::channel();
-
-        client
-            .ugc()
-            .create_item(app_id, FileType::Community, |result| {
-                tx.send(result).unwrap();
-            });
-
-        let result = rx.await.unwrap();
-        match result {
-            Ok((item_id, needs_to_accept_agreement)) => Ok(UgcResult {
-                item_id: BigInt::from(item_id.0),
-                needs_to_accept_agreement,
-            }),
-            Err(e) => Err(Error::from_reason(e.to_string())),
-        }
+    pub async fn create_item(
+        app_id: Option<u32>,
+        signal: Option<AbortSignal>,
+    ) -> Result<UgcResult> {
+        crate::panic::guard_async(crate::abort::abortable(signal, async move {
+            let backend = crate::client::get_backend()?;
+            let app_id = app_id
+                .map(steamworks::AppId)
+                .unwrap_or_else(|| backend.app_id());
+
+            let (tx, rx) = oneshot::channel();
+
+            backend.create_item(
+                app_id,
+                Box::new(|result| {
+                    let _ = tx.send(result);
+                }),
+            );
+
+            let result = crate::resources::until_shutdown(rx)
+                .await?
+                .map_err(SteamworksError::from)?;
+            match result {
+                Ok((item_id, needs_to_accept_agreement)) => Ok(UgcResult {
+                    item_id: BigInt::from(item_id.0),
+                    needs_to_accept_agreement,
+                }),
+                Err(e) => Err(SteamworksError::from(e).into()),
+            }
+        }))
+        .await
     }
 
     #[napi]
@@ -169,33 +181,38 @@ pub mod workshop {
         item_id: BigInt,
         update_details: UgcUpdate,
         app_id: Option<u32>,
-    ) -> Result<UgcResult, Error> {
-        let client = crate::client::get_client().unwrap();
-
-        let app_id = app_id
-            .map(steamworks::AppId)
-            .unwrap_or_else(|| client.utils().app_id());
-
-        let (tx, rx) = oneshot::channel();
-
-        {
-            let update_handle = client
-                .ugc()
-                .start_item_update(app_id, PublishedFileId(item_id.get_u64().1));
-
-            update_details.submit(update_handle, |result| {
-                tx.send(result).unwrap();
-            });
-        };
-
-        let result = rx.await.unwrap();
-        match result {
-            Ok((item_id, needs_to_accept_agreement)) => Ok(UgcResult {
-                item_id: BigInt::from(item_id.0),
-                needs_to_accept_agreement,
-            }),
-            Err(e) => Err(Error::from_reason(e.to_string())),
-        }
+        signal: Option<AbortSignal>,
+    ) -> Result<UgcResult> {
+        crate::panic::guard_async(crate::abort::abortable(signal, async move {
+            let backend = crate::client::get_backend()?;
+
+            let app_id = app_id
+                .map(steamworks::AppId)
+                .unwrap_or_else(|| backend.app_id());
+
+            let (tx, rx) = oneshot::channel();
+
+            backend.submit_item_update(
+                app_id,
+                PublishedFileId(item_id.get_u64().1),
+                update_details,
+                Box::new(|result| {
+                    let _ = tx.send(result);
+                }),
+            );
+
+            let result = crate::resources::until_shutdown(rx)
+                .await?
+                .map_err(SteamworksError::from)?;
+            match result {
+                Ok((item_id, needs_to_accept_agreement)) => Ok(UgcResult {
+                    item_id: BigInt::from(item_id.0),
+                    needs_to_accept_agreement,
+                }),
+                Err(e) => Err(SteamworksError::from(e).into()),
+            }
+        }))
+        .await
     }
 
     #[napi]
@@ -216,116 +233,136 @@ pub mod workshop {
         >,
 
         progress_callback_interval_ms: Option<u32>,
-    ) {
-        let success_callback = success_callback
-            .build_threadsafe_function::<UgcResult>()
-            .callee_handled::<false>()
-            .max_queue_size::<0>()
-            .build_callback(|ctx| Ok(vec![ctx.value]))
-            .unwrap();
-        let error_callback = error_callback
-            .build_threadsafe_function::<Error>()
-            .callee_handled::<false>()
-            .max_queue_size::<0>()
-            .build_callback(|ctx| Ok(vec![ctx.value]))
-            .unwrap();
-
-        let client = crate::client::get_client().unwrap();
-
-        let app_id = app_id
-            .map(steamworks::AppId)
-            .unwrap_or_else(|| client.utils().app_id());
-
-        {
-            let update_handle = client
-                .ugc()
-                .start_item_update(app_id, PublishedFileId(item_id.get_u64().1));
-
-            let update_watch_handle = update_details.submit(update_handle, move |result| {
-                match result {
-                    Ok((item_id, needs_to_accept_agreement)) => success_callback.call(
-                        UgcResult {
-                            item_id: BigInt::from(item_id.0),
-                            needs_to_accept_agreement,
-                        },
-                        ThreadsafeFunctionCallMode::Blocking,
-                    ),
-                    Err(e) => error_callback.call(
-                        Error::from_reason(e.to_string()),
-                        ThreadsafeFunctionCallMode::Blocking,
-                    ),
-                };
-            });
-
-            if let Some(progress_callback) = progress_callback {
-                let progress_callback = progress_callback
-                    .build_threadsafe_function::<UpdateProgress>()
-                    .callee_handled::<false>()
-                    .max_queue_size::<0>()
-                    .build_callback(|ctx| Ok(vec![ctx.value]))
-                    .unwrap();
-
-                std::thread::spawn(move || loop {
-                    let (status, progress, total) = update_watch_handle.progress();
-                    let value = UpdateProgress {
-                        status: status.into(),
-                        progress: BigInt::from(progress),
-                        total: BigInt::from(total),
-                    };
-                    progress_callback.call(value, ThreadsafeFunctionCallMode::Blocking);
-                    match status {
-                        steamworks::UpdateStatus::Invalid => break,
-                        steamworks::UpdateStatus::CommittingChanges => break,
-                        _ => (),
-                    }
-                    std::thread::sleep(std::time::Duration::from_millis(
-                        progress_callback_interval_ms.unwrap_or(1000) as u64,
-                    ));
-                });
+    ) -> Result<()> {
+        crate::panic::guard(|| {
+            let success_callback = success_callback
+                .build_threadsafe_function::<UgcResult>()
+                .callee_handled::<false>()
+                .max_queue_size::<0>()
+                .build_callback(|ctx| Ok(vec![ctx.value]))
+                .map_err(SteamworksError::from)?;
+            let error_callback = error_callback
+                .build_threadsafe_function::<napi::Error<ErrorCode>>()
+                .callee_handled::<false>()
+                .max_queue_size::<0>()
+                .build_callback(|ctx| Ok(vec![ctx.value]))
+                .map_err(SteamworksError::from)?;
+
+            let backend = crate::client::get_backend()?;
+
+            let app_id = app_id
+                .map(steamworks::AppId)
+                .unwrap_or_else(|| backend.app_id());
+
+            {
+                let update_watch_handle = backend.submit_item_update(
+                    app_id,
+                    PublishedFileId(item_id.get_u64().1),
+                    update_details,
+                    Box::new(move |result| {
+                        match result {
+                            Ok((item_id, needs_to_accept_agreement)) => success_callback.call(
+                                UgcResult {
+                                    item_id: BigInt::from(item_id.0),
+                                    needs_to_accept_agreement,
+                                },
+                                ThreadsafeFunctionCallMode::Blocking,
+                            ),
+                            Err(e) => error_callback.call(
+                                SteamworksError::from(e).into(),
+                                ThreadsafeFunctionCallMode::Blocking,
+                            ),
+                        };
+                    }),
+                );
+
+                // Backends that can't report upload progress don't hand out a watch handle.
+                if let (Some(progress_callback), Some(update_watch_handle)) =
+                    (progress_callback, update_watch_handle)
+                {
+                    let progress_callback = progress_callback
+                        .build_threadsafe_function::<UpdateProgress>()
+                        .callee_handled::<false>()
+                        .max_queue_size::<0>()
+                        .build_callback(|ctx| Ok(vec![ctx.value]))
+                        .map_err(SteamworksError::from)?;
+
+                    std::thread::spawn(move || loop {
+                        let (status, progress, total) = update_watch_handle.progress();
+                        let value = UpdateProgress {
+                            status: status.into(),
+                            progress: BigInt::from(progress),
+                            total: BigInt::from(total),
+                        };
+                        progress_callback.call(value, ThreadsafeFunctionCallMode::Blocking);
+                        match status {
+                            steamworks::UpdateStatus::Invalid => break,
+                            steamworks::UpdateStatus::CommittingChanges => break,
+                            _ => (),
+                        }
+                        std::thread::sleep(std::time::Duration::from_millis(
+                            progress_callback_interval_ms.unwrap_or(1000) as u64,
+                        ));
+                    });
+                }
             }
-        };
+
+            Ok(())
+        })
     }
 
     /// Subscribe to a workshop item. It will be downloaded and installed as soon as possible.
     ///
     /// {@link https://partner.steamgames.com/doc/api/ISteamUGC#SubscribeItem}
     #[napi]
-    pub async fn subscribe(item_id: BigInt) -> Result<(), Error> {
-        let client = crate::client::get_client().unwrap();
-        let (tx, rx) = oneshot::channel();
-
-        client
-            .ugc()
-            .subscribe_item(PublishedFileId(item_id.get_u64().1), |result| {
-                tx.send(result).unwrap();
-            });
-
-        let result = rx.await.unwrap();
-        match result {
-            Ok(()) => Ok(()),
-            Err(e) => Err(Error::from_reason(e.to_string())),
-        }
+    pub async fn subscribe(item_id: BigInt, signal: Option<AbortSignal>) -> Result<()> {
+        crate::panic::guard_async(crate::abort::abortable(signal, async move {
+            let backend = crate::client::get_backend()?;
+            let (tx, rx) = oneshot::channel();
+
+            backend.subscribe_item(
+                PublishedFileId(item_id.get_u64().1),
+                Box::new(|result| {
+                    let _ = tx.send(result);
+                }),
+            );
+
+            let result = crate::resources::until_shutdown(rx)
+                .await?
+                .map_err(SteamworksError::from)?;
+            match result {
+                Ok(()) => Ok(()),
+                Err(e) => Err(SteamworksError::from(e).into()),
+            }
+        }))
+        .await
     }
 
     /// Unsubscribe from a workshop item. This will result in the item being removed after the game quits.
     ///
     /// {@link https://partner.steamgames.com/doc/api/ISteamUGC#UnsubscribeItem}
     #[napi]
-    pub async fn unsubscribe(item_id: BigInt) -> Result<(), Error> {
-        let client = crate::client::get_client().unwrap();
-        let (tx, rx) = oneshot::channel();
-
-        client
-            .ugc()
-            .unsubscribe_item(PublishedFileId(item_id.get_u64().1), |result| {
-                tx.send(result).unwrap();
-            });
-
-        let result = rx.await.unwrap();
-        match result {
-            Ok(()) => Ok(()),
-            Err(e) => Err(Error::from_reason(e.to_string())),
-        }
+    pub async fn unsubscribe(item_id: BigInt, signal: Option<AbortSignal>) -> Result<()> {
+        crate::panic::guard_async(crate::abort::abortable(signal, async move {
+            let backend = crate::client::get_backend()?;
+            let (tx, rx) = oneshot::channel();
+
+            backend.unsubscribe_item(
+                PublishedFileId(item_id.get_u64().1),
+                Box::new(|result| {
+                    let _ = tx.send(result);
+                }),
+            );
+
+            let result = crate::resources::until_shutdown(rx)
+                .await?
+                .map_err(SteamworksError::from)?;
+            match result {
+                Ok(()) => Ok(()),
+                Err(e) => Err(SteamworksError::from(e).into()),
+            }
+        }))
+        .await
     }
 
     /// Gets the current state of a workshop item on this client. States can be combined.
@@ -336,13 +373,13 @@ pub mod workshop {
     /// {@link https://partner.steamgames.com/doc/api/ISteamUGC#GetItemState}
     /// {@link https://partner.steamgames.com/doc/api/ISteamUGC#EItemState}
     #[napi]
-    pub fn state(item_id: BigInt) -> u32 {
-        let client = crate::client::get_client().unwrap();
-        let result = client
-            .ugc()
-            .item_state(PublishedFileId(item_id.get_u64().1));
+    pub fn state(item_id: BigInt) -> Result<u32> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            let result = backend.item_state(PublishedFileId(item_id.get_u64().1));
 
-        result.bits()
+            Ok(result.bits())
+        })
     }
 
     /// Gets info about currently installed content on the disc for workshop item.
@@ -351,20 +388,11 @@ pub mod workshop {
     ///
     /// {@link https://partner.steamgames.com/doc/api/ISteamUGC#GetItemInstallInfo}
     #[napi]
-    pub fn install_info(item_id: BigInt) -> Option<InstallInfo> {
-        let client = crate::client::get_client().unwrap();
-        let result = client
-            .ugc()
-            .item_install_info(PublishedFileId(item_id.get_u64().1));
-
-        match result {
-            Some(install_info) => Some(InstallInfo {
-                folder: install_info.folder,
-                size_on_disk: BigInt::from(install_info.size_on_disk),
-                timestamp: install_info.timestamp,
-            }),
-            None => None,
-        }
+    pub fn install_info(item_id: BigInt) -> Result<Option<InstallInfo>> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            Ok(backend.item_install_info(PublishedFileId(item_id.get_u64().1)))
+        })
     }
 
     /// Get info about a pending download of a workshop item.
@@ -373,15 +401,15 @@ pub mod workshop {
     ///
     /// {@link https://partner.steamgames.com/doc/api/ISteamUGC#GetItemDownloadInfo}
     #[napi]
-    pub fn download_info(item_id: BigInt) -> Option<DownloadInfo> {
-        let client = crate::client::get_client().unwrap();
-        let result = client
-            .ugc()
-            .item_download_info(PublishedFileId(item_id.get_u64().1));
-
-        result.map(|download_info| DownloadInfo {
-            current: BigInt::from(download_info.0),
-            total: BigInt::from(download_info.1),
+    pub fn download_info(item_id: BigInt) -> Result<Option<DownloadInfo>> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            let result = backend.item_download_info(PublishedFileId(item_id.get_u64().1));
+
+            Ok(result.map(|download_info| DownloadInfo {
+                current: BigInt::from(download_info.0),
+                total: BigInt::from(download_info.1),
+            }))
         })
     }
 
@@ -392,43 +420,51 @@ pub mod workshop {
     ///
     /// {@link https://partner.steamgames.com/doc/api/ISteamUGC#DownloadItem}
     #[napi]
-    pub fn download(item_id: BigInt, high_priority: bool) -> bool {
-        let client = crate::client::get_client().unwrap();
-        client
-            .ugc()
-            .download_item(PublishedFileId(item_id.get_u64().1), high_priority)
+    pub fn download(item_id: BigInt, high_priority: bool) -> Result<bool> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            Ok(backend.download_item(PublishedFileId(item_id.get_u64().1), high_priority))
+        })
     }
 
     /// Get all subscribed workshop items.
     /// @returns an array of subscribed workshop item ids
     #[napi]
-    pub fn get_subscribed_items() -> Vec<BigInt> {
-        let client = crate::client::get_client().unwrap();
-        let result = client.ugc().subscribed_items(false);
-
-        result
-            .iter()
-            .map(|item| BigInt::from(item.0))
-            .collect::<Vec<_>>()
+    pub fn get_subscribed_items() -> Result<Vec<BigInt>> {
+        crate::panic::guard(|| {
+            let backend = crate::client::get_backend()?;
+            let result = backend.subscribed_items();
+
+            Ok(result
+                .iter()
+                .map(|item| BigInt::from(item.0))
+                .collect::<Vec<_>>())
+        })
     }
 
     // Deletes an item
     // @returns true or false
     #[napi]
-    pub async fn delete_item(item_id: BigInt) -> Result<(), Error> {
-        let client = crate::client::get_client().unwrap();
-        let (tx, rx) = oneshot::channel();
-
-        client
-            .ugc()
-            .delete_item(PublishedFileId(item_id.get_u64().1), |result| {
-                tx.send(result).unwrap();
-            });
-
-        let result = rx.await.unwrap();
-        match result {
-            Ok(()) => Ok(()),
-            Err(e) => Err(Error::from_reason(e.to_string())),
-        }
+    pub async fn delete_item(item_id: BigInt, signal: Option<AbortSignal>) -> Result<()> {
+        crate::panic::guard_async(crate::abort::abortable(signal, async move {
+            let backend = crate::client::get_backend()?;
+            let (tx, rx) = oneshot::channel();
+
+            backend.delete_item(
+                PublishedFileId(item_id.get_u64().1),
+                Box::new(|result| {
+                    let _ = tx.send(result);
+                }),
+            );
+
+            let result = crate::resources::until_shutdown(rx)
+                .await?
+                .map_err(SteamworksError::from)?;
+            match result {
+                Ok(()) => Ok(()),
+                Err(e) => Err(SteamworksError::from(e).into()),
+            }
+        }))
+        .await
     }
 }
@@ -2,12 +2,14 @@ use napi_derive::napi;
 
 #[napi]
 pub mod workshop {
-    use napi::bindgen_prelude::{BigInt, Error};
+    use napi::bindgen_prelude::BigInt;
     use steamworks::{AccountId, PublishedFileId};
     use tokio::sync::oneshot;
 
+    use crate::abort::AbortSignal;
     use crate::api::localplayer::PlayerSteamId;
     use crate::api::workshop::workshop::UgcItemVisibility;
+    use crate::error::{Result, SteamworksError};
 
     #[napi]
     pub enum UGCQueryType {
@@ -185,7 +187,7 @@ pub mod workshop {
         }
     }
 
-    #[derive(Debug)]
+    #[derive(Debug, Default)]
     #[napi(object)]
     pub struct WorkshopItemStatistic {
         pub num_subscriptions: Option<BigInt>, //   0	gets the number of subscriptions.
@@ -284,7 +286,10 @@ pub mod workshop {
     }
 
     impl WorkshopItem {
-        fn from_query_results(results: &steamworks::QueryResults, index: u32) -> Option<Self> {
+        pub(crate) fn from_query_results(
+            results: &steamworks::QueryResults,
+            index: u32,
+        ) -> Option<Self> {
             results.get(index).map(|item| Self {
                 published_file_id: BigInt::from(item.published_file_id.0),
                 creator_app_id: item.creator_app_id.map(|id| id.0),
@@ -339,17 +344,6 @@ pub mod workshop {
         pub was_cached: bool,
     }
 
-    impl WorkshopItemsResult {
-        fn from_query_results(query_results: steamworks::QueryResults) -> Self {
-            Self {
-                items: (0..query_results.returned_results())
-                    .map(|i| WorkshopItem::from_query_results(&query_results, i))
-                    .collect(),
-                was_cached: query_results.was_cached(),
-            }
-        }
-    }
-
     #[derive(Debug)]
     #[napi(object)]
     pub struct WorkshopItemQueryConfig {
@@ -374,7 +368,7 @@ pub mod workshop {
         pub consumer: Option<u32>,
     }
 
-    fn handle_query_config(
+    pub(crate) fn handle_query_config(
         mut query_handle: steamworks::QueryHandle,
         query_config: Option<WorkshopItemQueryConfig>,
     ) -> steamworks::QueryHandle {
@@ -429,66 +423,65 @@ pub mod workshop {
     pub async fn get_item(
         item: BigInt,
         query_config: Option<WorkshopItemQueryConfig>,
-    ) -> Result<Option<WorkshopItem>, Error> {
-        let client = crate::client::get_client().unwrap();
-        let (tx, rx) = oneshot::channel();
-
-        {
-            let mut query_handle = client
-                .ugc()
-                .query_item(PublishedFileId(item.get_u64().1))
-                .map_err(|e| Error::from_reason(e.to_string()))?;
-
-            query_handle = handle_query_config(query_handle, query_config);
-
-            query_handle.fetch(|fetch_result| {
-                tx.send(
-                    fetch_result
-                        .map(|query_results| WorkshopItem::from_query_results(&query_results, 0)),
-                )
-                .unwrap();
-            });
-        }
+        signal: Option<AbortSignal>,
+    ) -> Result<Option<WorkshopItem>> {
+        crate::panic::guard_async(crate::abort::abortable(signal, async move {
+            let backend = crate::client::get_backend()?;
+            let (tx, rx) = oneshot::channel();
 
-        rx.await
-            .unwrap()
-            .map_err(|e| Error::from_reason(e.to_string()))
+            backend
+                .query_items(
+                    vec![PublishedFileId(item.get_u64().1)],
+                    query_config,
+                    Box::new(|fetch_result| {
+                        let _ = tx.send(
+                            fetch_result.map(|(items, _)| items.into_iter().next().flatten()),
+                        );
+                    }),
+                )
+                .map_err(SteamworksError::from)?;
+
+            Ok(crate::resources::until_shutdown(rx)
+                .await?
+                .map_err(SteamworksError::from)?
+                .map_err(SteamworksError::from)?)
+        }))
+        .await
     }
 
     #[napi]
     pub async fn get_items(
         items: Vec<BigInt>,
         query_config: Option<WorkshopItemQueryConfig>,
-    ) -> Result<WorkshopItemsResult, Error> {
-        let client = crate::client::get_client().unwrap();
-        let (tx, rx) = oneshot::channel();
+        signal: Option<AbortSignal>,
+    ) -> Result<WorkshopItemsResult> {
+        crate::panic::guard_async(crate::abort::abortable(signal, async move {
+            let backend = crate::client::get_backend()?;
+            let (tx, rx) = oneshot::channel();
 
-        {
-            let mut query_handle = client
-                .ugc()
+            backend
                 .query_items(
                     items
                         .iter()
                         .map(|id| PublishedFileId(id.get_u64().1))
                         .collect(),
-                )
-                .map_err(|e| Error::from_reason(e.to_string()))?;
-
-            query_handle = handle_query_config(query_handle, query_config);
-
-            query_handle.fetch(|fetch_result| {
-                tx.send(
-                    fetch_result.map(|query_results| {
-                        WorkshopItemsResult::from_query_results(query_results)
+                    query_config,
+                    Box::new(|fetch_result| {
+                        let _ =
+                            tx.send(fetch_result.map(|(items, was_cached)| WorkshopItemsResult {
+                                items,
+                                was_cached,
+                            }));
                     }),
                 )
-                .unwrap();
-            });
-        }
-
-        rx.await
-            .unwrap()
-            .map_err(|e| Error::from_reason(e.to_string()))
+                .map_err(SteamworksError::from)?;
+
+            Ok(crate::resources::until_shutdown(rx)
+                .await?
+                .map_err(SteamworksError::from)?
+                .map_err(SteamworksError::from)?)
+        }))
+        .await
     }
 
     #[napi]
@@ -499,38 +492,42 @@ pub mod workshop {
         creator_app_id: u32,
         consumer_app_id: u32,
         query_config: Option<WorkshopItemQueryConfig>,
-    ) -> Result<WorkshopPaginatedResult, Error> {
-        let client = crate::client::get_client().unwrap();
-        let (tx, rx) = oneshot::channel();
-
-        {
-            // Start configuring the query for all items
-            let mut query_handle = client
-                .ugc()
-                .query_all(
-                    query_type.into(),
-                    item_type.into(),
-                    steamworks::AppIDs::Both {
-                        creator: steamworks::AppId(creator_app_id),
-                        consumer: steamworks::AppId(consumer_app_id),
-                    },
-                    page,
-                )
-                .map_err(|e| Error::from_reason(e.to_string()))?;
+        signal: Option<AbortSignal>,
+    ) -> Result<WorkshopPaginatedResult> {
+        crate::panic::guard_async(crate::abort::abortable(signal, async move {
+            let client = crate::client::get_client()?;
+            let (tx, rx) = oneshot::channel();
+
+            {
+                // Start configuring the query for all items
+                let mut query_handle = client
+                    .ugc()
+                    .query_all(
+                        query_type.into(),
+                        item_type.into(),
+                        steamworks::AppIDs::Both {
+                            creator: steamworks::AppId(creator_app_id),
+                            consumer: steamworks::AppId(consumer_app_id),
+                        },
+                        page,
+                    )
+                    .map_err(|e| SteamworksError::failed(e.to_string()))?;
 
-            query_handle = handle_query_config(query_handle, query_config);
+                query_handle = handle_query_config(query_handle, query_config);
 
-            query_handle.fetch(|fetch_result| {
-                tx.send(fetch_result.map(|query_results| {
-                    WorkshopPaginatedResult::from_query_results(query_results)
-                }))
-                .unwrap();
-            });
-        }
+                query_handle.fetch(|fetch_result| {
+                    let _ = tx.send(fetch_result.map(|query_results| {
+                        WorkshopPaginatedResult::from_query_results(query_results)
+                    }));
+                });
+            }
 
-        rx.await
-            .unwrap()
-            .map_err(|e| Error::from_reason(e.to_string()))
+            Ok(crate::resources::until_shutdown(rx)
+                .await?
+                .map_err(SteamworksError::from)?
+                .map_err(SteamworksError::from)?)
+        }))
+        .await
     }
 
     #[napi]
@@ -542,39 +539,43 @@ pub mod workshop {
         sort_order: UserListOrder,
         app_ids: AppIDs,
         query_config: Option<WorkshopItemQueryConfig>,
-    ) -> Result<WorkshopPaginatedResult, Error> {
-        let client = crate::client::get_client().unwrap();
-        let (tx, rx) = oneshot::channel();
-
-        {
-            // Start configuring the query for user items
-            let mut query_handle = client
-                .ugc()
-                .query_user(
-                    AccountId::from_raw(account_id),
-                    list_type.into(),
-                    item_type.into(),
-                    sort_order.into(),
-                    steamworks::AppIDs::Both {
-                        creator: steamworks::AppId(app_ids.creator.unwrap_or(0)),
-                        consumer: steamworks::AppId(app_ids.consumer.unwrap_or(0)),
-                    },
-                    page,
-                )
-                .map_err(|e| Error::from_reason(e.to_string()))?;
+        signal: Option<AbortSignal>,
+    ) -> Result<WorkshopPaginatedResult> {
+        crate::panic::guard_async(crate::abort::abortable(signal, async move {
+            let client = crate::client::get_client()?;
+            let (tx, rx) = oneshot::channel();
+
+            {
+                // Start configuring the query for user items
+                let mut query_handle = client
+                    .ugc()
+                    .query_user(
+                        AccountId::from_raw(account_id),
+                        list_type.into(),
+                        item_type.into(),
+                        sort_order.into(),
+                        steamworks::AppIDs::Both {
+                            creator: steamworks::AppId(app_ids.creator.unwrap_or(0)),
+                            consumer: steamworks::AppId(app_ids.consumer.unwrap_or(0)),
+                        },
+                        page,
+                    )
+                    .map_err(|e| SteamworksError::failed(e.to_string()))?;
 
-            query_handle = handle_query_config(query_handle, query_config);
+                query_handle = handle_query_config(query_handle, query_config);
 
-            query_handle.fetch(|fetch_result| {
-                tx.send(fetch_result.map(|query_results| {
-                    WorkshopPaginatedResult::from_query_results(query_results)
-                }))
-                .unwrap();
-            });
-        }
+                query_handle.fetch(|fetch_result| {
+                    let _ = tx.send(fetch_result.map(|query_results| {
+                        WorkshopPaginatedResult::from_query_results(query_results)
+                    }));
+                });
+            }
 
-        rx.await
-            .unwrap()
-            .map_err(|e| Error::from_reason(e.to_string()))
+            Ok(crate::resources::until_shutdown(rx)
+                .await?
+                .map_err(SteamworksError::from)?
+                .map_err(SteamworksError::from)?)
+        }))
+        .await
     }
 }
@@ -0,0 +1,1695 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use napi::bindgen_prelude::BigInt;
+use serde_json::Value;
+use steamworks::networking_types::NetworkingIdentity;
+use steamworks::sys::EInputActionOrigin;
+use steamworks::{AppId, ItemState, LobbyId, PublishedFileId, SteamError, SteamId};
+
+use super::{BackendKind, Callback, SteamBackend};
+use crate::api::achievement::achievement::AchievementInfo;
+use crate::api::callback::callback::SteamCallback;
+use crate::api::friends::FriendInfo;
+use crate::api::input::input::MotionData;
+use crate::api::leaderboard::leaderboard::{
+    DisplayType, EntriesRequest, LeaderboardEntry, ScoreUploaded, SortMethod, UploadScoreMethod,
+};
+use crate::api::localplayer::PlayerSteamId;
+use crate::api::stats::stats::StatType;
+use crate::api::workshop::workshop::{InstallInfo, UgcUpdate};
+use crate::api::workshop_item::workshop::{
+    WorkshopItem, WorkshopItemQueryConfig, WorkshopItemStatistic,
+};
+use crate::dispatch::Subscription;
+use crate::error::SteamworksError;
+use crate::schema::Schema;
+
+/// Steam ID of the fake local user.
+const LOCAL_STEAM_ID: u64 = 76561197960265729;
+/// Lobby IDs are chat-type steam IDs in the public universe.
+const LOBBY_ID_BASE: u64 = 0x0186_0000_0000_0000;
+
+struct MockLobby {
+    lobby_type: steamworks::LobbyType,
+    max_members: u32,
+    owner: SteamId,
+    members: Vec<SteamId>,
+    joinable: bool,
+    data: BTreeMap<String, String>,
+    chat: Vec<Vec<u8>>,
+}
+
+struct MockItem {
+    app_id: AppId,
+    owner: SteamId,
+    title: String,
+    description: String,
+    tags: Vec<String>,
+    visibility: steamworks::PublishedFileVisibility,
+    time_created: u32,
+    time_updated: u32,
+    subscribed: bool,
+    installed: bool,
+}
+
+#[derive(Default)]
+struct MockController {
+    input_type: Option<steamworks::InputType>,
+    action_set: u64,
+    digital: HashMap<u64, bool>,
+    analog: HashMap<u64, (f32, f32)>,
+    vibration: (u16, u16),
+}
+
+enum MockStat {
+    Int(i32),
+    Float(f32),
+    /// Reads as `value` until the first session comes in, then as the total rate.
+    AvgRate {
+        value: f32,
+        count: f64,
+        length: f64,
+    },
+}
+
+impl MockStat {
+    fn stat_type(&self) -> StatType {
+        match self {
+            MockStat::Int(_) => StatType::Int,
+            MockStat::Float(_) => StatType::Float,
+            MockStat::AvgRate { .. } => StatType::AvgRate,
+        }
+    }
+}
+
+#[derive(Default)]
+struct MockAchievement {
+    unlock_time: Option<u32>,
+    display_name: Option<String>,
+    description: String,
+    hidden: bool,
+    /// `(width, height, rgba)`
+    icon: Option<(u32, u32, Vec<u8>)>,
+    /// The last `(current, max)` progress indicated.
+    progress: Option<(u32, u32)>,
+}
+
+struct MockScore {
+    steam_id: SteamId,
+    score: i32,
+    details: Vec<i32>,
+    ugc: Option<u64>,
+}
+
+struct MockLeaderboard {
+    name: String,
+    sort_method: SortMethod,
+    display_type: DisplayType,
+    /// Best first, ties in the order the scores came in.
+    scores: Vec<MockScore>,
+}
+
+impl MockLeaderboard {
+    fn is_better(&self, score: i32, than: i32) -> bool {
+        match self.sort_method {
+            SortMethod::Ascending => score < than,
+            SortMethod::Descending => score > than,
+        }
+    }
+
+    /// @returns the 1-based rank the score got
+    fn insert(&mut self, score: MockScore) -> i32 {
+        let index = self
+            .scores
+            .iter()
+            .position(|other| self.is_better(score.score, other.score))
+            .unwrap_or(self.scores.len());
+        self.scores.insert(index, score);
+        index as i32 + 1
+    }
+
+    fn rank(&self, steam_id: SteamId) -> Option<usize> {
+        self.scores.iter().position(|s| s.steam_id == steam_id)
+    }
+
+    fn entry(rank: usize, score: &MockScore) -> LeaderboardEntry {
+        LeaderboardEntry {
+            user: PlayerSteamId::from_steamid(score.steam_id),
+            global_rank: rank as i32 + 1,
+            score: score.score,
+            details: score.details.clone(),
+            ugc_handle: score.ugc.map(BigInt::from),
+        }
+    }
+}
+
+/// Another user's stats and achievements, readable once they were requested.
+#[derive(Default)]
+struct MockUser {
+    /// The stats the user never wrote read as 0, the way Steam has them.
+    stats: HashMap<String, f64>,
+    /// The unlock times of the achievements the user has.
+    achievements: HashMap<String, u32>,
+    received: bool,
+}
+
+enum MockGlobalStat {
+    Int { total: i64, history: Vec<i64> },
+    Float { total: f64, history: Vec<f64> },
+}
+
+/// Steam's answer on a ticket, held back while `hold_tickets` is set.
+enum TicketAnswer {
+    Session(Callback<Result<(), SteamError>>),
+    WebApi(Callback<Result<Vec<u8>, SteamError>>, Vec<u8>),
+}
+
+impl TicketAnswer {
+    fn send(self) {
+        match self {
+            TicketAnswer::Session(callback) => callback(Ok(())),
+            TicketAnswer::WebApi(callback, data) => callback(Ok(data)),
+        }
+    }
+}
+
+#[derive(Default)]
+struct MockState {
+    stats: HashMap<String, MockStat>,
+    achievements: BTreeMap<String, MockAchievement>,
+    achievement_percents: HashMap<String, f32>,
+    global_percentages_received: bool,
+    global_stats: HashMap<String, MockGlobalStat>,
+    /// The history days asked for, `None` until the global stats were requested.
+    global_history_days: Option<usize>,
+    users: HashMap<u64, MockUser>,
+    /// The names `friends.requestUserInformation` gives, by Steam ID.
+    personas: HashMap<u64, String>,
+    /// How many times the stats were stored.
+    stores: u32,
+    store_fails: bool,
+    cloud_enabled_for_app: bool,
+    files: BTreeMap<String, Vec<u8>>,
+    lobbies: BTreeMap<u64, MockLobby>,
+    next_lobby: u64,
+    items: BTreeMap<u64, MockItem>,
+    next_item: u64,
+    action_sets: HashMap<String, u64>,
+    digital_actions: HashMap<String, u64>,
+    analog_actions: HashMap<String, u64>,
+    next_action: u64,
+    controllers: BTreeMap<u64, MockController>,
+    next_controller: u64,
+    leaderboards: BTreeMap<u64, MockLeaderboard>,
+    next_leaderboard: u64,
+    /// Posted the next time callbacks run.
+    callbacks: VecDeque<(SteamCallback, Value)>,
+    /// The tickets that weren't cancelled, with their answer while it's held back.
+    tickets: BTreeMap<u32, Option<TicketAnswer>>,
+    next_ticket: u32,
+    hold_tickets: bool,
+    /// The user logged in instead of `LOCAL_STEAM_ID`.
+    steam_id: Option<SteamId>,
+}
+
+/// In-memory stand-in for the Steam client.
+///
+/// Stats and achievements have to be defined through the `mock` module before
+/// they can be read or written, the same way Steam rejects names that are not
+/// part of the app's schema.
+pub struct MockBackend {
+    app_id: AppId,
+    state: Mutex<MockState>,
+}
+
+fn now() -> u32 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as u32)
+        .unwrap_or_default()
+}
+
+fn intern(handles: &mut HashMap<String, u64>, next: &mut u64, name: &str) -> u64 {
+    *handles.entry(name.to_string()).or_insert_with(|| {
+        *next += 1;
+        *next
+    })
+}
+
+impl MockBackend {
+    pub fn new(app_id: AppId) -> Self {
+        Self {
+            app_id,
+            state: Mutex::new(MockState {
+                cloud_enabled_for_app: true,
+                ..Default::default()
+            }),
+        }
+    }
+
+    pub fn define_stat(&self, name: &str, stat_type: StatType, value: f64) {
+        let stat = match stat_type {
+            StatType::Int => MockStat::Int(value as i32),
+            StatType::Float => MockStat::Float(value as f32),
+            StatType::AvgRate => MockStat::AvgRate {
+                value: value as f32,
+                count: 0.0,
+                length: 0.0,
+            },
+        };
+        let mut state = self.state.lock().unwrap();
+        state.stats.insert(name.to_string(), stat);
+    }
+
+    /// Defines every stat and achievement in `schema`, at their default values and locked.
+    pub fn define_schema(&self, schema: &Schema) {
+        for (name, stat) in &schema.stats {
+            let stat_type = stat.stat_type.unwrap_or(StatType::Int);
+            self.define_stat(name, stat_type, stat.default);
+        }
+        for (name, achievement) in &schema.achievements {
+            self.define_achievement(name, false);
+            self.define_achievement_info(
+                name,
+                &achievement.display_name,
+                &achievement.description,
+                achievement.hidden,
+            );
+        }
+    }
+
+    pub fn define_achievement(&self, name: &str, achieved: bool) {
+        let mut state = self.state.lock().unwrap();
+        let achievement = state.achievements.entry(name.to_string()).or_default();
+        achievement.unlock_time = achieved.then(now);
+    }
+
+    /// @returns false if the achievement isn't defined
+    pub fn define_achievement_info(
+        &self,
+        name: &str,
+        display_name: &str,
+        description: &str,
+        hidden: bool,
+    ) -> bool {
+        let mut state = self.state.lock().unwrap();
+        match state.achievements.get_mut(name) {
+            Some(achievement) => {
+                achievement.display_name = Some(display_name.to_string());
+                achievement.description = description.to_string();
+                achievement.hidden = hidden;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// @returns false if the achievement isn't defined
+    pub fn define_achievement_icon(
+        &self,
+        name: &str,
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+    ) -> bool {
+        let mut state = self.state.lock().unwrap();
+        match state.achievements.get_mut(name) {
+            Some(achievement) => {
+                achievement.icon = Some((width, height, rgba));
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn define_achievement_percent(&self, name: &str, percent: f32) {
+        let mut state = self.state.lock().unwrap();
+        state.achievement_percents.insert(name.to_string(), percent);
+    }
+
+    /// @param history - The daily totals, today first
+    pub fn define_global_stat(&self, name: &str, stat_type: StatType, total: f64, history: &[f64]) {
+        let stat = match stat_type {
+            StatType::Int => MockGlobalStat::Int {
+                total: total as i64,
+                history: history.iter().map(|&day| day as i64).collect(),
+            },
+            StatType::Float | StatType::AvgRate => MockGlobalStat::Float {
+                total,
+                history: history.to_vec(),
+            },
+        };
+        let mut state = self.state.lock().unwrap();
+        state.global_stats.insert(name.to_string(), stat);
+    }
+
+    pub fn define_user_stat(&self, steam_id: SteamId, name: &str, value: f64) {
+        let mut state = self.state.lock().unwrap();
+        let user = state.users.entry(steam_id.raw()).or_default();
+        user.stats.insert(name.to_string(), value);
+    }
+
+    pub fn define_user_name(&self, steam_id: SteamId, name: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.personas.insert(steam_id.raw(), name.to_string());
+    }
+
+    /// @param unlock_time - Default value is now
+    pub fn define_user_achievement(
+        &self,
+        steam_id: SteamId,
+        name: &str,
+        achieved: bool,
+        unlock_time: Option<u32>,
+    ) {
+        let mut state = self.state.lock().unwrap();
+        let user = state.users.entry(steam_id.raw()).or_default();
+        if achieved {
+            let unlock_time = unlock_time.unwrap_or_else(now);
+            user.achievements.insert(name.to_string(), unlock_time);
+        } else {
+            user.achievements.remove(name);
+        }
+    }
+
+    pub fn store_count(&self) -> u32 {
+        self.state.lock().unwrap().stores
+    }
+
+    pub fn set_store_fails(&self, fails: bool) {
+        self.state.lock().unwrap().store_fails = fails;
+    }
+
+    pub fn set_steam_id(&self, steam_id: SteamId) {
+        self.state.lock().unwrap().steam_id = Some(steam_id);
+    }
+
+    /// Holds back the answers on auth tickets, the held ones are answered once it's turned off.
+    pub fn set_hold_tickets(&self, hold: bool) {
+        let held: Vec<TicketAnswer> = {
+            let mut state = self.state.lock().unwrap();
+            state.hold_tickets = hold;
+            if hold {
+                return;
+            }
+            state
+                .tickets
+                .values_mut()
+                .filter_map(Option::take)
+                .collect()
+        };
+        for answer in held {
+            answer.send();
+        }
+    }
+
+    /// @returns how many auth tickets were issued and not cancelled
+    pub fn ticket_count(&self) -> u32 {
+        self.state.lock().unwrap().tickets.len() as u32
+    }
+
+    /// Answers right away unless answers are held back.
+    fn issue_ticket(&self, answer: TicketAnswer) -> u32 {
+        let mut state = self.state.lock().unwrap();
+        state.next_ticket += 1;
+        let ticket = state.next_ticket;
+        if state.hold_tickets {
+            state.tickets.insert(ticket, Some(answer));
+        } else {
+            state.tickets.insert(ticket, None);
+            drop(state);
+            answer.send();
+        }
+        ticket
+    }
+
+    /// Adds another user's score to a leaderboard, creating the leaderboard if needed.
+    pub fn add_leaderboard_score(
+        &self,
+        leaderboard: &str,
+        steam_id: SteamId,
+        score: i32,
+        details: Vec<i32>,
+    ) {
+        let mut state = self.state.lock().unwrap();
+        let handle = Self::leaderboard_handle(
+            &mut state,
+            leaderboard,
+            Some((SortMethod::Descending, DisplayType::Numeric)),
+        )
+        .unwrap();
+        let board = state.leaderboards.get_mut(&handle).unwrap();
+        if let Some(rank) = board.rank(steam_id) {
+            board.scores.remove(rank);
+        }
+        board.insert(MockScore {
+            steam_id,
+            score,
+            details,
+            ugc: None,
+        });
+    }
+
+    fn leaderboard_handle(
+        state: &mut MockState,
+        name: &str,
+        create: Option<(SortMethod, DisplayType)>,
+    ) -> Option<u64> {
+        if let Some((handle, _)) = state.leaderboards.iter().find(|(_, b)| b.name == name) {
+            return Some(*handle);
+        }
+        let (sort_method, display_type) = create?;
+        state.next_leaderboard += 1;
+        state.leaderboards.insert(
+            state.next_leaderboard,
+            MockLeaderboard {
+                name: name.to_string(),
+                sort_method,
+                display_type,
+                scores: Vec::new(),
+            },
+        );
+        Some(state.next_leaderboard)
+    }
+
+    /// Returns the last `(current, max)` progress indicated for the achievement.
+    pub fn achievement_progress(&self, name: &str) -> Option<(u32, u32)> {
+        let state = self.state.lock().unwrap();
+        state.achievements.get(name).and_then(|a| a.progress)
+    }
+
+    pub fn add_controller(&self, input_type: steamworks::InputType) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        state.next_controller += 1;
+        let handle = state.next_controller;
+        state.controllers.insert(
+            handle,
+            MockController {
+                input_type: Some(input_type),
+                ..Default::default()
+            },
+        );
+        handle
+    }
+
+    pub fn remove_controller(&self, controller: u64) -> bool {
+        let mut state = self.state.lock().unwrap();
+        state.controllers.remove(&controller).is_some()
+    }
+
+    pub fn set_digital_action(&self, controller: u64, action_name: &str, pressed: bool) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let state = &mut *state;
+        let action = intern(
+            &mut state.digital_actions,
+            &mut state.next_action,
+            action_name,
+        );
+        match state.controllers.get_mut(&controller) {
+            Some(controller) => {
+                controller.digital.insert(action, pressed);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn set_analog_action(&self, controller: u64, action_name: &str, x: f32, y: f32) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let state = &mut *state;
+        let action = intern(
+            &mut state.analog_actions,
+            &mut state.next_action,
+            action_name,
+        );
+        match state.controllers.get_mut(&controller) {
+            Some(controller) => {
+                controller.analog.insert(action, (x, y));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the last left/right vibration speeds sent to the controller.
+    pub fn vibration(&self, controller: u64) -> Option<(u16, u16)> {
+        let state = self.state.lock().unwrap();
+        state.controllers.get(&controller).map(|c| c.vibration)
+    }
+
+    /// Posts a callback the next time callbacks run, the way Steam would.
+    pub fn queue_callback(&self, kind: SteamCallback, payload: Value) {
+        self.state
+            .lock()
+            .unwrap()
+            .callbacks
+            .push_back((kind, payload));
+    }
+
+    pub fn reset(&self) {
+        let mut state = self.state.lock().unwrap();
+        *state = MockState {
+            cloud_enabled_for_app: true,
+            ..Default::default()
+        };
+    }
+
+    fn to_workshop_item(id: u64, item: &MockItem) -> WorkshopItem {
+        WorkshopItem {
+            published_file_id: BigInt::from(id),
+            creator_app_id: Some(item.app_id.0),
+            consumer_app_id: Some(item.app_id.0),
+            title: item.title.clone(),
+            description: item.description.clone(),
+            owner: PlayerSteamId::from_steamid(item.owner),
+            time_created: item.time_created,
+            time_updated: item.time_updated,
+            time_added_to_user_list: 0,
+            visibility: item.visibility.into(),
+            banned: false,
+            accepted_for_use: true,
+            tags: item.tags.clone(),
+            tags_truncated: false,
+            url: String::new(),
+            num_upvotes: 0,
+            num_downvotes: 0,
+            num_children: 0,
+            preview_url: None,
+            statistics: WorkshopItemStatistic::default(),
+        }
+    }
+}
+
+impl SteamBackend for MockBackend {
+    fn kind(&self) -> BackendKind {
+        BackendKind::Mock
+    }
+
+    fn as_mock(&self) -> Option<&MockBackend> {
+        Some(self)
+    }
+
+    fn run_callbacks(&self) {
+        // Taken out of the lock, the listeners are free to call into the backend.
+        let callbacks = std::mem::take(&mut self.state.lock().unwrap().callbacks);
+        for (kind, payload) in callbacks {
+            crate::dispatch::emit(kind, payload);
+        }
+    }
+
+    fn app_id(&self) -> AppId {
+        self.app_id
+    }
+
+    fn steam_id(&self) -> SteamId {
+        self.state
+            .lock()
+            .unwrap()
+            .steam_id
+            .unwrap_or(SteamId::from_raw(LOCAL_STEAM_ID))
+    }
+
+    fn build_id(&self) -> i32 {
+        0
+    }
+
+    fn is_logged_on(&self) -> bool {
+        true
+    }
+
+    fn interfaces(&self) -> Vec<String> {
+        [
+            "ISteamApps",
+            "ISteamInput",
+            "ISteamMatchmaking",
+            "ISteamRemoteStorage",
+            "ISteamUGC",
+            "ISteamUser",
+            "ISteamUserStats",
+        ]
+        .iter()
+        .map(|name| name.to_string())
+        .collect()
+    }
+
+    fn request_user_stats(&self) {}
+
+    fn get_stat_i32(&self, name: &str) -> Result<i32, ()> {
+        let state = self.state.lock().unwrap();
+        match state.stats.get(name) {
+            Some(MockStat::Int(value)) => Ok(*value),
+            _ => Err(()),
+        }
+    }
+
+    fn set_stat_i32(&self, name: &str, value: i32) -> Result<(), ()> {
+        let mut state = self.state.lock().unwrap();
+        match state.stats.get_mut(name) {
+            Some(MockStat::Int(stat)) => {
+                *stat = value;
+                Ok(())
+            }
+            _ => Err(()),
+        }
+    }
+
+    fn get_stat_f32(&self, name: &str) -> Result<f32, ()> {
+        let state = self.state.lock().unwrap();
+        match state.stats.get(name) {
+            Some(MockStat::Float(value)) => Ok(*value),
+            Some(MockStat::AvgRate {
+                value,
+                count,
+                length,
+            }) => Ok(if *length > 0.0 {
+                (count / length) as f32
+            } else {
+                *value
+            }),
+            _ => Err(()),
+        }
+    }
+
+    fn set_stat_f32(&self, name: &str, value: f32) -> Result<(), ()> {
+        let mut state = self.state.lock().unwrap();
+        match state.stats.get_mut(name) {
+            Some(MockStat::Float(stat)) => {
+                *stat = value;
+                Ok(())
+            }
+            _ => Err(()),
+        }
+    }
+
+    fn update_avg_rate_stat(
+        &self,
+        name: &str,
+        count_this_session: f32,
+        session_length: f64,
+    ) -> Result<(), ()> {
+        if session_length <= 0.0 {
+            return Err(());
+        }
+        let mut state = self.state.lock().unwrap();
+        match state.stats.get_mut(name) {
+            Some(MockStat::AvgRate { count, length, .. }) => {
+                *count += f64::from(count_this_session);
+                *length += session_length;
+                Ok(())
+            }
+            _ => Err(()),
+        }
+    }
+
+    fn stat_type(&self, name: &str) -> Option<StatType> {
+        let state = self.state.lock().unwrap();
+        state.stats.get(name).map(MockStat::stat_type)
+    }
+
+    fn store_stats(&self) -> Result<(), ()> {
+        let mut state = self.state.lock().unwrap();
+        if state.store_fails {
+            return Err(());
+        }
+        state.stores += 1;
+        Ok(())
+    }
+
+    fn reset_all_stats(&self, achievements_too: bool) -> Result<(), ()> {
+        let mut state = self.state.lock().unwrap();
+        for stat in state.stats.values_mut() {
+            *stat = match stat {
+                MockStat::Int(_) => MockStat::Int(0),
+                MockStat::Float(_) => MockStat::Float(0.0),
+                MockStat::AvgRate { .. } => MockStat::AvgRate {
+                    value: 0.0,
+                    count: 0.0,
+                    length: 0.0,
+                },
+            };
+        }
+        if achievements_too {
+            state
+                .achievements
+                .values_mut()
+                .for_each(|achievement| achievement.unlock_time = None);
+        }
+        Ok(())
+    }
+
+    fn get_achievement(&self, name: &str) -> Result<bool, ()> {
+        let state = self.state.lock().unwrap();
+        state
+            .achievements
+            .get(name)
+            .map(|achievement| achievement.unlock_time.is_some())
+            .ok_or(())
+    }
+
+    fn set_achievement(&self, name: &str) -> Result<(), ()> {
+        let mut state = self.state.lock().unwrap();
+        match state.achievements.get_mut(name) {
+            Some(achievement) => {
+                achievement.unlock_time.get_or_insert_with(now);
+                Ok(())
+            }
+            None => Err(()),
+        }
+    }
+
+    fn clear_achievement(&self, name: &str) -> Result<(), ()> {
+        let mut state = self.state.lock().unwrap();
+        match state.achievements.get_mut(name) {
+            Some(achievement) => {
+                achievement.unlock_time = None;
+                Ok(())
+            }
+            None => Err(()),
+        }
+    }
+
+    fn achievement_names(&self) -> Option<Vec<String>> {
+        let state = self.state.lock().unwrap();
+        Some(state.achievements.keys().cloned().collect())
+    }
+
+    fn achievement_info(&self, name: &str) -> Option<AchievementInfo> {
+        let state = self.state.lock().unwrap();
+        state
+            .achievements
+            .get(name)
+            .map(|achievement| AchievementInfo {
+                name: name.to_string(),
+                display_name: achievement
+                    .display_name
+                    .clone()
+                    .unwrap_or_else(|| name.to_string()),
+                description: achievement.description.clone(),
+                hidden: achievement.hidden,
+                unlocked: achievement.unlock_time.is_some(),
+                unlock_time: achievement.unlock_time,
+                global_percent: state.global_percentages_received.then(|| {
+                    f64::from(state.achievement_percents.get(name).copied().unwrap_or(0.0))
+                }),
+            })
+    }
+
+    fn achievement_icon(&self, name: &str) -> Result<Option<(u32, u32, Vec<u8>)>, ()> {
+        let state = self.state.lock().unwrap();
+        let achievement = state.achievements.get(name).ok_or(())?;
+        Ok(achievement.icon.clone())
+    }
+
+    fn indicate_achievement_progress(&self, name: &str, current: u32, max: u32) -> Result<(), ()> {
+        let mut state = self.state.lock().unwrap();
+        match state.achievements.get_mut(name) {
+            Some(achievement) if achievement.unlock_time.is_none() && current < max => {
+                achievement.progress = Some((current, max));
+                Ok(())
+            }
+            _ => Err(()),
+        }
+    }
+
+    fn request_global_achievement_percentages(&self, callback: Callback<Result<(), SteamError>>) {
+        self.state.lock().unwrap().global_percentages_received = true;
+        callback(Ok(()));
+    }
+
+    fn achievement_achieved_percent(&self, name: &str) -> Option<f32> {
+        let state = self.state.lock().unwrap();
+        if !state.global_percentages_received || !state.achievements.contains_key(name) {
+            return None;
+        }
+        Some(state.achievement_percents.get(name).copied().unwrap_or(0.0))
+    }
+
+    fn most_achieved_achievements(&self) -> Vec<(String, f32, bool)> {
+        let state = self.state.lock().unwrap();
+        if !state.global_percentages_received {
+            return Vec::new();
+        }
+        let mut achievements: Vec<_> = state
+            .achievements
+            .iter()
+            .map(|(name, achievement)| {
+                let percent = state.achievement_percents.get(name).copied().unwrap_or(0.0);
+                (name.clone(), percent, achievement.unlock_time.is_some())
+            })
+            .collect();
+        achievements.sort_by(|a, b| b.1.total_cmp(&a.1));
+        achievements
+    }
+
+    fn request_global_stats(
+        &self,
+        history_days: u32,
+        callback: Callback<Result<(), SteamError>>,
+    ) -> Result<Option<Subscription>, SteamworksError> {
+        self.state.lock().unwrap().global_history_days = Some(history_days.min(60) as usize);
+        callback(Ok(()));
+        Ok(None)
+    }
+
+    fn global_stat_i64(&self, name: &str) -> Option<i64> {
+        let state = self.state.lock().unwrap();
+        state.global_history_days?;
+        match state.global_stats.get(name) {
+            Some(MockGlobalStat::Int { total, .. }) => Some(*total),
+            _ => None,
+        }
+    }
+
+    fn global_stat_f64(&self, name: &str) -> Option<f64> {
+        let state = self.state.lock().unwrap();
+        state.global_history_days?;
+        match state.global_stats.get(name) {
+            Some(MockGlobalStat::Float { total, .. }) => Some(*total),
+            _ => None,
+        }
+    }
+
+    fn global_stat_history_i64(&self, name: &str, days: usize) -> Option<Vec<i64>> {
+        let state = self.state.lock().unwrap();
+        let days = days.min(state.global_history_days?);
+        match state.global_stats.get(name) {
+            Some(MockGlobalStat::Int { history, .. }) if days > 0 && !history.is_empty() => {
+                Some(history.iter().copied().take(days).collect())
+            }
+            _ => None,
+        }
+    }
+
+    fn global_stat_history_f64(&self, name: &str, days: usize) -> Option<Vec<f64>> {
+        let state = self.state.lock().unwrap();
+        let days = days.min(state.global_history_days?);
+        match state.global_stats.get(name) {
+            Some(MockGlobalStat::Float { history, .. }) if days > 0 && !history.is_empty() => {
+                Some(history.iter().copied().take(days).collect())
+            }
+            _ => None,
+        }
+    }
+
+    fn request_user_stats_for(
+        &self,
+        steam_id: SteamId,
+        callback: Callback<Result<(), SteamError>>,
+    ) -> Result<Option<Subscription>, SteamworksError> {
+        let result = {
+            let mut state = self.state.lock().unwrap();
+            // Steam fails for users who don't have the app, here the ones nothing was defined for.
+            match state.users.get_mut(&steam_id.raw()) {
+                Some(user) => {
+                    user.received = true;
+                    Ok(())
+                }
+                None => Err(SteamError::Generic),
+            }
+        };
+        callback(result);
+        Ok(None)
+    }
+
+    fn user_stat_i32(&self, steam_id: SteamId, name: &str) -> Result<i32, ()> {
+        let state = self.state.lock().unwrap();
+        let user = state.users.get(&steam_id.raw()).filter(|u| u.received);
+        match (user, state.stats.get(name)) {
+            (Some(user), Some(MockStat::Int(_))) => {
+                Ok(user.stats.get(name).copied().unwrap_or_default() as i32)
+            }
+            _ => Err(()),
+        }
+    }
+
+    fn user_stat_f32(&self, steam_id: SteamId, name: &str) -> Result<f32, ()> {
+        let state = self.state.lock().unwrap();
+        let user = state.users.get(&steam_id.raw()).filter(|u| u.received);
+        match (user, state.stats.get(name)) {
+            (Some(user), Some(MockStat::Float(_) | MockStat::AvgRate { .. })) => {
+                Ok(user.stats.get(name).copied().unwrap_or_default() as f32)
+            }
+            _ => Err(()),
+        }
+    }
+
+    fn user_achievement(&self, steam_id: SteamId, name: &str) -> Option<(bool, u32)> {
+        let state = self.state.lock().unwrap();
+        let user = state.users.get(&steam_id.raw()).filter(|u| u.received)?;
+        if !state.achievements.contains_key(name) {
+            return None;
+        }
+        Some(match user.achievements.get(name) {
+            Some(&unlock_time) => (true, unlock_time),
+            None => (false, 0),
+        })
+    }
+
+    fn find_leaderboard(
+        &self,
+        name: &str,
+        create: Option<(SortMethod, DisplayType)>,
+        callback: Callback<Result<Option<u64>, SteamError>>,
+    ) {
+        let handle = {
+            let mut state = self.state.lock().unwrap();
+            Self::leaderboard_handle(&mut state, name, create)
+        };
+        callback(Ok(handle));
+    }
+
+    fn leaderboard_name(&self, leaderboard: u64) -> String {
+        let state = self.state.lock().unwrap();
+        state
+            .leaderboards
+            .get(&leaderboard)
+            .map(|board| board.name.clone())
+            .unwrap_or_default()
+    }
+
+    fn leaderboard_entry_count(&self, leaderboard: u64) -> i32 {
+        let state = self.state.lock().unwrap();
+        state
+            .leaderboards
+            .get(&leaderboard)
+            .map_or(0, |board| board.scores.len() as i32)
+    }
+
+    fn leaderboard_sort_method(&self, leaderboard: u64) -> Option<SortMethod> {
+        let state = self.state.lock().unwrap();
+        state
+            .leaderboards
+            .get(&leaderboard)
+            .map(|board| board.sort_method)
+    }
+
+    fn leaderboard_display_type(&self, leaderboard: u64) -> Option<DisplayType> {
+        let state = self.state.lock().unwrap();
+        state
+            .leaderboards
+            .get(&leaderboard)
+            .map(|board| board.display_type)
+    }
+
+    fn upload_leaderboard_score(
+        &self,
+        leaderboard: u64,
+        method: UploadScoreMethod,
+        score: i32,
+        details: &[i32],
+        callback: Callback<Result<ScoreUploaded, SteamError>>,
+    ) {
+        let result = {
+            let mut state = self.state.lock().unwrap();
+            match state.leaderboards.get_mut(&leaderboard) {
+                Some(board) => {
+                    let previous = board.rank(self.steam_id());
+                    let kept = previous.filter(|&rank| {
+                        method == UploadScoreMethod::KeepBest
+                            && !board.is_better(score, board.scores[rank].score)
+                    });
+                    let global_rank_new = match kept {
+                        Some(rank) => rank as i32 + 1,
+                        None => {
+                            // Replacing the score keeps the UGC attached to the entry.
+                            let ugc = previous.and_then(|rank| board.scores.remove(rank).ugc);
+                            board.insert(MockScore {
+                                steam_id: self.steam_id(),
+                                score,
+                                details: details.to_vec(),
+                                ugc,
+                            })
+                        }
+                    };
+                    Ok(ScoreUploaded {
+                        score,
+                        score_changed: kept.is_none(),
+                        global_rank_new,
+                        global_rank_previous: previous.map_or(0, |rank| rank as i32 + 1),
+                    })
+                }
+                None => Err(SteamError::InvalidParameter),
+            }
+        };
+        callback(result);
+    }
+
+    fn download_leaderboard_entries(
+        &self,
+        leaderboard: u64,
+        request: EntriesRequest,
+        callback: Callback<Result<Vec<LeaderboardEntry>, SteamError>>,
+    ) -> Result<Option<Subscription>, SteamworksError> {
+        let result = {
+            let state = self.state.lock().unwrap();
+            match state.leaderboards.get(&leaderboard) {
+                Some(board) => {
+                    let ranked = board.scores.iter().enumerate();
+                    let in_range =
+                        |rank: usize, start: i64, end: i64| (start..=end).contains(&(rank as i64));
+                    let entries: Vec<_> = match request {
+                        EntriesRequest::Global { start, end } => ranked
+                            .filter(|(rank, _)| {
+                                in_range(*rank, i64::from(start) - 1, i64::from(end) - 1)
+                            })
+                            .map(|(rank, score)| MockLeaderboard::entry(rank, score))
+                            .collect(),
+                        EntriesRequest::AroundUser { start, end } => {
+                            match board.rank(self.steam_id()) {
+                                Some(user) => ranked
+                                    .filter(|(rank, _)| {
+                                        let user = user as i64;
+                                        in_range(
+                                            *rank,
+                                            user + i64::from(start),
+                                            user + i64::from(end),
+                                        )
+                                    })
+                                    .map(|(rank, score)| MockLeaderboard::entry(rank, score))
+                                    .collect(),
+                                None => Vec::new(),
+                            }
+                        }
+                        // Everyone is the local user's friend here.
+                        EntriesRequest::Friends => ranked
+                            .map(|(rank, score)| MockLeaderboard::entry(rank, score))
+                            .collect(),
+                        EntriesRequest::Users(users) => ranked
+                            .filter(|(_, score)| users.contains(&score.steam_id))
+                            .map(|(rank, score)| MockLeaderboard::entry(rank, score))
+                            .collect(),
+                    };
+                    Ok(entries)
+                }
+                None => Err(SteamError::InvalidParameter),
+            }
+        };
+        callback(result);
+        Ok(None)
+    }
+
+    fn attach_leaderboard_ugc(
+        &self,
+        leaderboard: u64,
+        ugc: u64,
+        callback: Callback<Result<(), SteamError>>,
+    ) -> Result<Option<Subscription>, SteamworksError> {
+        let result = {
+            let mut state = self.state.lock().unwrap();
+            let steam_id = self.steam_id();
+            match state.leaderboards.get_mut(&leaderboard) {
+                Some(board) => match board.scores.iter_mut().find(|s| s.steam_id == steam_id) {
+                    Some(score) => {
+                        score.ugc = Some(ugc);
+                        Ok(())
+                    }
+                    None => Err(SteamError::FileNotFound),
+                },
+                None => Err(SteamError::InvalidParameter),
+            }
+        };
+        callback(result);
+        Ok(None)
+    }
+
+    fn session_ticket(
+        &self,
+        _identity: NetworkingIdentity,
+        callback: Callback<Result<(), SteamError>>,
+    ) -> Result<(u32, Vec<u8>), SteamworksError> {
+        let ticket = self.issue_ticket(TicketAnswer::Session(callback));
+        Ok((
+            ticket,
+            format!("mock-session-ticket-{}", ticket).into_bytes(),
+        ))
+    }
+
+    fn web_api_ticket(
+        &self,
+        identity: &str,
+        callback: Callback<Result<Vec<u8>, SteamError>>,
+    ) -> Result<u32, SteamworksError> {
+        let data = format!("mock-web-api-ticket-{}", identity).into_bytes();
+        Ok(self.issue_ticket(TicketAnswer::WebApi(callback, data)))
+    }
+
+    fn cancel_ticket(&self, ticket: u32) {
+        // A held answer is dropped without being sent, like Steam never answering.
+        let answer = self.state.lock().unwrap().tickets.remove(&ticket);
+        drop(answer);
+    }
+
+    fn is_cloud_enabled_for_account(&self) -> bool {
+        true
+    }
+
+    fn is_cloud_enabled_for_app(&self) -> bool {
+        self.state.lock().unwrap().cloud_enabled_for_app
+    }
+
+    fn set_cloud_enabled_for_app(&self, enabled: bool) {
+        self.state.lock().unwrap().cloud_enabled_for_app = enabled;
+    }
+
+    fn read_file(&self, name: &str) -> std::io::Result<Vec<u8>> {
+        let state = self.state.lock().unwrap();
+        state
+            .files
+            .get(name)
+            .cloned()
+            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "file does not exist"))
+    }
+
+    fn write_file(&self, name: &str, content: &[u8]) -> std::io::Result<()> {
+        let mut state = self.state.lock().unwrap();
+        state.files.insert(name.to_string(), content.to_vec());
+        Ok(())
+    }
+
+    fn delete_file(&self, name: &str) -> bool {
+        let mut state = self.state.lock().unwrap();
+        state.files.remove(name).is_some()
+    }
+
+    fn file_exists(&self, name: &str) -> bool {
+        let state = self.state.lock().unwrap();
+        state.files.contains_key(name)
+    }
+
+    fn list_files(&self) -> Vec<(String, u64)> {
+        let state = self.state.lock().unwrap();
+        state
+            .files
+            .iter()
+            .map(|(name, content)| (name.clone(), content.len() as u64))
+            .collect()
+    }
+
+    fn create_lobby(
+        &self,
+        lobby_type: steamworks::LobbyType,
+        max_members: u32,
+        callback: Callback<Result<LobbyId, SteamError>>,
+    ) {
+        let lobby_id = {
+            let mut state = self.state.lock().unwrap();
+            state.next_lobby += 1;
+            let lobby_id = LobbyId::from_raw(LOBBY_ID_BASE | state.next_lobby);
+            state.lobbies.insert(
+                lobby_id.raw(),
+                MockLobby {
+                    lobby_type,
+                    max_members,
+                    owner: self.steam_id(),
+                    members: vec![self.steam_id()],
+                    joinable: true,
+                    data: BTreeMap::new(),
+                    chat: Vec::new(),
+                },
+            );
+            lobby_id
+        };
+        callback(Ok(lobby_id));
+    }
+
+    fn join_lobby(&self, lobby: LobbyId, callback: Callback<Result<LobbyId, ()>>) {
+        let result = {
+            let mut state = self.state.lock().unwrap();
+            match state.lobbies.get_mut(&lobby.raw()) {
+                Some(mock) if mock.joinable && mock.members.len() < mock.max_members as usize => {
+                    if !mock.members.contains(&self.steam_id()) {
+                        mock.members.push(self.steam_id());
+                    }
+                    Ok(lobby)
+                }
+                _ => Err(()),
+            }
+        };
+        callback(result);
+    }
+
+    fn request_lobby_list(&self, callback: Callback<Result<Vec<LobbyId>, SteamError>>) {
+        let lobbies = {
+            let state = self.state.lock().unwrap();
+            state
+                .lobbies
+                .iter()
+                .filter(|(_, lobby)| matches!(lobby.lobby_type, steamworks::LobbyType::Public))
+                .map(|(id, _)| LobbyId::from_raw(*id))
+                .collect()
+        };
+        callback(Ok(lobbies));
+    }
+
+    fn leave_lobby(&self, lobby: LobbyId) {
+        let mut state = self.state.lock().unwrap();
+        let steam_id = self.steam_id();
+        let empty = match state.lobbies.get_mut(&lobby.raw()) {
+            Some(mock) => {
+                mock.members.retain(|member| *member != steam_id);
+                if let Some(next_owner) = mock.members.first() {
+                    mock.owner = *next_owner;
+                }
+                mock.members.is_empty()
+            }
+            None => false,
+        };
+        if empty {
+            state.lobbies.remove(&lobby.raw());
+        }
+    }
+
+    fn lobby_member_count(&self, lobby: LobbyId) -> usize {
+        let state = self.state.lock().unwrap();
+        state
+            .lobbies
+            .get(&lobby.raw())
+            .map(|lobby| lobby.members.len())
+            .unwrap_or_default()
+    }
+
+    fn lobby_member_limit(&self, lobby: LobbyId) -> Option<usize> {
+        let state = self.state.lock().unwrap();
+        state
+            .lobbies
+            .get(&lobby.raw())
+            .map(|lobby| lobby.max_members as usize)
+    }
+
+    fn lobby_members(&self, lobby: LobbyId) -> Vec<SteamId> {
+        let state = self.state.lock().unwrap();
+        state
+            .lobbies
+            .get(&lobby.raw())
+            .map(|lobby| lobby.members.clone())
+            .unwrap_or_default()
+    }
+
+    fn lobby_owner(&self, lobby: LobbyId) -> SteamId {
+        let state = self.state.lock().unwrap();
+        state
+            .lobbies
+            .get(&lobby.raw())
+            .map(|lobby| lobby.owner)
+            .unwrap_or_else(|| SteamId::from_raw(0))
+    }
+
+    fn set_lobby_joinable(&self, lobby: LobbyId, joinable: bool) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let steam_id = self.steam_id();
+        match state.lobbies.get_mut(&lobby.raw()) {
+            Some(mock) if mock.owner == steam_id => {
+                mock.joinable = joinable;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn lobby_data(&self, lobby: LobbyId, key: &str) -> Option<String> {
+        let state = self.state.lock().unwrap();
+        state
+            .lobbies
+            .get(&lobby.raw())
+            .and_then(|lobby| lobby.data.get(key).cloned())
+    }
+
+    fn set_lobby_data(&self, lobby: LobbyId, key: &str, value: &str) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let steam_id = self.steam_id();
+        match state.lobbies.get_mut(&lobby.raw()) {
+            Some(mock) if mock.owner == steam_id => {
+                mock.data.insert(key.to_string(), value.to_string());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn delete_lobby_data(&self, lobby: LobbyId, key: &str) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let steam_id = self.steam_id();
+        match state.lobbies.get_mut(&lobby.raw()) {
+            Some(mock) if mock.owner == steam_id => mock.data.remove(key).is_some(),
+            _ => false,
+        }
+    }
+
+    fn lobby_full_data(&self, lobby: LobbyId) -> Vec<(String, String)> {
+        let state = self.state.lock().unwrap();
+        state
+            .lobbies
+            .get(&lobby.raw())
+            .map(|lobby| {
+                lobby
+                    .data
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn send_lobby_chat_message(&self, lobby: LobbyId, message: &[u8]) -> Result<(), SteamError> {
+        let mut state = self.state.lock().unwrap();
+        match state.lobbies.get_mut(&lobby.raw()) {
+            Some(mock) => {
+                mock.chat.push(message.to_vec());
+                Ok(())
+            }
+            None => Err(SteamError::InvalidSteamID),
+        }
+    }
+
+    fn lobby_chat_entry(&self, lobby: LobbyId, chat_id: i32) -> Vec<u8> {
+        let state = self.state.lock().unwrap();
+        state
+            .lobbies
+            .get(&lobby.raw())
+            .and_then(|lobby| {
+                usize::try_from(chat_id)
+                    .ok()
+                    .and_then(|i| lobby.chat.get(i))
+            })
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn create_item(
+        &self,
+        app_id: AppId,
+        callback: Callback<Result<(PublishedFileId, bool), SteamError>>,
+    ) {
+        let item_id = {
+            let mut state = self.state.lock().unwrap();
+            state.next_item += 1;
+            let item_id = state.next_item;
+            state.items.insert(
+                item_id,
+                MockItem {
+                    app_id,
+                    owner: self.steam_id(),
+                    title: String::new(),
+                    description: String::new(),
+                    tags: Vec::new(),
+                    visibility: steamworks::PublishedFileVisibility::Private,
+                    time_created: now(),
+                    time_updated: now(),
+                    subscribed: false,
+                    installed: false,
+                },
+            );
+            PublishedFileId(item_id)
+        };
+        callback(Ok((item_id, false)));
+    }
+
+    fn submit_item_update(
+        &self,
+        _app_id: AppId,
+        item: PublishedFileId,
+        details: UgcUpdate,
+        callback: Callback<Result<(PublishedFileId, bool), SteamError>>,
+    ) -> Option<steamworks::UpdateWatchHandle> {
+        let result = {
+            let mut state = self.state.lock().unwrap();
+            match state.items.get_mut(&item.0) {
+                Some(mock) => {
+                    if let Some(title) = details.title {
+                        mock.title = title;
+                    }
+                    if let Some(description) = details.description {
+                        mock.description = description;
+                    }
+                    if let Some(tags) = details.tags {
+                        mock.tags = tags;
+                    }
+                    if let Some(visibility) = details.visibility {
+                        mock.visibility = visibility.into();
+                    }
+                    mock.time_updated = now();
+                    Ok((item, false))
+                }
+                None => Err(SteamError::FileNotFound),
+            }
+        };
+        callback(result);
+        None
+    }
+
+    fn subscribe_item(&self, item: PublishedFileId, callback: Callback<Result<(), SteamError>>) {
+        let result = {
+            let mut state = self.state.lock().unwrap();
+            match state.items.get_mut(&item.0) {
+                Some(mock) => {
+                    mock.subscribed = true;
+                    mock.installed = true;
+                    Ok(())
+                }
+                None => Err(SteamError::FileNotFound),
+            }
+        };
+        callback(result);
+    }
+
+    fn unsubscribe_item(&self, item: PublishedFileId, callback: Callback<Result<(), SteamError>>) {
+        let result = {
+            let mut state = self.state.lock().unwrap();
+            match state.items.get_mut(&item.0) {
+                Some(mock) => {
+                    mock.subscribed = false;
+                    Ok(())
+                }
+                None => Err(SteamError::FileNotFound),
+            }
+        };
+        callback(result);
+    }
+
+    fn delete_item(&self, item: PublishedFileId, callback: Callback<Result<(), SteamError>>) {
+        let result = {
+            let mut state = self.state.lock().unwrap();
+            match state.items.remove(&item.0) {
+                Some(_) => Ok(()),
+                None => Err(SteamError::FileNotFound),
+            }
+        };
+        callback(result);
+    }
+
+    fn item_state(&self, item: PublishedFileId) -> ItemState {
+        let state = self.state.lock().unwrap();
+        let mut item_state = ItemState::empty();
+        if let Some(mock) = state.items.get(&item.0) {
+            if mock.subscribed {
+                item_state |= ItemState::SUBSCRIBED;
+            }
+            if mock.installed {
+                item_state |= ItemState::INSTALLED;
+            }
+        }
+        item_state
+    }
+
+    fn item_install_info(&self, item: PublishedFileId) -> Option<InstallInfo> {
+        let state = self.state.lock().unwrap();
+        state
+            .items
+            .get(&item.0)
+            .filter(|mock| mock.installed)
+            .map(|mock| InstallInfo {
+                folder: format!("mock/workshop/{}", item.0),
+                size_on_disk: BigInt::from(0u64),
+                timestamp: mock.time_updated,
+            })
+    }
+
+    fn item_download_info(&self, item: PublishedFileId) -> Option<(u64, u64)> {
+        let state = self.state.lock().unwrap();
+        state.items.get(&item.0).map(|_| (0, 0))
+    }
+
+    fn download_item(&self, item: PublishedFileId, _high_priority: bool) -> bool {
+        let mut state = self.state.lock().unwrap();
+        match state.items.get_mut(&item.0) {
+            Some(mock) => {
+                mock.installed = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn subscribed_items(&self) -> Vec<PublishedFileId> {
+        let state = self.state.lock().unwrap();
+        state
+            .items
+            .iter()
+            .filter(|(_, item)| item.subscribed)
+            .map(|(id, _)| PublishedFileId(*id))
+            .collect()
+    }
+
+    fn query_items(
+        &self,
+        items: Vec<PublishedFileId>,
+        _query_config: Option<WorkshopItemQueryConfig>,
+        callback: Callback<Result<(Vec<Option<WorkshopItem>>, bool), SteamError>>,
+    ) -> Result<(), SteamError> {
+        let results = {
+            let state = self.state.lock().unwrap();
+            items
+                .iter()
+                .map(|id| {
+                    state
+                        .items
+                        .get(&id.0)
+                        .map(|item| Self::to_workshop_item(id.0, item))
+                })
+                .collect()
+        };
+        callback(Ok((results, false)));
+        Ok(())
+    }
+
+    fn request_user_information(&self, _steam_id: SteamId, _require_name_only: bool) -> bool {
+        // Every persona is known right away.
+        false
+    }
+
+    fn friend_info(&self, steam_id: SteamId) -> FriendInfo {
+        let state = self.state.lock().unwrap();
+        FriendInfo {
+            // What Steam has for a user it knows nothing about.
+            name: state
+                .personas
+                .get(&steam_id.raw())
+                .cloned()
+                .unwrap_or_else(|| "[unknown]".to_string()),
+            nick_name: None,
+            small_avatar: None,
+            medium_avatar: None,
+            large_avatar: None,
+            id: BigInt::from(steam_id.raw()),
+        }
+    }
+
+    fn input_init(&self) {}
+
+    fn input_shutdown(&self) {}
+
+    fn input_run_frame(&self) {}
+
+    fn connected_controllers(&self) -> Vec<u64> {
+        let state = self.state.lock().unwrap();
+        state.controllers.keys().copied().collect()
+    }
+
+    fn action_set_handle(&self, name: &str) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        let state = &mut *state;
+        intern(&mut state.action_sets, &mut state.next_action, name)
+    }
+
+    fn digital_action_handle(&self, name: &str) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        let state = &mut *state;
+        intern(&mut state.digital_actions, &mut state.next_action, name)
+    }
+
+    fn analog_action_handle(&self, name: &str) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        let state = &mut *state;
+        intern(&mut state.analog_actions, &mut state.next_action, name)
+    }
+
+    fn activate_action_set(&self, controller: u64, action_set: u64) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(controller) = state.controllers.get_mut(&controller) {
+            controller.action_set = action_set;
+        }
+    }
+
+    fn current_action_set(&self, controller: u64) -> u64 {
+        let state = self.state.lock().unwrap();
+        state
+            .controllers
+            .get(&controller)
+            .map(|controller| controller.action_set)
+            .unwrap_or_default()
+    }
+
+    fn digital_action_pressed(&self, controller: u64, action: u64) -> bool {
+        let state = self.state.lock().unwrap();
+        state
+            .controllers
+            .get(&controller)
+            .and_then(|controller| controller.digital.get(&action).copied())
+            .unwrap_or(false)
+    }
+
+    fn analog_action_vector(&self, controller: u64, action: u64) -> (f32, f32) {
+        let state = self.state.lock().unwrap();
+        state
+            .controllers
+            .get(&controller)
+            .and_then(|controller| controller.analog.get(&action).copied())
+            .unwrap_or_default()
+    }
+
+    fn digital_action_origins(
+        &self,
+        _controller: u64,
+        _action_set: u64,
+        _action: u64,
+    ) -> Vec<EInputActionOrigin> {
+        // The fake controllers have no bindings.
+        Vec::new()
+    }
+
+    fn analog_action_origins(
+        &self,
+        _controller: u64,
+        _action_set: u64,
+        _action: u64,
+    ) -> Vec<EInputActionOrigin> {
+        Vec::new()
+    }
+
+    fn action_origin_glyph(&self, _origin: EInputActionOrigin) -> String {
+        String::new()
+    }
+
+    fn input_type(&self, controller: u64) -> steamworks::InputType {
+        let state = self.state.lock().unwrap();
+        state
+            .controllers
+            .get(&controller)
+            .and_then(|controller| controller.input_type)
+            .unwrap_or(steamworks::InputType::Unknown)
+    }
+
+    fn motion_data(&self, _controller: u64) -> MotionData {
+        MotionData {
+            rot_quat_x: 0.0,
+            rot_quat_y: 0.0,
+            rot_quat_z: 0.0,
+            rot_quat_w: 1.0,
+            pos_accel_x: 0.0,
+            pos_accel_y: 0.0,
+            pos_accel_z: 0.0,
+            rot_vel_x: 0.0,
+            rot_vel_y: 0.0,
+            rot_vel_z: 0.0,
+        }
+    }
+
+    fn trigger_vibration(&self, controller: u64, left_speed: u16, right_speed: u16) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(controller) = state.controllers.get_mut(&controller) {
+            controller.vibration = (left_speed, right_speed);
+        }
+    }
+}
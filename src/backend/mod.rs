@@ -0,0 +1,276 @@
+//! The backend every API module talks to.
+//!
+//! `SteamworksBackend` forwards to a real `steamworks::Client`, while
+//! `MockBackend` keeps everything in memory so the API can be exercised
+//! without a running Steam client.
+
+use napi_derive::napi;
+use steamworks::networking_types::NetworkingIdentity;
+use steamworks::sys::EInputActionOrigin;
+use steamworks::{AppId, LobbyId, PublishedFileId, SteamError, SteamId};
+
+use crate::api::achievement::achievement::AchievementInfo;
+use crate::api::friends::FriendInfo;
+use crate::api::input::input::MotionData;
+use crate::api::leaderboard::leaderboard::{
+    DisplayType, EntriesRequest, LeaderboardEntry, ScoreUploaded, SortMethod, UploadScoreMethod,
+};
+use crate::api::stats::stats::StatType;
+use crate::api::workshop::workshop::{InstallInfo, UgcUpdate};
+use crate::api::workshop_item::workshop::{WorkshopItem, WorkshopItemQueryConfig};
+use crate::dispatch::Subscription;
+use crate::error::SteamworksError;
+
+mod mock;
+mod steam;
+
+pub use mock::MockBackend;
+pub use steam::SteamworksBackend;
+
+#[napi]
+pub enum BackendKind {
+    /// Talk to the running Steam client.
+    Steam,
+    /// Keep stats, achievements, cloud files, lobbies, workshop items and
+    /// controllers in memory. Nothing is sent to Steam.
+    Mock,
+}
+
+pub type Callback<T> = Box<dyn FnOnce(T) + Send + 'static>;
+
+pub trait SteamBackend: Send + Sync {
+    fn kind(&self) -> BackendKind;
+
+    /// Downcast helper used by the `mock` module.
+    fn as_mock(&self) -> Option<&MockBackend> {
+        None
+    }
+
+    fn run_callbacks(&self);
+    fn app_id(&self) -> AppId;
+    fn steam_id(&self) -> SteamId;
+    fn build_id(&self) -> i32;
+    fn is_logged_on(&self) -> bool;
+    /// The Steam interfaces that can be used, e.g. `ISteamUserStats`.
+    fn interfaces(&self) -> Vec<String>;
+
+    // Stats
+    fn request_user_stats(&self);
+    fn get_stat_i32(&self, name: &str) -> Result<i32, ()>;
+    fn set_stat_i32(&self, name: &str, value: i32) -> Result<(), ()>;
+    fn get_stat_f32(&self, name: &str) -> Result<f32, ()>;
+    fn set_stat_f32(&self, name: &str, value: f32) -> Result<(), ()>;
+    fn update_avg_rate_stat(
+        &self,
+        name: &str,
+        count_this_session: f32,
+        session_length: f64,
+    ) -> Result<(), ()>;
+    /// `None` if the stat doesn't exist.
+    fn stat_type(&self, name: &str) -> Option<StatType>;
+    fn store_stats(&self) -> Result<(), ()>;
+    fn reset_all_stats(&self, achievements_too: bool) -> Result<(), ()>;
+
+    // Achievements
+    fn get_achievement(&self, name: &str) -> Result<bool, ()>;
+    fn set_achievement(&self, name: &str) -> Result<(), ()>;
+    fn clear_achievement(&self, name: &str) -> Result<(), ()>;
+    fn achievement_names(&self) -> Option<Vec<String>>;
+    /// `None` if the achievement doesn't exist.
+    fn achievement_info(&self, name: &str) -> Option<AchievementInfo>;
+    /// The icon for the achievement's current state as `(width, height, rgba)`, `Ok(None)` while
+    /// Steam is still fetching it. `Err` if the achievement doesn't exist.
+    fn achievement_icon(&self, name: &str) -> Result<Option<(u32, u32, Vec<u8>)>, ()>;
+    /// Fails if the achievement doesn't exist, is already unlocked or `current` isn't below `max`.
+    fn indicate_achievement_progress(&self, name: &str, current: u32, max: u32) -> Result<(), ()>;
+    fn request_global_achievement_percentages(&self, callback: Callback<Result<(), SteamError>>);
+    /// `None` until the global percentages came in, or if the achievement doesn't exist.
+    fn achievement_achieved_percent(&self, name: &str) -> Option<f32>;
+    /// Every achievement's name, global unlock percentage and whether the user has it, the most
+    /// achieved first. Empty until the global percentages came in.
+    fn most_achieved_achievements(&self) -> Vec<(String, f32, bool)>;
+
+    // Global stats
+    /// @returns what waits for Steam's answer, if anything does, it stops waiting once dropped
+    fn request_global_stats(
+        &self,
+        history_days: u32,
+        callback: Callback<Result<(), SteamError>>,
+    ) -> Result<Option<Subscription>, SteamworksError>;
+    /// `None` until the global stats came in, or if it isn't an int stat.
+    fn global_stat_i64(&self, name: &str) -> Option<i64>;
+    /// `None` until the global stats came in, or if it isn't a float stat.
+    fn global_stat_f64(&self, name: &str) -> Option<f64>;
+    /// The daily totals, today first, at most `days` of them.
+    fn global_stat_history_i64(&self, name: &str, days: usize) -> Option<Vec<i64>>;
+    /// The daily totals, today first, at most `days` of them.
+    fn global_stat_history_f64(&self, name: &str, days: usize) -> Option<Vec<f64>>;
+
+    // Other users' stats
+    /// Calls back once `steam_id`'s stats and achievements came in, which the `user_*` reads need.
+    /// @returns what waits for Steam's answer, if anything does, it stops waiting once dropped
+    fn request_user_stats_for(
+        &self,
+        steam_id: SteamId,
+        callback: Callback<Result<(), SteamError>>,
+    ) -> Result<Option<Subscription>, SteamworksError>;
+    fn user_stat_i32(&self, steam_id: SteamId, name: &str) -> Result<i32, ()>;
+    fn user_stat_f32(&self, steam_id: SteamId, name: &str) -> Result<f32, ()>;
+    /// `(achieved, unlock_time)`, `None` if the achievement doesn't exist or the user's stats
+    /// weren't requested.
+    fn user_achievement(&self, steam_id: SteamId, name: &str) -> Option<(bool, u32)>;
+
+    // Leaderboards
+    /// Calls back with the leaderboard's handle, `None` if there's no such leaderboard. It's
+    /// created with the given sort method and display type when those are passed.
+    fn find_leaderboard(
+        &self,
+        name: &str,
+        create: Option<(SortMethod, DisplayType)>,
+        callback: Callback<Result<Option<u64>, SteamError>>,
+    );
+    fn leaderboard_name(&self, leaderboard: u64) -> String;
+    fn leaderboard_entry_count(&self, leaderboard: u64) -> i32;
+    fn leaderboard_sort_method(&self, leaderboard: u64) -> Option<SortMethod>;
+    fn leaderboard_display_type(&self, leaderboard: u64) -> Option<DisplayType>;
+    fn upload_leaderboard_score(
+        &self,
+        leaderboard: u64,
+        method: UploadScoreMethod,
+        score: i32,
+        details: &[i32],
+        callback: Callback<Result<ScoreUploaded, SteamError>>,
+    );
+    /// @returns what waits for Steam's answer, if anything does, it stops waiting once dropped
+    fn download_leaderboard_entries(
+        &self,
+        leaderboard: u64,
+        request: EntriesRequest,
+        callback: Callback<Result<Vec<LeaderboardEntry>, SteamError>>,
+    ) -> Result<Option<Subscription>, SteamworksError>;
+    /// @returns what waits for Steam's answer, if anything does, it stops waiting once dropped
+    fn attach_leaderboard_ugc(
+        &self,
+        leaderboard: u64,
+        ugc: u64,
+        callback: Callback<Result<(), SteamError>>,
+    ) -> Result<Option<Subscription>, SteamworksError>;
+
+    // Auth tickets, by a handle the backend hands out
+    /// Issues a session ticket for `identity`, calling back with Steam's answer once it can be sent.
+    /// @returns the ticket's handle and bytes
+    fn session_ticket(
+        &self,
+        identity: NetworkingIdentity,
+        callback: Callback<Result<(), SteamError>>,
+    ) -> Result<(u32, Vec<u8>), SteamworksError>;
+    /// Issues a Web API ticket for `identity`, calling back with its bytes once they're ready.
+    /// @returns the ticket's handle
+    fn web_api_ticket(
+        &self,
+        identity: &str,
+        callback: Callback<Result<Vec<u8>, SteamError>>,
+    ) -> Result<u32, SteamworksError>;
+    /// Cancels the ticket, a pending answer is never called back.
+    fn cancel_ticket(&self, ticket: u32);
+
+    // Cloud
+    fn is_cloud_enabled_for_account(&self) -> bool;
+    fn is_cloud_enabled_for_app(&self) -> bool;
+    fn set_cloud_enabled_for_app(&self, enabled: bool);
+    fn read_file(&self, name: &str) -> std::io::Result<Vec<u8>>;
+    fn write_file(&self, name: &str, content: &[u8]) -> std::io::Result<()>;
+    fn delete_file(&self, name: &str) -> bool;
+    fn file_exists(&self, name: &str) -> bool;
+    fn list_files(&self) -> Vec<(String, u64)>;
+
+    // Matchmaking
+    fn create_lobby(
+        &self,
+        lobby_type: steamworks::LobbyType,
+        max_members: u32,
+        callback: Callback<Result<LobbyId, SteamError>>,
+    );
+    fn join_lobby(&self, lobby: LobbyId, callback: Callback<Result<LobbyId, ()>>);
+    fn request_lobby_list(&self, callback: Callback<Result<Vec<LobbyId>, SteamError>>);
+    fn leave_lobby(&self, lobby: LobbyId);
+    fn lobby_member_count(&self, lobby: LobbyId) -> usize;
+    fn lobby_member_limit(&self, lobby: LobbyId) -> Option<usize>;
+    fn lobby_members(&self, lobby: LobbyId) -> Vec<SteamId>;
+    fn lobby_owner(&self, lobby: LobbyId) -> SteamId;
+    fn set_lobby_joinable(&self, lobby: LobbyId, joinable: bool) -> bool;
+    fn lobby_data(&self, lobby: LobbyId, key: &str) -> Option<String>;
+    fn set_lobby_data(&self, lobby: LobbyId, key: &str, value: &str) -> bool;
+    fn delete_lobby_data(&self, lobby: LobbyId, key: &str) -> bool;
+    fn lobby_full_data(&self, lobby: LobbyId) -> Vec<(String, String)>;
+    fn send_lobby_chat_message(&self, lobby: LobbyId, message: &[u8]) -> Result<(), SteamError>;
+    fn lobby_chat_entry(&self, lobby: LobbyId, chat_id: i32) -> Vec<u8>;
+
+    // Workshop
+    fn create_item(
+        &self,
+        app_id: AppId,
+        callback: Callback<Result<(PublishedFileId, bool), SteamError>>,
+    );
+    /// Returns the watch handle of the update when the backend can report upload progress.
+    fn submit_item_update(
+        &self,
+        app_id: AppId,
+        item: PublishedFileId,
+        details: UgcUpdate,
+        callback: Callback<Result<(PublishedFileId, bool), SteamError>>,
+    ) -> Option<steamworks::UpdateWatchHandle>;
+    fn subscribe_item(&self, item: PublishedFileId, callback: Callback<Result<(), SteamError>>);
+    fn unsubscribe_item(&self, item: PublishedFileId, callback: Callback<Result<(), SteamError>>);
+    fn delete_item(&self, item: PublishedFileId, callback: Callback<Result<(), SteamError>>);
+    fn item_state(&self, item: PublishedFileId) -> steamworks::ItemState;
+    fn item_install_info(&self, item: PublishedFileId) -> Option<InstallInfo>;
+    fn item_download_info(&self, item: PublishedFileId) -> Option<(u64, u64)>;
+    fn download_item(&self, item: PublishedFileId, high_priority: bool) -> bool;
+    fn subscribed_items(&self) -> Vec<PublishedFileId>;
+    /// Fetches the details of the given items, the boolean tells whether the response was cached.
+    fn query_items(
+        &self,
+        items: Vec<PublishedFileId>,
+        query_config: Option<WorkshopItemQueryConfig>,
+        callback: Callback<Result<(Vec<Option<WorkshopItem>>, bool), SteamError>>,
+    ) -> Result<(), SteamError>;
+
+    // Friends
+    /// Asks Steam for `steam_id`'s persona, `PersonaStateChange` is posted once it came in.
+    /// @returns false if Steam already has it
+    fn request_user_information(&self, steam_id: SteamId, require_name_only: bool) -> bool;
+    fn friend_info(&self, steam_id: SteamId) -> FriendInfo;
+
+    // Input
+    fn input_init(&self);
+    fn input_shutdown(&self);
+    fn input_run_frame(&self);
+    fn connected_controllers(&self) -> Vec<u64>;
+    fn action_set_handle(&self, name: &str) -> u64;
+    fn digital_action_handle(&self, name: &str) -> u64;
+    fn analog_action_handle(&self, name: &str) -> u64;
+    fn activate_action_set(&self, controller: u64, action_set: u64);
+    fn current_action_set(&self, controller: u64) -> u64;
+    fn digital_action_pressed(&self, controller: u64, action: u64) -> bool;
+    fn analog_action_vector(&self, controller: u64, action: u64) -> (f32, f32);
+    /// What `action` is bound to on the controller in `action_set`.
+    fn digital_action_origins(
+        &self,
+        controller: u64,
+        action_set: u64,
+        action: u64,
+    ) -> Vec<EInputActionOrigin>;
+    /// What `action` is bound to on the controller in `action_set`.
+    fn analog_action_origins(
+        &self,
+        controller: u64,
+        action_set: u64,
+        action: u64,
+    ) -> Vec<EInputActionOrigin>;
+    /// The path to the image Steam shows for `origin`.
+    fn action_origin_glyph(&self, origin: EInputActionOrigin) -> String;
+    fn input_type(&self, controller: u64) -> steamworks::InputType;
+    fn motion_data(&self, controller: u64) -> MotionData;
+    fn trigger_vibration(&self, controller: u64, left_speed: u16, right_speed: u16);
+}
@@ -0,0 +1,1124 @@
+use std::collections::{HashMap, HashSet};
+use std::ffi::{c_char, CStr, CString};
+use std::io::{Read, Write};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+use steamworks::networking_types::NetworkingIdentity;
+use steamworks::sys;
+use steamworks::{
+    AppId, AuthSessionTicketResponse, AuthTicket, Client, Leaderboard, LeaderboardDisplayType,
+    LeaderboardSortMethod, LobbyId, PublishedFileId, SteamError, SteamId, TicketForWebApiResponse,
+    UserStatsReceived,
+};
+
+use super::{BackendKind, Callback, SteamBackend};
+use crate::api::achievement::achievement::AchievementInfo;
+use crate::api::callback::callback::SteamCallback;
+use crate::api::callback::{GlobalStatsReceived, LeaderboardScoresDownloaded, LeaderboardUgcSet};
+use crate::api::friends::FriendInfo;
+use crate::api::input::input::MotionData;
+use crate::api::leaderboard::leaderboard::{
+    DisplayType, EntriesRequest, LeaderboardEntry, ScoreUploaded, SortMethod, UploadScoreMethod,
+};
+use crate::api::stats::stats::StatType;
+use crate::api::workshop::workshop::{InstallInfo, UgcUpdate};
+use crate::api::workshop_item::workshop::{
+    handle_query_config, WorkshopItem, WorkshopItemQueryConfig,
+};
+use crate::dispatch::Subscription;
+use crate::error::SteamworksError;
+use napi::bindgen_prelude::BigInt;
+
+/// Steam keeps at most this many days of global stat history.
+const MAX_HISTORY_DAYS: usize = 60;
+
+struct IssuedTicket {
+    ticket: AuthTicket,
+    /// Waits for Steam's answer on the ticket, which goes away with it.
+    _answer: Subscription,
+}
+
+pub struct SteamworksBackend {
+    client: Arc<Client>,
+    /// steamworks-rs can't make a `Leaderboard` out of a raw handle, so the found ones are kept.
+    leaderboards: Arc<Mutex<HashMap<u64, Leaderboard>>>,
+    /// steamworks-rs doesn't expose the raw handle, so the tickets get one of ours.
+    tickets: Mutex<HashMap<u32, IssuedTicket>>,
+    next_ticket: AtomicU32,
+}
+
+impl SteamworksBackend {
+    pub fn new(client: Arc<Client>) -> Self {
+        Self {
+            client,
+            leaderboards: Arc::default(),
+            tickets: Mutex::new(HashMap::new()),
+            next_ticket: AtomicU32::new(1),
+        }
+    }
+
+    /// Keeps the ticket along with what waits for its answer, or cancels it if there's nothing
+    /// to wait with.
+    fn issue_ticket(
+        &self,
+        ticket: AuthTicket,
+        answer: Result<Subscription, SteamworksError>,
+    ) -> Result<u32, SteamworksError> {
+        let answer = answer.inspect_err(|_| {
+            self.client.user().cancel_authentication_ticket(ticket);
+        })?;
+        let handle = self.next_ticket.fetch_add(1, Ordering::Relaxed);
+        self.tickets.lock().unwrap().insert(
+            handle,
+            IssuedTicket {
+                ticket,
+                _answer: answer,
+            },
+        );
+        Ok(handle)
+    }
+
+    /// @returns whether the achievement exists, whether it's unlocked and when
+    fn achievement_and_unlock_time(name: &CStr) -> Option<(bool, u32)> {
+        let mut achieved = false;
+        let mut unlock_time = 0;
+        let found = unsafe {
+            sys::SteamAPI_ISteamUserStats_GetAchievementAndUnlockTime(
+                sys::SteamAPI_SteamUserStats_v013(),
+                name.as_ptr(),
+                &mut achieved,
+                &mut unlock_time,
+            )
+        };
+        found.then_some((achieved, unlock_time))
+    }
+
+    fn achievement_display_attribute(name: &CStr, key: &CStr) -> String {
+        unsafe {
+            let value = sys::SteamAPI_ISteamUserStats_GetAchievementDisplayAttribute(
+                sys::SteamAPI_SteamUserStats_v013(),
+                name.as_ptr(),
+                key.as_ptr(),
+            );
+            if value.is_null() {
+                String::new()
+            } else {
+                CStr::from_ptr(value).to_string_lossy().into_owned()
+            }
+        }
+    }
+
+    fn leaderboard(&self, handle: u64) -> Option<Leaderboard> {
+        self.leaderboards.lock().unwrap().get(&handle).cloned()
+    }
+
+    fn most_achieved_info(
+        iterator: Option<i32>,
+        name: &mut [c_char],
+        percent: &mut f32,
+        achieved: &mut bool,
+    ) -> i32 {
+        unsafe {
+            let user_stats = sys::SteamAPI_SteamUserStats_v013();
+            match iterator {
+                None => sys::SteamAPI_ISteamUserStats_GetMostAchievedAchievementInfo(
+                    user_stats,
+                    name.as_mut_ptr(),
+                    name.len() as u32,
+                    percent,
+                    achieved,
+                ),
+                Some(previous) => sys::SteamAPI_ISteamUserStats_GetNextMostAchievedAchievementInfo(
+                    user_stats,
+                    previous,
+                    name.as_mut_ptr(),
+                    name.len() as u32,
+                    percent,
+                    achieved,
+                ),
+            }
+        }
+    }
+}
+
+impl SteamBackend for SteamworksBackend {
+    fn kind(&self) -> BackendKind {
+        BackendKind::Steam
+    }
+
+    fn run_callbacks(&self) {
+        self.client.run_callbacks();
+    }
+
+    fn app_id(&self) -> AppId {
+        self.client.utils().app_id()
+    }
+
+    fn steam_id(&self) -> SteamId {
+        self.client.user().steam_id()
+    }
+
+    fn build_id(&self) -> i32 {
+        unsafe {
+            steamworks::sys::SteamAPI_ISteamApps_GetAppBuildId(
+                steamworks::sys::SteamAPI_SteamApps_v008(),
+            )
+        }
+    }
+
+    fn is_logged_on(&self) -> bool {
+        unsafe {
+            steamworks::sys::SteamAPI_ISteamUser_BLoggedOn(
+                steamworks::sys::SteamAPI_SteamUser_v023(),
+            )
+        }
+    }
+
+    fn interfaces(&self) -> Vec<String> {
+        // The accessors return null when the client doesn't provide that interface version.
+        let interfaces = unsafe {
+            [
+                ("ISteamApps", !sys::SteamAPI_SteamApps_v008().is_null()),
+                (
+                    "ISteamFriends",
+                    !sys::SteamAPI_SteamFriends_v018().is_null(),
+                ),
+                ("ISteamInput", !sys::SteamAPI_SteamInput_v006().is_null()),
+                (
+                    "ISteamMatchmaking",
+                    !sys::SteamAPI_SteamMatchmaking_v009().is_null(),
+                ),
+                (
+                    "ISteamNetworking",
+                    !sys::SteamAPI_SteamNetworking_v006().is_null(),
+                ),
+                (
+                    "ISteamRemoteStorage",
+                    !sys::SteamAPI_SteamRemoteStorage_v016().is_null(),
+                ),
+                ("ISteamUGC", !sys::SteamAPI_SteamUGC_v021().is_null()),
+                ("ISteamUser", !sys::SteamAPI_SteamUser_v023().is_null()),
+                (
+                    "ISteamUserStats",
+                    !sys::SteamAPI_SteamUserStats_v013().is_null(),
+                ),
+                ("ISteamUtils", !sys::SteamAPI_SteamUtils_v010().is_null()),
+            ]
+        };
+        interfaces
+            .into_iter()
+            .filter(|(_, available)| *available)
+            .map(|(name, _)| name.to_string())
+            .collect()
+    }
+
+    fn request_user_stats(&self) {
+        self.client
+            .user_stats()
+            .request_user_stats(self.client.user().steam_id().raw());
+    }
+
+    fn get_stat_i32(&self, name: &str) -> Result<i32, ()> {
+        self.client.user_stats().get_stat_i32(name)
+    }
+
+    fn set_stat_i32(&self, name: &str, value: i32) -> Result<(), ()> {
+        self.client.user_stats().set_stat_i32(name, value)
+    }
+
+    fn get_stat_f32(&self, name: &str) -> Result<f32, ()> {
+        self.client.user_stats().get_stat_f32(name)
+    }
+
+    fn set_stat_f32(&self, name: &str, value: f32) -> Result<(), ()> {
+        self.client.user_stats().set_stat_f32(name, value)
+    }
+
+    fn update_avg_rate_stat(
+        &self,
+        name: &str,
+        count_this_session: f32,
+        session_length: f64,
+    ) -> Result<(), ()> {
+        let name = CString::new(name).map_err(|_| ())?;
+        let updated = unsafe {
+            steamworks::sys::SteamAPI_ISteamUserStats_UpdateAvgRateStat(
+                steamworks::sys::SteamAPI_SteamUserStats_v013(),
+                name.as_ptr(),
+                count_this_session,
+                session_length,
+            )
+        };
+        updated.then_some(()).ok_or(())
+    }
+
+    fn stat_type(&self, name: &str) -> Option<StatType> {
+        // Steam only reads a stat with the accessor of its declared type. Average rate stats are
+        // floats as far as it's concerned.
+        let stats = self.client.user_stats();
+        if stats.get_stat_i32(name).is_ok() {
+            Some(StatType::Int)
+        } else if stats.get_stat_f32(name).is_ok() {
+            Some(StatType::Float)
+        } else {
+            None
+        }
+    }
+
+    fn store_stats(&self) -> Result<(), ()> {
+        self.client.user_stats().store_stats()
+    }
+
+    fn reset_all_stats(&self, achievements_too: bool) -> Result<(), ()> {
+        self.client.user_stats().reset_all_stats(achievements_too)
+    }
+
+    fn get_achievement(&self, name: &str) -> Result<bool, ()> {
+        self.client.user_stats().achievement(name).get()
+    }
+
+    fn set_achievement(&self, name: &str) -> Result<(), ()> {
+        self.client.user_stats().achievement(name).set()
+    }
+
+    fn clear_achievement(&self, name: &str) -> Result<(), ()> {
+        self.client.user_stats().achievement(name).clear()
+    }
+
+    fn achievement_names(&self) -> Option<Vec<String>> {
+        self.client.user_stats().get_achievement_names()
+    }
+
+    fn achievement_info(&self, name: &str) -> Option<AchievementInfo> {
+        let api_name = CString::new(name).ok()?;
+        let (unlocked, unlock_time) = Self::achievement_and_unlock_time(&api_name)?;
+        Some(AchievementInfo {
+            name: name.to_string(),
+            display_name: Self::achievement_display_attribute(&api_name, c"name"),
+            description: Self::achievement_display_attribute(&api_name, c"desc"),
+            hidden: Self::achievement_display_attribute(&api_name, c"hidden") == "1",
+            unlocked,
+            // Steam reports 0 for achievements unlocked before it kept track of the time.
+            unlock_time: (unlocked && unlock_time != 0).then_some(unlock_time),
+            global_percent: self.achievement_achieved_percent(name).map(f64::from),
+        })
+    }
+
+    fn achievement_icon(&self, name: &str) -> Result<Option<(u32, u32, Vec<u8>)>, ()> {
+        let name = CString::new(name).map_err(|_| ())?;
+        Self::achievement_and_unlock_time(&name).ok_or(())?;
+        unsafe {
+            // 0 until the icon is fetched, `UserAchievementIconFetched` is posted then.
+            let image = sys::SteamAPI_ISteamUserStats_GetAchievementIcon(
+                sys::SteamAPI_SteamUserStats_v013(),
+                name.as_ptr(),
+            );
+            if image == 0 {
+                return Ok(None);
+            }
+            let utils = sys::SteamAPI_SteamUtils_v010();
+            let (mut width, mut height) = (0, 0);
+            if !sys::SteamAPI_ISteamUtils_GetImageSize(utils, image, &mut width, &mut height) {
+                return Err(());
+            }
+            let mut rgba = vec![0u8; width as usize * height as usize * 4];
+            if !sys::SteamAPI_ISteamUtils_GetImageRGBA(
+                utils,
+                image,
+                rgba.as_mut_ptr(),
+                rgba.len() as i32,
+            ) {
+                return Err(());
+            }
+            Ok(Some((width, height, rgba)))
+        }
+    }
+
+    fn indicate_achievement_progress(&self, name: &str, current: u32, max: u32) -> Result<(), ()> {
+        let name = CString::new(name).map_err(|_| ())?;
+        let shown = unsafe {
+            sys::SteamAPI_ISteamUserStats_IndicateAchievementProgress(
+                sys::SteamAPI_SteamUserStats_v013(),
+                name.as_ptr(),
+                current,
+                max,
+            )
+        };
+        shown.then_some(()).ok_or(())
+    }
+
+    fn request_global_achievement_percentages(&self, callback: Callback<Result<(), SteamError>>) {
+        self.client
+            .user_stats()
+            .request_global_achievement_percentages(move |result| callback(result.map(|_| ())));
+    }
+
+    fn achievement_achieved_percent(&self, name: &str) -> Option<f32> {
+        self.client
+            .user_stats()
+            .achievement(name)
+            .get_achievement_achieved_percent()
+            .ok()
+    }
+
+    fn most_achieved_achievements(&self) -> Vec<(String, f32, bool)> {
+        let mut achievements = Vec::new();
+        let mut name = [0 as c_char; 256];
+        let mut percent = 0.0;
+        let mut achieved = false;
+        let mut iterator = Self::most_achieved_info(None, &mut name, &mut percent, &mut achieved);
+        while iterator != -1 {
+            let achievement = unsafe { CStr::from_ptr(name.as_ptr()) };
+            achievements.push((
+                achievement.to_string_lossy().into_owned(),
+                percent,
+                achieved,
+            ));
+            iterator =
+                Self::most_achieved_info(Some(iterator), &mut name, &mut percent, &mut achieved);
+        }
+        achievements
+    }
+
+    fn request_global_stats(
+        &self,
+        history_days: u32,
+        callback: Callback<Result<(), SteamError>>,
+    ) -> Result<Option<Subscription>, SteamworksError> {
+        let game_id = u64::from(self.app_id().0);
+        let waiting = crate::dispatch::on_next(
+            SteamCallback::GlobalStatsReceived,
+            move |received: &GlobalStatsReceived| {
+                (received.game_id == game_id).then_some(received.result)
+            },
+            callback,
+        )?;
+        // steamworks-rs has no wrapper for the call, its answer is posted as a callback as well.
+        unsafe {
+            sys::SteamAPI_ISteamUserStats_RequestGlobalStats(
+                sys::SteamAPI_SteamUserStats_v013(),
+                history_days.min(MAX_HISTORY_DAYS as u32) as i32,
+            );
+        }
+        Ok(Some(waiting))
+    }
+
+    fn global_stat_i64(&self, name: &str) -> Option<i64> {
+        let name = CString::new(name).ok()?;
+        let mut value = 0;
+        let found = unsafe {
+            sys::SteamAPI_ISteamUserStats_GetGlobalStatInt64(
+                sys::SteamAPI_SteamUserStats_v013(),
+                name.as_ptr(),
+                &mut value,
+            )
+        };
+        found.then_some(value)
+    }
+
+    fn global_stat_f64(&self, name: &str) -> Option<f64> {
+        let name = CString::new(name).ok()?;
+        let mut value = 0.0;
+        let found = unsafe {
+            sys::SteamAPI_ISteamUserStats_GetGlobalStatDouble(
+                sys::SteamAPI_SteamUserStats_v013(),
+                name.as_ptr(),
+                &mut value,
+            )
+        };
+        found.then_some(value)
+    }
+
+    fn global_stat_history_i64(&self, name: &str, days: usize) -> Option<Vec<i64>> {
+        let name = CString::new(name).ok()?;
+        let mut history = vec![0i64; days.min(MAX_HISTORY_DAYS)];
+        let filled = unsafe {
+            sys::SteamAPI_ISteamUserStats_GetGlobalStatHistoryInt64(
+                sys::SteamAPI_SteamUserStats_v013(),
+                name.as_ptr(),
+                history.as_mut_ptr(),
+                std::mem::size_of_val(history.as_slice()) as u32,
+            )
+        };
+        // 0 when the stat doesn't exist or no history was requested.
+        (filled > 0).then(|| {
+            history.truncate(filled as usize);
+            history
+        })
+    }
+
+    fn global_stat_history_f64(&self, name: &str, days: usize) -> Option<Vec<f64>> {
+        let name = CString::new(name).ok()?;
+        let mut history = vec![0f64; days.min(MAX_HISTORY_DAYS)];
+        let filled = unsafe {
+            sys::SteamAPI_ISteamUserStats_GetGlobalStatHistoryDouble(
+                sys::SteamAPI_SteamUserStats_v013(),
+                name.as_ptr(),
+                history.as_mut_ptr(),
+                std::mem::size_of_val(history.as_slice()) as u32,
+            )
+        };
+        (filled > 0).then(|| {
+            history.truncate(filled as usize);
+            history
+        })
+    }
+
+    fn request_user_stats_for(
+        &self,
+        steam_id: SteamId,
+        callback: Callback<Result<(), SteamError>>,
+    ) -> Result<Option<Subscription>, SteamworksError> {
+        let waiting = crate::dispatch::on_next(
+            SteamCallback::UserStatsReceived,
+            move |received: &UserStatsReceived| {
+                (received.steam_id == steam_id).then_some(received.result)
+            },
+            callback,
+        )?;
+        self.client.user_stats().request_user_stats(steam_id.raw());
+        Ok(Some(waiting))
+    }
+
+    fn user_stat_i32(&self, steam_id: SteamId, name: &str) -> Result<i32, ()> {
+        let name = CString::new(name).map_err(|_| ())?;
+        let mut value = 0;
+        let found = unsafe {
+            sys::SteamAPI_ISteamUserStats_GetUserStatInt32(
+                sys::SteamAPI_SteamUserStats_v013(),
+                steam_id.raw(),
+                name.as_ptr(),
+                &mut value,
+            )
+        };
+        found.then_some(value).ok_or(())
+    }
+
+    fn user_stat_f32(&self, steam_id: SteamId, name: &str) -> Result<f32, ()> {
+        let name = CString::new(name).map_err(|_| ())?;
+        let mut value = 0.0;
+        let found = unsafe {
+            sys::SteamAPI_ISteamUserStats_GetUserStatFloat(
+                sys::SteamAPI_SteamUserStats_v013(),
+                steam_id.raw(),
+                name.as_ptr(),
+                &mut value,
+            )
+        };
+        found.then_some(value).ok_or(())
+    }
+
+    fn user_achievement(&self, steam_id: SteamId, name: &str) -> Option<(bool, u32)> {
+        let name = CString::new(name).ok()?;
+        let mut achieved = false;
+        let mut unlock_time = 0;
+        let found = unsafe {
+            sys::SteamAPI_ISteamUserStats_GetUserAchievementAndUnlockTime(
+                sys::SteamAPI_SteamUserStats_v013(),
+                steam_id.raw(),
+                name.as_ptr(),
+                &mut achieved,
+                &mut unlock_time,
+            )
+        };
+        found.then_some((achieved, unlock_time))
+    }
+
+    fn find_leaderboard(
+        &self,
+        name: &str,
+        create: Option<(SortMethod, DisplayType)>,
+        callback: Callback<Result<Option<u64>, SteamError>>,
+    ) {
+        // steamworks-rs panics on a name it can't pass on.
+        if name.contains('\0') {
+            return callback(Ok(None));
+        }
+        let leaderboards = self.leaderboards.clone();
+        let found = move |found: Result<Option<Leaderboard>, SteamError>| {
+            callback(found.map(|found| {
+                found.map(|leaderboard| {
+                    let handle = leaderboard.raw();
+                    leaderboards.lock().unwrap().insert(handle, leaderboard);
+                    handle
+                })
+            }))
+        };
+        let user_stats = self.client.user_stats();
+        match create {
+            None => user_stats.find_leaderboard(name, found),
+            Some((sort_method, display_type)) => user_stats.find_or_create_leaderboard(
+                name,
+                match sort_method {
+                    SortMethod::Ascending => LeaderboardSortMethod::Ascending,
+                    SortMethod::Descending => LeaderboardSortMethod::Descending,
+                },
+                match display_type {
+                    DisplayType::Numeric => LeaderboardDisplayType::Numeric,
+                    DisplayType::TimeSeconds => LeaderboardDisplayType::TimeSeconds,
+                    DisplayType::TimeMilliSeconds => LeaderboardDisplayType::TimeMilliSeconds,
+                },
+                found,
+            ),
+        }
+    }
+
+    fn leaderboard_name(&self, leaderboard: u64) -> String {
+        self.leaderboard(leaderboard)
+            .map(|leaderboard| self.client.user_stats().get_leaderboard_name(&leaderboard))
+            .unwrap_or_default()
+    }
+
+    fn leaderboard_entry_count(&self, leaderboard: u64) -> i32 {
+        self.leaderboard(leaderboard)
+            .map(|leaderboard| {
+                self.client
+                    .user_stats()
+                    .get_leaderboard_entry_count(&leaderboard)
+            })
+            .unwrap_or_default()
+    }
+
+    fn leaderboard_sort_method(&self, leaderboard: u64) -> Option<SortMethod> {
+        let leaderboard = self.leaderboard(leaderboard)?;
+        match self
+            .client
+            .user_stats()
+            .get_leaderboard_sort_method(&leaderboard)?
+        {
+            LeaderboardSortMethod::Ascending => Some(SortMethod::Ascending),
+            LeaderboardSortMethod::Descending => Some(SortMethod::Descending),
+        }
+    }
+
+    fn leaderboard_display_type(&self, leaderboard: u64) -> Option<DisplayType> {
+        let leaderboard = self.leaderboard(leaderboard)?;
+        match self
+            .client
+            .user_stats()
+            .get_leaderboard_display_type(&leaderboard)?
+        {
+            LeaderboardDisplayType::Numeric => Some(DisplayType::Numeric),
+            LeaderboardDisplayType::TimeSeconds => Some(DisplayType::TimeSeconds),
+            LeaderboardDisplayType::TimeMilliSeconds => Some(DisplayType::TimeMilliSeconds),
+        }
+    }
+
+    fn upload_leaderboard_score(
+        &self,
+        leaderboard: u64,
+        method: UploadScoreMethod,
+        score: i32,
+        details: &[i32],
+        callback: Callback<Result<ScoreUploaded, SteamError>>,
+    ) {
+        let Some(leaderboard) = self.leaderboard(leaderboard) else {
+            return callback(Err(SteamError::InvalidParameter));
+        };
+        self.client.user_stats().upload_leaderboard_score(
+            &leaderboard,
+            match method {
+                UploadScoreMethod::KeepBest => steamworks::UploadScoreMethod::KeepBest,
+                UploadScoreMethod::ForceUpdate => steamworks::UploadScoreMethod::ForceUpdate,
+            },
+            score,
+            details,
+            move |uploaded| {
+                callback(uploaded.and_then(|uploaded| {
+                    // `None` when Steam didn't take the score.
+                    let uploaded = uploaded.ok_or(SteamError::Generic)?;
+                    Ok(ScoreUploaded {
+                        score: uploaded.score,
+                        score_changed: uploaded.was_changed,
+                        global_rank_new: uploaded.global_rank_new,
+                        global_rank_previous: uploaded.global_rank_previous,
+                    })
+                }))
+            },
+        );
+    }
+
+    fn download_leaderboard_entries(
+        &self,
+        leaderboard: u64,
+        request: EntriesRequest,
+        callback: Callback<Result<Vec<LeaderboardEntry>, SteamError>>,
+    ) -> Result<Option<Subscription>, SteamworksError> {
+        if self.leaderboard(leaderboard).is_none() {
+            callback(Err(SteamError::InvalidParameter));
+            return Ok(None);
+        }
+        // Steam doesn't tell which download the entries answer, the leaderboard and, for given
+        // users, the users have to do.
+        let users: Option<HashSet<u64>> = match &request {
+            EntriesRequest::Users(users) => Some(users.iter().map(|user| user.raw()).collect()),
+            _ => None,
+        };
+        let waiting = crate::dispatch::on_next(
+            SteamCallback::LeaderboardScoresDownloaded,
+            move |downloaded: &LeaderboardScoresDownloaded| {
+                let requested = |entry: &LeaderboardEntry| {
+                    users.as_ref().map_or(true, |users| {
+                        users.contains(&entry.user.steam_id64.get_u64().1)
+                    })
+                };
+                (downloaded.leaderboard == leaderboard && downloaded.entries.iter().all(requested))
+                    .then(|| downloaded.entries.clone())
+            },
+            move |entries| callback(Ok(entries)),
+        )?;
+        // steamworks-rs doesn't read the attached UGC, nor downloads given users' entries.
+        unsafe {
+            let user_stats = sys::SteamAPI_SteamUserStats_v013();
+            match request {
+                EntriesRequest::Global { start, end } => {
+                    sys::SteamAPI_ISteamUserStats_DownloadLeaderboardEntries(
+                        user_stats,
+                        leaderboard,
+                        sys::ELeaderboardDataRequest::k_ELeaderboardDataRequestGlobal,
+                        start,
+                        end,
+                    )
+                }
+                EntriesRequest::AroundUser { start, end } => {
+                    sys::SteamAPI_ISteamUserStats_DownloadLeaderboardEntries(
+                        user_stats,
+                        leaderboard,
+                        sys::ELeaderboardDataRequest::k_ELeaderboardDataRequestGlobalAroundUser,
+                        start,
+                        end,
+                    )
+                }
+                EntriesRequest::Friends => {
+                    sys::SteamAPI_ISteamUserStats_DownloadLeaderboardEntries(
+                        user_stats,
+                        leaderboard,
+                        sys::ELeaderboardDataRequest::k_ELeaderboardDataRequestFriends,
+                        0,
+                        0,
+                    )
+                }
+                EntriesRequest::Users(users) => {
+                    let mut users: Vec<u64> = users.iter().map(|user| user.raw()).collect();
+                    sys::SteamAPI_ISteamUserStats_DownloadLeaderboardEntriesForUsers(
+                        user_stats,
+                        leaderboard,
+                        users.as_mut_ptr().cast(),
+                        users.len() as i32,
+                    )
+                }
+            };
+        }
+        Ok(Some(waiting))
+    }
+
+    fn attach_leaderboard_ugc(
+        &self,
+        leaderboard: u64,
+        ugc: u64,
+        callback: Callback<Result<(), SteamError>>,
+    ) -> Result<Option<Subscription>, SteamworksError> {
+        if self.leaderboard(leaderboard).is_none() {
+            callback(Err(SteamError::InvalidParameter));
+            return Ok(None);
+        }
+        let waiting = crate::dispatch::on_next(
+            SteamCallback::LeaderboardUgcSet,
+            move |set: &LeaderboardUgcSet| (set.leaderboard == leaderboard).then_some(set.result),
+            callback,
+        )?;
+        // steamworks-rs has no wrapper for the call.
+        unsafe {
+            sys::SteamAPI_ISteamUserStats_AttachLeaderboardUGC(
+                sys::SteamAPI_SteamUserStats_v013(),
+                leaderboard,
+                ugc,
+            );
+        }
+        Ok(Some(waiting))
+    }
+
+    fn session_ticket(
+        &self,
+        identity: NetworkingIdentity,
+        callback: Callback<Result<(), SteamError>>,
+    ) -> Result<(u32, Vec<u8>), SteamworksError> {
+        let (ticket, data) = self.client.user().authentication_session_ticket(identity);
+        let answer = crate::dispatch::on_next(
+            SteamCallback::AuthSessionTicketResponse,
+            move |response: &AuthSessionTicketResponse| {
+                (response.ticket == ticket).then_some(response.result)
+            },
+            callback,
+        );
+        Ok((self.issue_ticket(ticket, answer)?, data))
+    }
+
+    fn web_api_ticket(
+        &self,
+        identity: &str,
+        callback: Callback<Result<Vec<u8>, SteamError>>,
+    ) -> Result<u32, SteamworksError> {
+        let ticket = self
+            .client
+            .user()
+            .authentication_session_ticket_for_webapi(identity);
+        let answer = crate::dispatch::on_next(
+            SteamCallback::TicketForWebApiResponse,
+            move |response: &TicketForWebApiResponse| {
+                (response.ticket_handle == ticket).then(|| {
+                    let mut data = response.ticket.clone();
+                    data.truncate(response.ticket_len as usize);
+                    response.result.map(|()| data)
+                })
+            },
+            callback,
+        );
+        self.issue_ticket(ticket, answer)
+    }
+
+    fn cancel_ticket(&self, ticket: u32) {
+        let issued = self.tickets.lock().unwrap().remove(&ticket);
+        if let Some(issued) = issued {
+            self.client
+                .user()
+                .cancel_authentication_ticket(issued.ticket);
+        }
+    }
+
+    fn is_cloud_enabled_for_account(&self) -> bool {
+        self.client.remote_storage().is_cloud_enabled_for_account()
+    }
+
+    fn is_cloud_enabled_for_app(&self) -> bool {
+        self.client.remote_storage().is_cloud_enabled_for_app()
+    }
+
+    fn set_cloud_enabled_for_app(&self, enabled: bool) {
+        self.client
+            .remote_storage()
+            .set_cloud_enabled_for_app(enabled)
+    }
+
+    fn read_file(&self, name: &str) -> std::io::Result<Vec<u8>> {
+        let mut buf = Vec::new();
+        self.client
+            .remote_storage()
+            .file(name)
+            .read()
+            .read_to_end(&mut buf)?;
+        Ok(buf)
+    }
+
+    fn write_file(&self, name: &str, content: &[u8]) -> std::io::Result<()> {
+        self.client
+            .remote_storage()
+            .file(name)
+            .write()
+            .write_all(content)
+    }
+
+    fn delete_file(&self, name: &str) -> bool {
+        self.client.remote_storage().file(name).delete()
+    }
+
+    fn file_exists(&self, name: &str) -> bool {
+        self.client.remote_storage().file(name).exists()
+    }
+
+    fn list_files(&self) -> Vec<(String, u64)> {
+        self.client
+            .remote_storage()
+            .files()
+            .into_iter()
+            .map(|identity| (identity.name, identity.size))
+            .collect()
+    }
+
+    fn create_lobby(
+        &self,
+        lobby_type: steamworks::LobbyType,
+        max_members: u32,
+        callback: Callback<Result<LobbyId, SteamError>>,
+    ) {
+        self.client
+            .matchmaking()
+            .create_lobby(lobby_type, max_members, move |result| callback(result));
+    }
+
+    fn join_lobby(&self, lobby: LobbyId, callback: Callback<Result<LobbyId, ()>>) {
+        self.client
+            .matchmaking()
+            .join_lobby(lobby, move |result| callback(result));
+    }
+
+    fn request_lobby_list(&self, callback: Callback<Result<Vec<LobbyId>, SteamError>>) {
+        self.client
+            .matchmaking()
+            .request_lobby_list(move |result| callback(result));
+    }
+
+    fn leave_lobby(&self, lobby: LobbyId) {
+        self.client.matchmaking().leave_lobby(lobby);
+    }
+
+    fn lobby_member_count(&self, lobby: LobbyId) -> usize {
+        self.client.matchmaking().lobby_member_count(lobby)
+    }
+
+    fn lobby_member_limit(&self, lobby: LobbyId) -> Option<usize> {
+        self.client.matchmaking().lobby_member_limit(lobby)
+    }
+
+    fn lobby_members(&self, lobby: LobbyId) -> Vec<SteamId> {
+        self.client.matchmaking().lobby_members(lobby)
+    }
+
+    fn lobby_owner(&self, lobby: LobbyId) -> SteamId {
+        self.client.matchmaking().lobby_owner(lobby)
+    }
+
+    fn set_lobby_joinable(&self, lobby: LobbyId, joinable: bool) -> bool {
+        self.client
+            .matchmaking()
+            .set_lobby_joinable(lobby, joinable)
+    }
+
+    fn lobby_data(&self, lobby: LobbyId, key: &str) -> Option<String> {
+        self.client
+            .matchmaking()
+            .lobby_data(lobby, key)
+            .map(|s| s.to_string())
+    }
+
+    fn set_lobby_data(&self, lobby: LobbyId, key: &str, value: &str) -> bool {
+        self.client.matchmaking().set_lobby_data(lobby, key, value)
+    }
+
+    fn delete_lobby_data(&self, lobby: LobbyId, key: &str) -> bool {
+        self.client.matchmaking().delete_lobby_data(lobby, key)
+    }
+
+    fn lobby_full_data(&self, lobby: LobbyId) -> Vec<(String, String)> {
+        let matchmaking = self.client.matchmaking();
+        let count = matchmaking.lobby_data_count(lobby);
+        (0..count)
+            .filter_map(|i| matchmaking.lobby_data_by_index(lobby, i))
+            .collect()
+    }
+
+    fn send_lobby_chat_message(&self, lobby: LobbyId, message: &[u8]) -> Result<(), SteamError> {
+        self.client
+            .matchmaking()
+            .send_lobby_chat_message(lobby, message)
+    }
+
+    fn lobby_chat_entry(&self, lobby: LobbyId, chat_id: i32) -> Vec<u8> {
+        let mut buffer = vec![0u8; 4096];
+        self.client
+            .matchmaking()
+            .get_lobby_chat_entry(lobby, chat_id, &mut buffer);
+        buffer
+    }
+
+    fn create_item(
+        &self,
+        app_id: AppId,
+        callback: Callback<Result<(PublishedFileId, bool), SteamError>>,
+    ) {
+        self.client
+            .ugc()
+            .create_item(app_id, steamworks::FileType::Community, move |result| {
+                callback(result)
+            });
+    }
+
+    fn submit_item_update(
+        &self,
+        app_id: AppId,
+        item: PublishedFileId,
+        details: UgcUpdate,
+        callback: Callback<Result<(PublishedFileId, bool), SteamError>>,
+    ) -> Option<steamworks::UpdateWatchHandle> {
+        let update_handle = self.client.ugc().start_item_update(app_id, item);
+        Some(details.submit(update_handle, move |result| callback(result)))
+    }
+
+    fn subscribe_item(&self, item: PublishedFileId, callback: Callback<Result<(), SteamError>>) {
+        self.client
+            .ugc()
+            .subscribe_item(item, move |result| callback(result));
+    }
+
+    fn unsubscribe_item(&self, item: PublishedFileId, callback: Callback<Result<(), SteamError>>) {
+        self.client
+            .ugc()
+            .unsubscribe_item(item, move |result| callback(result));
+    }
+
+    fn delete_item(&self, item: PublishedFileId, callback: Callback<Result<(), SteamError>>) {
+        self.client
+            .ugc()
+            .delete_item(item, move |result| callback(result));
+    }
+
+    fn item_state(&self, item: PublishedFileId) -> steamworks::ItemState {
+        self.client.ugc().item_state(item)
+    }
+
+    fn item_install_info(&self, item: PublishedFileId) -> Option<InstallInfo> {
+        self.client
+            .ugc()
+            .item_install_info(item)
+            .map(|install_info| InstallInfo {
+                folder: install_info.folder,
+                size_on_disk: BigInt::from(install_info.size_on_disk),
+                timestamp: install_info.timestamp,
+            })
+    }
+
+    fn item_download_info(&self, item: PublishedFileId) -> Option<(u64, u64)> {
+        self.client.ugc().item_download_info(item)
+    }
+
+    fn download_item(&self, item: PublishedFileId, high_priority: bool) -> bool {
+        self.client.ugc().download_item(item, high_priority)
+    }
+
+    fn subscribed_items(&self) -> Vec<PublishedFileId> {
+        self.client.ugc().subscribed_items(false)
+    }
+
+    fn query_items(
+        &self,
+        items: Vec<PublishedFileId>,
+        query_config: Option<WorkshopItemQueryConfig>,
+        callback: Callback<Result<(Vec<Option<WorkshopItem>>, bool), SteamError>>,
+    ) -> Result<(), SteamError> {
+        let query_handle = self.client.ugc().query_items(items)?;
+        handle_query_config(query_handle, query_config).fetch(move |fetch_result| {
+            callback(fetch_result.map(|query_results| {
+                (
+                    (0..query_results.returned_results())
+                        .map(|i| WorkshopItem::from_query_results(&query_results, i))
+                        .collect(),
+                    query_results.was_cached(),
+                )
+            }))
+        });
+        Ok(())
+    }
+
+    fn request_user_information(&self, steam_id: SteamId, require_name_only: bool) -> bool {
+        self.client
+            .friends()
+            .request_user_information(steam_id, require_name_only)
+    }
+
+    fn friend_info(&self, steam_id: SteamId) -> FriendInfo {
+        self.client.friends().get_friend(steam_id).into()
+    }
+
+    fn input_init(&self) {
+        self.client.input().init(false);
+    }
+
+    fn input_shutdown(&self) {
+        self.client.input().shutdown()
+    }
+
+    fn input_run_frame(&self) {
+        self.client.input().run_frame();
+    }
+
+    fn connected_controllers(&self) -> Vec<u64> {
+        self.client.input().get_connected_controllers()
+    }
+
+    fn action_set_handle(&self, name: &str) -> u64 {
+        self.client.input().get_action_set_handle(name)
+    }
+
+    fn digital_action_handle(&self, name: &str) -> u64 {
+        self.client.input().get_digital_action_handle(name)
+    }
+
+    fn analog_action_handle(&self, name: &str) -> u64 {
+        self.client.input().get_analog_action_handle(name)
+    }
+
+    fn activate_action_set(&self, controller: u64, action_set: u64) {
+        self.client
+            .input()
+            .activate_action_set_handle(controller, action_set);
+    }
+
+    fn current_action_set(&self, controller: u64) -> u64 {
+        unsafe {
+            let x = steamworks::sys::SteamAPI_SteamInput_v006();
+            steamworks::sys::SteamAPI_ISteamInput_GetCurrentActionSet(x, controller)
+        }
+    }
+
+    fn digital_action_pressed(&self, controller: u64, action: u64) -> bool {
+        self.client
+            .input()
+            .get_digital_action_data(controller, action)
+            .bState
+    }
+
+    fn analog_action_vector(&self, controller: u64, action: u64) -> (f32, f32) {
+        let data = self
+            .client
+            .input()
+            .get_analog_action_data(controller, action);
+        (data.x, data.y)
+    }
+
+    fn digital_action_origins(
+        &self,
+        controller: u64,
+        action_set: u64,
+        action: u64,
+    ) -> Vec<sys::EInputActionOrigin> {
+        self.client
+            .input()
+            .get_digital_action_origins(controller, action_set, action)
+    }
+
+    fn analog_action_origins(
+        &self,
+        controller: u64,
+        action_set: u64,
+        action: u64,
+    ) -> Vec<sys::EInputActionOrigin> {
+        self.client
+            .input()
+            .get_analog_action_origins(controller, action_set, action)
+    }
+
+    fn action_origin_glyph(&self, origin: sys::EInputActionOrigin) -> String {
+        self.client.input().get_glyph_for_action_origin(origin)
+    }
+
+    fn input_type(&self, controller: u64) -> steamworks::InputType {
+        self.client.input().get_input_type_for_handle(controller)
+    }
+
+    fn motion_data(&self, controller: u64) -> MotionData {
+        self.client.input().get_motion_data(controller).into()
+    }
+
+    fn trigger_vibration(&self, controller: u64, left_speed: u16, right_speed: u16) {
+        unsafe {
+            let x = steamworks::sys::SteamAPI_SteamInput_v006();
+            steamworks::sys::SteamAPI_ISteamInput_TriggerVibration(
+                x,
+                controller,
+                left_speed,
+                right_speed,
+            );
+        }
+    }
+}
@@ -1,36 +1,86 @@
 use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 use steamworks::Client;
 
+use crate::backend::{SteamBackend, SteamworksBackend};
+use crate::error::SteamworksError;
+
+const RELEASE_TIMEOUT: Duration = Duration::from_secs(1);
+
 lazy_static! {
     static ref STEAM_CLIENT: Mutex<Option<Arc<Client>>> = Mutex::new(None);
+    static ref STEAM_BACKEND: Mutex<Option<Arc<dyn SteamBackend>>> = Mutex::new(None);
 }
 
 pub fn has_client() -> bool {
     STEAM_CLIENT.lock().unwrap().is_some()
 }
 
-pub fn get_client() -> Result<Arc<Client>, &'static str> {
+pub fn has_backend() -> bool {
+    STEAM_BACKEND.lock().unwrap().is_some()
+}
+
+pub fn get_client() -> Result<Arc<Client>, SteamworksError> {
     let option = STEAM_CLIENT.lock().unwrap().to_owned();
-    option.ok_or_else(|| "Steam client's not initialized.")
+    option.ok_or(SteamworksError::NotInitialized)
+}
+
+pub fn get_backend() -> Result<Arc<dyn SteamBackend>, SteamworksError> {
+    let option = STEAM_BACKEND.lock().unwrap().to_owned();
+    option.ok_or(SteamworksError::NotInitialized)
 }
 
 pub fn set_client(client: Client) {
+    let client = Arc::new(client);
+    *STEAM_BACKEND.lock().unwrap() = Some(Arc::new(SteamworksBackend::new(client.clone())));
     let mut client_ref = STEAM_CLIENT.lock().unwrap();
-    *client_ref = Some(Arc::new(client));
+    *client_ref = Some(client);
+}
+
+/// Installs a backend that is not backed by a `steamworks::Client`, e.g. the mock backend.
+pub fn set_backend(backend: Arc<dyn SteamBackend>) {
+    *STEAM_BACKEND.lock().unwrap() = Some(backend);
 }
 
 pub fn drop_client() {
+    // A running game server still needs the pump.
+    if !crate::server::has_server() {
+        crate::pump::stop();
+    }
+    crate::ready::reset();
+    crate::dispatch::reset();
+    crate::progress::reset(get_backend().ok().as_deref());
+    crate::journal::close();
+    crate::schema::reset();
+    // Flushed now, a recording left in the static would lose its buffered tail on exit.
+    if let Err(e) = crate::dispatch::stop_recording() {
+        log_warn!("Couldn't finish the callback recording: {}", e);
+    }
+    crate::resources::release();
+    // The steamworks backend holds a reference to the client, so it has to go first.
+    STEAM_BACKEND.lock().unwrap().take();
     if !has_client() {
         return;
     }
     let mut client_ref = STEAM_CLIENT.lock().unwrap();
     if let Some(arc) = client_ref.take() {
+        // Rejected operations let go of their references on the runtime's threads, give them a moment.
+        let deadline = Instant::now() + RELEASE_TIMEOUT;
+        while Arc::strong_count(&arc) > 1 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(5));
+        }
         match Arc::try_unwrap(arc) {
             Ok(client) => {
                 drop(client);
             }
             Err(arc) => {
-                println!("Warning: Tried to drop Steam client but there are still {} strong references to it.", Arc::strong_count(&arc));
+                log_warn!(
+                    "Tried to drop Steam client but there are still {} strong references to it.",
+                    Arc::strong_count(&arc)
+                );
+                *STEAM_BACKEND.lock().unwrap() =
+                    Some(Arc::new(SteamworksBackend::new(arc.clone())));
                 *client_ref = Some(arc);
             }
         }
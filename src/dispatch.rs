@@ -0,0 +1,356 @@
+//! Fans Steam callbacks out to every listener, JS handlers and Rust alike.
+//!
+//! steamworks-rs keeps a single handler per callback id, so each kind is registered with Steam
+//! once and every payload goes through `emit`. That's also where callbacks get recorded, and
+//! where a recording is played back into.
+//!
+//! Rust code waiting on a callback gets the steamworks-rs struct itself through `wait_for`,
+//! those waiters only ever see callbacks Steam really dispatched, never replayed ones.
+
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Value};
+use steamworks::CallbackHandle;
+use tokio::sync::oneshot;
+
+use crate::api::callback::callback::SteamCallback;
+use crate::error::SteamworksError;
+
+pub type Listener = Arc<dyn Fn(&Value) + Send + Sync>;
+type TypedListener = Arc<dyn Fn(&dyn Any) + Send + Sync>;
+
+#[derive(Default)]
+struct Channel {
+    // Kept for as long as the client lives, dropping a handle from inside a callback deadlocks.
+    handle: Option<CallbackHandle>,
+    listeners: BTreeMap<u64, Listener>,
+    typed: BTreeMap<u64, TypedListener>,
+}
+
+struct Recorder {
+    writer: BufWriter<File>,
+    started: Instant,
+    count: u32,
+}
+
+lazy_static! {
+    static ref CHANNELS: Mutex<HashMap<SteamCallback, Channel>> = Mutex::new(HashMap::new());
+    // Steam holds its own lock while dispatching, which ends up in `emit` taking `CHANNELS`, so
+    // registering happens outside of `CHANNELS` and this keeps it from happening twice.
+    static ref REGISTERING: Mutex<()> = Mutex::new(());
+    static ref RECORDER: Mutex<Option<Recorder>> = Mutex::new(None);
+}
+
+static NEXT_LISTENER: AtomicU64 = AtomicU64::new(1);
+
+/// Registers `kind` with Steam if the client is running and it isn't registered yet.
+fn register(kind: SteamCallback) -> Result<(), SteamworksError> {
+    crate::client::get_backend()?;
+    let Ok(client) = crate::client::get_client() else {
+        return Ok(());
+    };
+
+    let _registering = REGISTERING.lock().unwrap();
+    let registered = CHANNELS
+        .lock()
+        .unwrap()
+        .get(&kind)
+        .is_some_and(|channel| channel.handle.is_some());
+    if !registered {
+        let handle = crate::api::callback::callback::register_steam(kind, &client);
+        CHANNELS.lock().unwrap().entry(kind).or_default().handle = Some(handle);
+    }
+    Ok(())
+}
+
+/// Adds a listener for `kind`, registering the callback with Steam if the client is running.
+/// @returns the id to `unsubscribe` with
+pub fn subscribe(kind: SteamCallback, listener: Listener) -> Result<u64, SteamworksError> {
+    register(kind)?;
+    let id = NEXT_LISTENER.fetch_add(1, Ordering::Relaxed);
+    CHANNELS
+        .lock()
+        .unwrap()
+        .entry(kind)
+        .or_default()
+        .listeners
+        .insert(id, listener);
+    Ok(id)
+}
+
+fn subscribe_typed(kind: SteamCallback, listener: TypedListener) -> Result<u64, SteamworksError> {
+    register(kind)?;
+    let id = NEXT_LISTENER.fetch_add(1, Ordering::Relaxed);
+    CHANNELS
+        .lock()
+        .unwrap()
+        .entry(kind)
+        .or_default()
+        .typed
+        .insert(id, listener);
+    Ok(id)
+}
+
+/// @returns false if the listener was already gone
+pub fn unsubscribe(kind: SteamCallback, id: u64) -> bool {
+    CHANNELS
+        .lock()
+        .unwrap()
+        .get_mut(&kind)
+        .map(|channel| {
+            channel.listeners.remove(&id).is_some() || channel.typed.remove(&id).is_some()
+        })
+        .unwrap_or(false)
+}
+
+/// Unsubscribes when dropped, e.g. when the future waiting on it is.
+pub struct Subscription {
+    kind: SteamCallback,
+    id: u64,
+}
+
+impl Subscription {
+    pub fn new(kind: SteamCallback, listener: Listener) -> Result<Self, SteamworksError> {
+        Ok(Self {
+            kind,
+            id: subscribe(kind, listener)?,
+        })
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        unsubscribe(self.kind, self.id);
+    }
+}
+
+/// A callback being waited for, see `wait_for`.
+pub struct Waiter<T> {
+    subscription: Subscription,
+    rx: oneshot::Receiver<T>,
+}
+
+/// Hands the first `C` that `select` picks a value out of to `then`, on the thread Steam dispatches
+/// it on. Dropping the subscription stops waiting.
+pub fn on_next<C, T>(
+    kind: SteamCallback,
+    select: impl FnMut(&C) -> Option<T> + Send + 'static,
+    then: impl FnOnce(T) + Send + 'static,
+) -> Result<Subscription, SteamworksError>
+where
+    C: steamworks::Callback + 'static,
+    T: Send + 'static,
+{
+    let pending = Mutex::new(Some((select, then)));
+    let id = subscribe_typed(
+        kind,
+        Arc::new(move |value: &dyn Any| {
+            let Some(value) = value.downcast_ref::<C>() else {
+                return;
+            };
+            let mut pending = pending.lock().unwrap();
+            if let Some(selected) = pending.as_mut().and_then(|(select, _)| select(value)) {
+                if let Some((_, then)) = pending.take() {
+                    then(selected);
+                }
+            }
+        }),
+    )?;
+    Ok(Subscription { kind, id })
+}
+
+/// Starts waiting for the first `C` that `select` picks a value out of. Call it before whatever
+/// makes Steam post the callback, then `wait` on the result.
+pub fn wait_for<C, T>(
+    kind: SteamCallback,
+    select: impl FnMut(&C) -> Option<T> + Send + 'static,
+) -> Result<Waiter<T>, SteamworksError>
+where
+    C: steamworks::Callback + 'static,
+    T: Send + 'static,
+{
+    let (tx, rx) = oneshot::channel();
+    let subscription = on_next(kind, select, move |selected| {
+        let _ = tx.send(selected);
+    })?;
+    Ok(Waiter { subscription, rx })
+}
+
+impl<T> Waiter<T> {
+    /// Rejects with a TIMEOUT after `timeout`, and with SHUT_DOWN when the client goes away.
+    pub async fn wait(self, timeout: Duration) -> Result<T, SteamworksError> {
+        let kind = self.subscription.kind;
+        receive(self.rx, timeout, format!("{:?}", kind)).await
+    }
+}
+
+/// Waits for whatever Steam answers through `rx`, a callback or a call result, the same way
+/// `Waiter::wait` does.
+/// @param what - Names what's being waited for in the timeout error
+pub async fn receive<T>(
+    rx: oneshot::Receiver<T>,
+    timeout: Duration,
+    what: impl Display,
+) -> Result<T, SteamworksError> {
+    match crate::resources::until_shutdown(tokio::time::timeout(timeout, rx)).await? {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(e)) => Err(e.into()),
+        Err(_) => Err(SteamworksError::timeout(format!(
+            "Steam didn't answer {} in time.",
+            what
+        ))),
+    }
+}
+
+/// Hands the callback Steam dispatched to the `wait_for` waiters, before it's turned into JSON.
+pub fn emit_typed(kind: SteamCallback, value: &dyn Any) {
+    let typed: Vec<TypedListener> = match CHANNELS.lock().unwrap().get(&kind) {
+        Some(channel) => channel.typed.values().cloned().collect(),
+        None => return,
+    };
+    for listener in typed {
+        listener(value);
+    }
+}
+
+/// Records a callback Steam dispatched and hands it to the listeners.
+pub fn emit(kind: SteamCallback, payload: Value) {
+    record(kind, &payload);
+    deliver(kind, &payload);
+}
+
+fn deliver(kind: SteamCallback, payload: &Value) {
+    // Cloned out of the lock, listeners are free to subscribe and unsubscribe.
+    let listeners: Vec<Listener> = match CHANNELS.lock().unwrap().get(&kind) {
+        Some(channel) => channel.listeners.values().cloned().collect(),
+        None => return,
+    };
+    log_trace!("Dispatching {:?} to {} listeners", kind, listeners.len());
+    for listener in listeners {
+        listener(payload);
+    }
+}
+
+/// Unregisters everything from Steam and forgets the listeners, before the client goes away.
+pub fn reset() {
+    let channels = std::mem::take(&mut *CHANNELS.lock().unwrap());
+    drop(channels);
+}
+
+fn record(kind: SteamCallback, payload: &Value) {
+    let mut recorder = RECORDER.lock().unwrap();
+    let Some(recorder) = recorder.as_mut() else {
+        return;
+    };
+    let line = json!({
+        "time": recorder.started.elapsed().as_millis() as u64,
+        "timestamp": SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|now| now.as_millis() as u64)
+            .unwrap_or_default(),
+        "kind": kind.name(),
+        "payload": payload,
+    });
+    let result = serde_json::to_writer(&mut recorder.writer, &line)
+        .map_err(std::io::Error::from)
+        .and_then(|()| recorder.writer.write_all(b"\n"));
+    match result {
+        Ok(()) => recorder.count += 1,
+        Err(e) => log_warn!("Couldn't record {:?}: {}", kind, e),
+    }
+}
+
+/// Starts writing every dispatched callback to `path` as JSON lines, replacing a running recording.
+pub fn start_recording(path: &Path) -> Result<(), SteamworksError> {
+    let writer = BufWriter::new(File::create(path)?);
+    stop_recording()?;
+    *RECORDER.lock().unwrap() = Some(Recorder {
+        writer,
+        started: Instant::now(),
+        count: 0,
+    });
+    log_info!("Recording callbacks to {}", path.display());
+    Ok(())
+}
+
+/// @returns how many callbacks were recorded, `None` if nothing was recording
+pub fn stop_recording() -> Result<Option<u32>, SteamworksError> {
+    let recorder = RECORDER.lock().unwrap().take();
+    match recorder {
+        Some(mut recorder) => {
+            recorder.writer.flush()?;
+            Ok(Some(recorder.count))
+        }
+        None => Ok(None),
+    }
+}
+
+pub fn is_recording() -> bool {
+    RECORDER.lock().unwrap().is_some()
+}
+
+pub struct RecordedCallback {
+    pub time: Duration,
+    pub kind: SteamCallback,
+    pub payload: Value,
+}
+
+/// Reads a recording made by `start_recording`.
+pub fn read_recording(path: &Path) -> Result<Vec<RecordedCallback>, SteamworksError> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut records = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let invalid = |reason: &str| {
+            SteamworksError::invalid_parameter(format!(
+                "{}:{}: {}",
+                path.display(),
+                index + 1,
+                reason
+            ))
+        };
+        let mut value: Value = serde_json::from_str(&line).map_err(|e| invalid(&e.to_string()))?;
+        let kind = value["kind"]
+            .as_str()
+            .and_then(SteamCallback::from_name)
+            .ok_or_else(|| invalid("unknown callback kind"))?;
+        records.push(RecordedCallback {
+            time: Duration::from_millis(value["time"].as_u64().unwrap_or(0)),
+            kind,
+            payload: value["payload"].take(),
+        });
+    }
+    Ok(records)
+}
+
+/// Feeds recorded callbacks to the listeners, in order. Replayed callbacks aren't recorded again.
+/// @param realtime - Keep the recorded delays between callbacks, instead of replaying them back to back
+pub async fn replay(
+    records: Vec<RecordedCallback>,
+    realtime: bool,
+) -> Result<u32, SteamworksError> {
+    let started = Instant::now();
+    let mut count = 0;
+    for record in records {
+        if realtime {
+            let elapsed = started.elapsed();
+            if record.time > elapsed {
+                crate::resources::until_shutdown(tokio::time::sleep(record.time - elapsed)).await?;
+            }
+        }
+        deliver(record.kind, &record.payload);
+        count += 1;
+    }
+    Ok(count)
+}
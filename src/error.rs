@@ -0,0 +1,226 @@
+use std::fmt;
+
+use steamworks::SteamError;
+
+/// Stable error codes, exposed to JS as the `code` property of thrown errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    NotInitialized,
+    Timeout,
+    CallbackDropped,
+    InvalidParameter,
+    InvalidState,
+    NotFound,
+    LimitExceeded,
+    NoConnection,
+    NotLoggedOn,
+    AccessDenied,
+    Busy,
+    Cancelled,
+    Ignored,
+    ServiceUnavailable,
+    AuthFailed,
+    ShutDown,
+    Aborted,
+    Panic,
+    Io,
+    SteamError,
+    Failed,
+}
+
+impl AsRef<str> for ErrorCode {
+    fn as_ref(&self) -> &str {
+        match self {
+            ErrorCode::NotInitialized => "NOT_INITIALIZED",
+            ErrorCode::Timeout => "TIMEOUT",
+            ErrorCode::CallbackDropped => "CALLBACK_DROPPED",
+            ErrorCode::InvalidParameter => "INVALID_PARAMETER",
+            ErrorCode::InvalidState => "INVALID_STATE",
+            ErrorCode::NotFound => "NOT_FOUND",
+            ErrorCode::LimitExceeded => "LIMIT_EXCEEDED",
+            ErrorCode::NoConnection => "NO_CONNECTION",
+            ErrorCode::NotLoggedOn => "NOT_LOGGED_ON",
+            ErrorCode::AccessDenied => "ACCESS_DENIED",
+            ErrorCode::Busy => "BUSY",
+            ErrorCode::Cancelled => "CANCELLED",
+            ErrorCode::Ignored => "IGNORED",
+            ErrorCode::ServiceUnavailable => "SERVICE_UNAVAILABLE",
+            ErrorCode::AuthFailed => "AUTH_FAILED",
+            ErrorCode::ShutDown => "SHUT_DOWN",
+            // What Node uses for its own AbortError.
+            ErrorCode::Aborted => "ABORT_ERR",
+            ErrorCode::Panic => "PANIC",
+            ErrorCode::Io => "IO_ERROR",
+            ErrorCode::SteamError => "STEAM_ERROR",
+            ErrorCode::Failed => "FAILED",
+        }
+    }
+}
+
+impl From<&SteamError> for ErrorCode {
+    fn from(error: &SteamError) -> Self {
+        match error {
+            SteamError::Timeout => ErrorCode::Timeout,
+            SteamError::InvalidParameter => ErrorCode::InvalidParameter,
+            SteamError::InvalidState => ErrorCode::InvalidState,
+            SteamError::FileNotFound => ErrorCode::NotFound,
+            SteamError::LimitExceeded => ErrorCode::LimitExceeded,
+            SteamError::NoConnection => ErrorCode::NoConnection,
+            SteamError::NotLoggedOn => ErrorCode::NotLoggedOn,
+            SteamError::AccessDenied => ErrorCode::AccessDenied,
+            SteamError::Busy => ErrorCode::Busy,
+            SteamError::Cancelled => ErrorCode::Cancelled,
+            SteamError::Ignored => ErrorCode::Ignored,
+            SteamError::ServiceUnavailable => ErrorCode::ServiceUnavailable,
+            _ => ErrorCode::SteamError,
+        }
+    }
+}
+
+/// The error type every module in this crate reports failures with.
+#[derive(Debug)]
+pub enum SteamworksError {
+    /// `init` hasn't been called, or the client has been shut down.
+    NotInitialized,
+    /// `initServer` hasn't been called, or the game server has been shut down.
+    ServerNotInitialized,
+    /// Steam didn't answer in time.
+    Timeout(String),
+    /// The callback that was supposed to resolve the operation went away without answering.
+    CallbackDropped,
+    InvalidParameter(String),
+    NotFound(String),
+    /// Steam refused an auth ticket.
+    AuthFailed(String),
+    /// The client was shut down while the operation was pending.
+    ShutDown,
+    /// The operation's `AbortSignal` was aborted.
+    Aborted,
+    /// A bug in this crate, the process keeps running.
+    Panic {
+        message: String,
+        backtrace: String,
+    },
+    Io(std::io::Error),
+    Steam(SteamError),
+    /// Steam reported a failure without any more details.
+    Failed(String),
+}
+
+pub type Result<T, E = napi::Error<ErrorCode>> = std::result::Result<T, E>;
+
+impl SteamworksError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            SteamworksError::NotInitialized | SteamworksError::ServerNotInitialized => {
+                ErrorCode::NotInitialized
+            }
+            SteamworksError::Timeout(_) => ErrorCode::Timeout,
+            SteamworksError::CallbackDropped => ErrorCode::CallbackDropped,
+            SteamworksError::InvalidParameter(_) => ErrorCode::InvalidParameter,
+            SteamworksError::NotFound(_) => ErrorCode::NotFound,
+            SteamworksError::AuthFailed(_) => ErrorCode::AuthFailed,
+            SteamworksError::ShutDown => ErrorCode::ShutDown,
+            SteamworksError::Aborted => ErrorCode::Aborted,
+            SteamworksError::Panic { .. } => ErrorCode::Panic,
+            SteamworksError::Io(_) => ErrorCode::Io,
+            SteamworksError::Steam(e) => e.into(),
+            SteamworksError::Failed(_) => ErrorCode::Failed,
+        }
+    }
+
+    pub fn timeout(message: impl Into<String>) -> Self {
+        SteamworksError::Timeout(message.into())
+    }
+
+    pub fn invalid_parameter(message: impl Into<String>) -> Self {
+        SteamworksError::InvalidParameter(message.into())
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        SteamworksError::NotFound(message.into())
+    }
+
+    pub fn failed(message: impl Into<String>) -> Self {
+        SteamworksError::Failed(message.into())
+    }
+
+    /// The backend has no stat by that name.
+    pub fn unknown_stat(name: &str) -> Self {
+        SteamworksError::NotFound(format!("There's no stat named {}", name))
+    }
+
+    /// The backend has no achievement by that name.
+    pub fn unknown_achievement(name: &str) -> Self {
+        SteamworksError::NotFound(format!("There's no achievement named {}", name))
+    }
+}
+
+impl fmt::Display for SteamworksError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SteamworksError::NotInitialized => write!(f, "Steam client's not initialized."),
+            SteamworksError::ServerNotInitialized => {
+                write!(f, "Steam game server's not initialized.")
+            }
+            SteamworksError::Timeout(message)
+            | SteamworksError::InvalidParameter(message)
+            | SteamworksError::NotFound(message)
+            | SteamworksError::AuthFailed(message)
+            | SteamworksError::Failed(message) => write!(f, "{}", message),
+            SteamworksError::CallbackDropped => {
+                write!(f, "Steam dropped the callback before it answered.")
+            }
+            SteamworksError::ShutDown => {
+                write!(
+                    f,
+                    "The Steam client was shut down before the operation finished."
+                )
+            }
+            SteamworksError::Aborted => write!(f, "The operation was aborted."),
+            SteamworksError::Panic { message, backtrace } => {
+                write!(f, "{}\n\nstack backtrace:\n{}", message, backtrace)
+            }
+            SteamworksError::Io(e) => write!(f, "{}", e),
+            SteamworksError::Steam(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for SteamworksError {}
+
+impl From<SteamError> for SteamworksError {
+    fn from(error: SteamError) -> Self {
+        SteamworksError::Steam(error)
+    }
+}
+
+impl From<std::io::Error> for SteamworksError {
+    fn from(error: std::io::Error) -> Self {
+        SteamworksError::Io(error)
+    }
+}
+
+impl From<tokio::sync::oneshot::error::RecvError> for SteamworksError {
+    fn from(_: tokio::sync::oneshot::error::RecvError) -> Self {
+        SteamworksError::CallbackDropped
+    }
+}
+
+impl From<tokio::time::error::Elapsed> for SteamworksError {
+    fn from(_: tokio::time::error::Elapsed) -> Self {
+        SteamworksError::timeout("Steam did not callback in time")
+    }
+}
+
+impl From<napi::Error> for SteamworksError {
+    fn from(error: napi::Error) -> Self {
+        SteamworksError::Failed(error.reason)
+    }
+}
+
+impl From<SteamworksError> for napi::Error<ErrorCode> {
+    fn from(error: SteamworksError) -> Self {
+        napi::Error::new(error.code(), error.to_string())
+    }
+}
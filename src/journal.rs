@@ -0,0 +1,520 @@
+//! Stat and achievement writes Steam couldn't store, kept in a file until it can.
+//!
+//! Every write is remembered until a store goes through. When a store fails, e.g. while Steam's
+//! servers are unreachable, the writes go into the journal file, and they're replayed on
+//! `SteamServersConnected`, or once the user's stats come in on the next launch. Replaying only
+//! ever raises a stat and only unlocks achievements, so progress made elsewhere in the meantime
+//! isn't lost either. Average rate sessions aren't journaled, replaying one isn't idempotent the
+//! way raising a stat is, and it could be counted twice.
+//!
+//! A journal belongs to the user and app it was written for, it's kept in a file of their own and
+//! never replayed into another's stats.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use serde_json::{json, Value};
+
+use crate::api::callback::callback::SteamCallback;
+use crate::backend::SteamBackend;
+
+/// The layout of the file, a journal of another version is discarded.
+const VERSION: u64 = 1;
+
+/// Whose stats a journal holds writes to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Owner {
+    steam_id: u64,
+    app_id: u32,
+}
+
+impl Owner {
+    fn of(backend: &dyn SteamBackend) -> Self {
+        Owner {
+            steam_id: backend.steam_id().raw(),
+            app_id: backend.app_id().0,
+        }
+    }
+
+    /// `path` with the owner added to the file name, e.g. `journal-76561197960265729-480.json`.
+    fn path(&self, path: &Path) -> PathBuf {
+        let mut name = path.file_stem().map(OsString::from).unwrap_or_default();
+        name.push(format!("-{}-{}", self.steam_id, self.app_id));
+        if let Some(extension) = path.extension() {
+            name.push(".");
+            name.push(extension);
+        }
+        path.with_file_name(name)
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Writes {
+    ints: BTreeMap<String, i32>,
+    floats: BTreeMap<String, f32>,
+    unlocks: BTreeSet<String>,
+}
+
+impl Writes {
+    fn is_empty(&self) -> bool {
+        self.ints.is_empty() && self.floats.is_empty() && self.unlocks.is_empty()
+    }
+
+    /// Takes `other` in, the higher value wins for stats in both.
+    fn merge(&mut self, other: Writes) {
+        for (name, value) in other.ints {
+            let merged = self.ints.entry(name).or_insert(value);
+            *merged = (*merged).max(value);
+        }
+        for (name, value) in other.floats {
+            let merged = self.floats.entry(name).or_insert(value);
+            *merged = merged.max(value);
+        }
+        self.unlocks.extend(other.unlocks);
+    }
+
+    fn to_json(&self, owner: Owner) -> Value {
+        json!({
+            "version": VERSION,
+            "steam_id": owner.steam_id,
+            "app_id": owner.app_id,
+            "ints": self.ints,
+            "floats": self.floats,
+            "unlocks": self.unlocks,
+        })
+    }
+
+    fn from_json(value: &Value) -> Option<Self> {
+        let mut writes = Writes::default();
+        for (name, value) in value["ints"].as_object()? {
+            writes
+                .ints
+                .insert(name.clone(), i32::try_from(value.as_i64()?).ok()?);
+        }
+        for (name, value) in value["floats"].as_object()? {
+            writes.floats.insert(name.clone(), value.as_f64()? as f32);
+        }
+        for name in value["unlocks"].as_array()? {
+            writes.unlocks.insert(name.as_str()?.to_string());
+        }
+        Some(writes)
+    }
+
+    /// Reads a journal file back, or tells why it's discarded.
+    fn parse(content: &str, owner: Owner) -> Result<Self, String> {
+        let value = serde_json::from_str::<Value>(content).map_err(|e| e.to_string())?;
+        if value["version"].as_u64() != Some(VERSION) {
+            return Err(format!("its version {} is unknown", value["version"]));
+        }
+        if value["steam_id"].as_u64() != Some(owner.steam_id)
+            || value["app_id"].as_u64() != Some(u64::from(owner.app_id))
+        {
+            return Err(format!(
+                "it's another user's or app's, not {} in {}",
+                owner.steam_id, owner.app_id
+            ));
+        }
+        Writes::from_json(&value).ok_or_else(|| "it's malformed".to_string())
+    }
+}
+
+struct Journal {
+    path: PathBuf,
+    owner: Owner,
+    /// Written since the last store that went through.
+    pending: Writes,
+    /// In the file, waiting to be replayed.
+    journaled: Writes,
+    subscriptions: Vec<(SteamCallback, u64)>,
+}
+
+impl Journal {
+    /// Whether the backend is still logged in as the user the journal was opened for.
+    fn owned_by(&self, backend: &dyn SteamBackend) -> bool {
+        Owner::of(backend) == self.owner
+    }
+
+    /// Writes the journaled writes out, or removes the file once there are none.
+    fn save(&self) {
+        let result = if self.journaled.is_empty() {
+            match fs::remove_file(&self.path) {
+                Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+                result => result,
+            }
+        } else {
+            // Written next to it first, a crash halfway through leaves the old journal intact.
+            let temporary = self.path.with_extension("tmp");
+            fs::write(&temporary, self.journaled.to_json(self.owner).to_string())
+                .and_then(|()| fs::rename(&temporary, &self.path))
+        };
+        if let Err(e) = result {
+            log_warn!(
+                "Couldn't update the stat journal {}: {}",
+                self.path.display(),
+                e
+            );
+        }
+    }
+}
+
+lazy_static! {
+    static ref JOURNAL: Mutex<Option<Journal>> = Mutex::new(None);
+}
+
+fn load(path: &Path, owner: Owner) -> Writes {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Writes::default(),
+        Err(e) => {
+            log_warn!("Couldn't read the stat journal {}: {}", path.display(), e);
+            return Writes::default();
+        }
+    };
+    Writes::parse(&content, owner).unwrap_or_else(|reason| {
+        log_warn!("Discarding the stat journal {}, {}", path.display(), reason);
+        Writes::default()
+    })
+}
+
+/// Starts journaling for the logged in user into `path`, with their Steam ID and the app ID added
+/// to the file name. What's already in it gets replayed once the stats come in.
+pub fn open(path: PathBuf, backend: &dyn SteamBackend) {
+    close();
+
+    let owner = Owner::of(backend);
+    let path = owner.path(&path);
+    let local_id = owner.steam_id;
+    let subscriptions = [
+        (
+            SteamCallback::SteamServersConnected,
+            crate::dispatch::subscribe(
+                SteamCallback::SteamServersConnected,
+                Arc::new(|_: &Value| replay()),
+            ),
+        ),
+        (
+            SteamCallback::UserStatsReceived,
+            crate::dispatch::subscribe(
+                SteamCallback::UserStatsReceived,
+                Arc::new(move |received: &Value| {
+                    if received["steam_id"].as_u64() == Some(local_id)
+                        && received["success"].as_bool() == Some(true)
+                    {
+                        replay();
+                    }
+                }),
+            ),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(kind, id)| match id {
+        Ok(id) => Some((kind, id)),
+        Err(e) => {
+            log_warn!("Couldn't watch {:?}: {}", kind, e);
+            None
+        }
+    })
+    .collect();
+
+    let journaled = load(&path, owner);
+    log_debug!(
+        "Journaling stats into {}, {} writes to replay",
+        path.display(),
+        journaled.ints.len() + journaled.floats.len() + journaled.unlocks.len()
+    );
+    *JOURNAL.lock().unwrap() = Some(Journal {
+        path,
+        owner,
+        pending: Writes::default(),
+        journaled,
+        subscriptions,
+    });
+}
+
+/// Stops journaling, called when the client shuts down. The file is left for the next launch.
+pub fn close() {
+    let Some(journal) = JOURNAL.lock().unwrap().take() else {
+        return;
+    };
+    for (kind, id) in journal.subscriptions {
+        crate::dispatch::unsubscribe(kind, id);
+    }
+}
+
+pub fn int_written(name: &str, value: i32) {
+    if let Some(journal) = JOURNAL.lock().unwrap().as_mut() {
+        journal.pending.ints.insert(name.to_string(), value);
+    }
+}
+
+pub fn float_written(name: &str, value: f32) {
+    if let Some(journal) = JOURNAL.lock().unwrap().as_mut() {
+        journal.pending.floats.insert(name.to_string(), value);
+    }
+}
+
+/// Achievements only move forward when replayed, so locking one again drops its unlock from the
+/// journal as well.
+pub fn achievement_written(name: &str, unlocked: bool) {
+    let mut journal = JOURNAL.lock().unwrap();
+    let Some(journal) = journal.as_mut() else {
+        return;
+    };
+    if unlocked {
+        journal.pending.unlocks.insert(name.to_string());
+    } else {
+        journal.pending.unlocks.remove(name);
+        if journal.journaled.unlocks.remove(name) {
+            journal.save();
+        }
+    }
+}
+
+/// Forgets every write, called when the stats are reset.
+pub fn clear() {
+    if let Some(journal) = JOURNAL.lock().unwrap().as_mut() {
+        journal.pending = Writes::default();
+        journal.journaled = Writes::default();
+        journal.save();
+    }
+}
+
+/// Stores the stats, journaling the writes since the last store if that fails.
+pub fn store(backend: &dyn SteamBackend) -> Result<(), ()> {
+    let stored = backend.store_stats();
+    if let Some(journal) = JOURNAL.lock().unwrap().as_mut() {
+        let pending = std::mem::take(&mut journal.pending);
+        if !journal.owned_by(backend) {
+            log_debug!("Another user is logged in, not journaling their stats");
+        } else if stored.is_err() && !pending.is_empty() {
+            log_debug!("Couldn't store the stats, journaling them");
+            journal.journaled.merge(pending);
+            journal.save();
+        }
+    }
+    stored
+}
+
+/// Forgets the writes since the last store, called when the stats were stored without `store`.
+pub fn stored() {
+    if let Some(journal) = JOURNAL.lock().unwrap().as_mut() {
+        journal.pending = Writes::default();
+    }
+}
+
+/// Applies the journaled writes and stores them. Stats are only raised, achievements only
+/// unlocked, and writes to stats or achievements that don't exist anymore are dropped.
+pub fn replay() {
+    let Ok(backend) = crate::client::get_backend() else {
+        return;
+    };
+    let mut journal = JOURNAL.lock().unwrap();
+    let Some(journal) = journal.as_mut().filter(|j| !j.journaled.is_empty()) else {
+        return;
+    };
+    if !journal.owned_by(&*backend) {
+        log_warn!(
+            "Not replaying the stat journal {} into another user's stats",
+            journal.path.display()
+        );
+        return;
+    }
+
+    let writes = &journal.journaled;
+    for (name, &value) in &writes.ints {
+        match backend.get_stat_i32(name) {
+            Ok(current) if current >= value => {}
+            Ok(_) => {
+                if backend.set_stat_i32(name, value).is_err() {
+                    log_warn!("Couldn't replay {} = {}", name, value);
+                }
+            }
+            Err(()) => log_warn!("Dropping the journaled write to the unknown stat {}", name),
+        }
+    }
+    for (name, &value) in &writes.floats {
+        match backend.get_stat_f32(name) {
+            Ok(current) if current >= value => {}
+            Ok(_) => {
+                if backend.set_stat_f32(name, value).is_err() {
+                    log_warn!("Couldn't replay {} = {}", name, value);
+                }
+            }
+            Err(()) => log_warn!("Dropping the journaled write to the unknown stat {}", name),
+        }
+    }
+    for name in &writes.unlocks {
+        match backend.get_achievement(name) {
+            Ok(true) => {}
+            Ok(false) => {
+                if backend.set_achievement(name).is_err() {
+                    log_warn!("Couldn't replay the unlock of {}", name);
+                }
+            }
+            Err(()) => log_warn!(
+                "Dropping the journaled unlock of the unknown achievement {}",
+                name
+            ),
+        }
+    }
+
+    if backend.store_stats().is_ok() {
+        log_info!("Replayed the stat journal {}", journal.path.display());
+        journal.pending = Writes::default();
+        journal.journaled = Writes::default();
+        journal.save();
+    } else {
+        log_debug!("Still can't store the stats, keeping the journal");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OWNER: Owner = Owner {
+        steam_id: 76561197960265729,
+        app_id: 480,
+    };
+
+    fn writes(ints: &[(&str, i32)], floats: &[(&str, f32)], unlocks: &[&str]) -> Writes {
+        Writes {
+            ints: ints
+                .iter()
+                .map(|&(name, v)| (name.to_string(), v))
+                .collect(),
+            floats: floats
+                .iter()
+                .map(|&(name, v)| (name.to_string(), v))
+                .collect(),
+            unlocks: unlocks.iter().map(|name| name.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn path_has_the_owner() {
+        assert_eq!(
+            OWNER.path(Path::new("saves/journal.json")),
+            PathBuf::from("saves/journal-76561197960265729-480.json")
+        );
+        assert_eq!(
+            OWNER.path(Path::new("journal")),
+            PathBuf::from("journal-76561197960265729-480")
+        );
+    }
+
+    #[test]
+    fn parse_reads_back_what_was_written() {
+        let written = writes(&[("kills", 3)], &[("distance", 1.5)], &["ACH_WIN"]);
+        let content = written.to_json(OWNER).to_string();
+        assert_eq!(Writes::parse(&content, OWNER), Ok(written));
+    }
+
+    #[test]
+    fn parse_rejects_an_unknown_version() {
+        let mut value = writes(&[("kills", 3)], &[], &[]).to_json(OWNER);
+        value["version"] = json!(VERSION + 1);
+        assert!(Writes::parse(&value.to_string(), OWNER).is_err());
+
+        value.as_object_mut().unwrap().remove("version");
+        assert!(Writes::parse(&value.to_string(), OWNER).is_err());
+    }
+
+    #[test]
+    fn parse_rejects_another_owner() {
+        let written = writes(&[("kills", 3)], &[], &[]);
+        let other_user = Owner {
+            steam_id: OWNER.steam_id + 1,
+            ..OWNER
+        };
+        let other_app = Owner {
+            app_id: OWNER.app_id + 1,
+            ..OWNER
+        };
+        for owner in [other_user, other_app] {
+            let content = written.to_json(owner).to_string();
+            assert!(Writes::parse(&content, OWNER).is_err());
+        }
+    }
+
+    #[test]
+    fn parse_rejects_a_malformed_file() {
+        assert!(Writes::parse("", OWNER).is_err());
+        assert!(Writes::parse("{\"version\": 1", OWNER).is_err());
+
+        let mut value = Writes::default().to_json(OWNER);
+        value["ints"] = json!({ "kills": "three" });
+        assert!(Writes::parse(&value.to_string(), OWNER).is_err());
+
+        let mut value = Writes::default().to_json(OWNER);
+        value["ints"] = json!({ "kills": i64::from(i32::MAX) + 1 });
+        assert!(Writes::parse(&value.to_string(), OWNER).is_err());
+
+        let mut value = Writes::default().to_json(OWNER);
+        value.as_object_mut().unwrap().remove("unlocks");
+        assert!(Writes::parse(&value.to_string(), OWNER).is_err());
+    }
+
+    #[test]
+    fn merge_keeps_the_higher_value_whichever_side_it_is_on() {
+        let older = || {
+            writes(
+                &[("kills", 5), ("deaths", 1)],
+                &[("distance", 2.5)],
+                &["ACH_WIN"],
+            )
+        };
+        let newer = || {
+            writes(
+                &[("kills", 3), ("wins", 2)],
+                &[("distance", 4.0), ("speed", 1.0)],
+                &["ACH_TRAVEL"],
+            )
+        };
+        let merged = writes(
+            &[("deaths", 1), ("kills", 5), ("wins", 2)],
+            &[("distance", 4.0), ("speed", 1.0)],
+            &["ACH_TRAVEL", "ACH_WIN"],
+        );
+
+        let mut forward = older();
+        forward.merge(newer());
+        assert_eq!(forward, merged);
+
+        let mut backward = newer();
+        backward.merge(older());
+        assert_eq!(backward, merged);
+    }
+
+    #[test]
+    fn load_starts_empty_without_a_usable_file() {
+        let dir = std::env::temp_dir().join(format!("journal-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let missing = dir.join("missing.json");
+        assert!(load(&missing, OWNER).is_empty());
+
+        let foreign = dir.join("foreign.json");
+        let other = Owner {
+            steam_id: OWNER.steam_id + 1,
+            ..OWNER
+        };
+        fs::write(
+            &foreign,
+            writes(&[("kills", 3)], &[], &[]).to_json(other).to_string(),
+        )
+        .unwrap();
+        assert!(load(&foreign, OWNER).is_empty());
+
+        let own = dir.join("own.json");
+        let written = writes(&[("kills", 3)], &[], &["ACH_WIN"]);
+        fs::write(&own, written.to_json(OWNER).to_string()).unwrap();
+        assert_eq!(load(&own, OWNER), written);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,58 +1,456 @@
-use napi::bindgen_prelude::Error;
+use std::fs;
+use std::io;
+use std::net::Ipv4Addr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use abort::AbortSignal;
+use api::localplayer::PlayerSteamId;
+
+use api::server::server::ServerMode;
+use backend::{BackendKind, MockBackend};
+use error::{Result, SteamworksError};
+use logging::LogLevel;
+use napi::bindgen_prelude::Function;
 use napi_derive::napi;
 use steamworks::AppId;
 use steamworks::Client;
+use steamworks::Server;
 use steamworks::SteamAPIInitError;
 
+#[macro_use]
+pub mod logging;
+
+pub mod abort;
+pub mod backend;
 pub mod client;
+pub mod dispatch;
+pub mod error;
+pub mod journal;
+pub mod panic;
+pub mod png;
+pub mod progress;
+pub mod pump;
+pub mod ready;
+pub mod resources;
+pub mod schema;
+pub mod server;
+pub mod stream;
+pub mod vdf;
 
 #[macro_use]
 extern crate lazy_static;
 
+#[derive(Default)]
+#[napi(object)]
+pub struct InitOptions {
+    /// App ID of the game to load. Without it, Steam looks for a steam_appid.txt file
+    /// or uses the app the game was launched as.
+    pub app_id: Option<u32>,
+    /// The backend to drive the API with, defaults to `BackendKind.Steam`.
+    /// `BackendKind.Mock` keeps everything in memory and doesn't need Steam to be running.
+    pub backend: Option<BackendKind>,
+    /// Relaunch the game through Steam if it wasn't started by Steam, see `restartAppIfNecessary`.
+    /// When that happens, init is skipped, `restarting` is set on the report and the game should quit.
+    /// Requires `appId`. Defaults to false.
+    pub restart_if_necessary: Option<bool>,
+    /// Write a steam_appid.txt file with `appId` to the working directory for the duration of init,
+    /// for running outside of Steam during development. Requires `appId`. Defaults to false.
+    pub write_steam_app_id_file: Option<bool>,
+    /// Run callbacks (and `input.runFrame`) on a background thread, so pending promises
+    /// resolve even if nothing calls `runCallbacks`. Defaults to false.
+    pub auto_run_callbacks: Option<bool>,
+    /// How often the background pump runs, in milliseconds. Defaults to 8ms.
+    pub callback_interval_ms: Option<u32>,
+    /// Ask Steam for the local user's stats and achievements right away. Defaults to true.
+    pub request_stats_on_init: Option<bool>,
+    /// Same as calling `setLogLevel` before init.
+    pub log_level: Option<LogLevel>,
+    /// The app's stats and achievements schema, a VDF file like the `UserGameStatsSchema_<appId>.bin`
+    /// Steam caches, the same layout as JSON, or the JSON the Web API's `GetSchemaForGame` returns.
+    /// With it, the `stats` and `achievement` modules throw `NOT_FOUND` for a name that isn't in
+    /// it, and `INVALID_PARAMETER` for a stat of another type, instead of returning false or null.
+    /// The mock backend defines everything in it.
+    pub schema: Option<String>,
+    /// A file to keep the stat and achievement writes in when Steam can't store them, e.g. while
+    /// its servers are unreachable. They're replayed on `SteamServersConnected`, or on the next
+    /// launch once the stats come in, keeping the higher value of a stat and never locking an
+    /// achievement again. The user's Steam ID and the app ID are added to the file name, e.g.
+    /// `journal.json` is kept as `journal-76561197960265729-480.json`, and a journal is never
+    /// replayed into another user's stats. Average rate stats aren't kept. Off by default.
+    pub stat_journal: Option<String>,
+}
+
+#[napi(object)]
+pub struct InitReport {
+    pub backend: BackendKind,
+    pub app_id: u32,
+    /// The build id of the installed depot, 0 when running outside of Steam
+    pub build_id: i32,
+    /// Whether the user is connected to the Steam servers, false in offline mode
+    pub logged_on: bool,
+    pub steam_id: Option<PlayerSteamId>,
+    /// The Steam interfaces that can be used, e.g. "ISteamUserStats"
+    pub interfaces: Vec<String>,
+    /// Whether the user's stats were requested
+    pub stats_requested: bool,
+    /// The game was relaunched through Steam, and should quit now.
+    pub restarting: bool,
+}
+
+/// Initialize the Steam client, or the mock backend.
+/// @returns what got initialized, and whether Steam is relaunching the game instead
 #[napi]
-pub fn init(app_id: Option<u32>) -> Result<(), Error> {
-    println!("Initializing Steamworks client...");
-    better_panic::Settings::debug()
-        .verbosity(better_panic::Verbosity::Full)
-        .most_recent_first(true)
-        .install();
-    if client::has_client() {
-        client::drop_client();
+pub fn init(options: Option<InitOptions>) -> Result<InitReport> {
+    crate::panic::guard(|| {
+        let options = options.unwrap_or_default();
+        if let Some(level) = options.log_level {
+            logging::set_level(level, None);
+        }
+
+        log_info!("Initializing Steamworks client...");
+
+        let app_id = options.app_id.map(AppId);
+        if app_id == Some(AppId(0)) {
+            return Err(SteamworksError::invalid_parameter("appId can't be 0").into());
+        }
+        let restart_if_necessary = options.restart_if_necessary.unwrap_or(false);
+        let write_steam_app_id_file = options.write_steam_app_id_file.unwrap_or(false);
+        if app_id.is_none() && (restart_if_necessary || write_steam_app_id_file) {
+            return Err(SteamworksError::invalid_parameter(
+                "appId is required by restartIfNecessary and writeSteamAppIdFile",
+            )
+            .into());
+        }
+
+        let schema = options
+            .schema
+            .as_deref()
+            .map(|path| schema::Schema::load(Path::new(path)))
+            .transpose()?;
+
+        // Only validated options replace a running client.
+        if client::has_backend() {
+            client::drop_client();
+        }
+
+        let pump_interval = options.auto_run_callbacks.unwrap_or(false).then(|| {
+            options
+                .callback_interval_ms
+                .unwrap_or(pump::DEFAULT_INTERVAL_MS)
+        });
+        let request_stats = options.request_stats_on_init.unwrap_or(true);
+
+        let is_mock = matches!(options.backend, Some(BackendKind::Mock));
+        if let Some(app_id) = app_id.filter(|_| restart_if_necessary && !is_mock) {
+            if steamworks::restart_app_if_necessary(app_id) {
+                log_info!("Relaunching app {} through Steam", app_id.0);
+                return Ok(InitReport {
+                    backend: BackendKind::Steam,
+                    app_id: app_id.0,
+                    build_id: 0,
+                    logged_on: false,
+                    steam_id: None,
+                    interfaces: vec![],
+                    stats_requested: false,
+                    restarting: true,
+                });
+            }
+        }
+
+        if is_mock {
+            client::set_backend(Arc::new(MockBackend::new(app_id.unwrap_or(AppId(480)))));
+            ready::mark_all_ready();
+        } else {
+            let steam_appid_file = match app_id {
+                Some(app_id) if write_steam_app_id_file => Some(SteamAppIdFile::write(app_id)?),
+                _ => None,
+            };
+            let steam_client = app_id
+                .map(Client::init_app)
+                .unwrap_or_else(Client::init)
+                .map_err(init_error);
+            // Cleaned up whether init worked or not, Steam only reads it during init.
+            drop(steam_appid_file);
+            client::set_client(steam_client?);
+            ready::track(&client::get_client()?);
+        }
+
+        let backend = client::get_backend()?;
+        if let Some(schema) = schema {
+            if let Some(mock) = backend.as_mock() {
+                mock.define_schema(&schema);
+            }
+            log_info!(
+                "Loaded a schema of {} stats and {} achievements",
+                schema.stats.len(),
+                schema.achievements.len()
+            );
+            schema::set(schema);
+        }
+        if let Some(path) = options.stat_journal {
+            journal::open(PathBuf::from(path), &*backend);
+        }
+        if request_stats {
+            backend.request_user_stats();
+        }
+        if let Some(interval_ms) = pump_interval {
+            pump::start(interval_ms);
+        }
+
+        let report = InitReport {
+            backend: backend.kind(),
+            app_id: backend.app_id().0,
+            build_id: backend.build_id(),
+            logged_on: backend.is_logged_on(),
+            steam_id: Some(PlayerSteamId::from_steamid(backend.steam_id())),
+            interfaces: backend.interfaces(),
+            stats_requested: request_stats,
+            restarting: false,
+        };
+        log_info!(
+            "Initialized app {} (build {}), logged on: {}",
+            report.app_id,
+            report.build_id,
+            report.logged_on
+        );
+        Ok(report)
+    })
+}
+
+/// steam_appid.txt in the working directory, put back the way it was when dropped.
+struct SteamAppIdFile {
+    path: PathBuf,
+    previous: Option<Vec<u8>>,
+}
+
+impl SteamAppIdFile {
+    fn write(app_id: AppId) -> Result<Self, SteamworksError> {
+        let path = std::env::current_dir()?.join("steam_appid.txt");
+        let previous = match fs::read(&path) {
+            Ok(content) => Some(content),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+        fs::write(&path, app_id.0.to_string())?;
+        Ok(Self { path, previous })
     }
+}
 
-    let steam_client = app_id
-        .map(|app_id| Client::init_app(AppId(app_id)))
-        .unwrap_or_else(Client::init)
-        .map_err(|e| match e {
-            SteamAPIInitError::FailedGeneric(msg)
-            | SteamAPIInitError::NoSteamClient(msg)
-            | SteamAPIInitError::VersionMismatch(msg) => Error::from_reason(msg),
-        })?;
+impl Drop for SteamAppIdFile {
+    fn drop(&mut self) {
+        let result = match self.previous.take() {
+            Some(content) => fs::write(&self.path, content),
+            None => fs::remove_file(&self.path),
+        };
+        if let Err(e) = result {
+            log_warn!("Couldn't clean up {}: {}", self.path.display(), e);
+        }
+    }
+}
 
-    steam_client
-        .user_stats()
-        .request_user_stats(steam_client.user().steam_id().raw());
+fn init_error(e: SteamAPIInitError) -> SteamworksError {
+    match e {
+        SteamAPIInitError::FailedGeneric(msg)
+        | SteamAPIInitError::NoSteamClient(msg)
+        | SteamAPIInitError::VersionMismatch(msg) => SteamworksError::failed(msg),
+    }
+}
 
-    client::set_client(steam_client);
-    Ok(())
+/// Waits until the local user's stats, persona and cloud are available, so that early calls to
+/// `stats.getInt` or `achievement.isActivated` don't come back empty.
+/// Stats are only received if they were requested, see `InitOptions.requestStatsOnInit`.
+/// @param timeoutMs - Defaults to 10 seconds, rejects with `TIMEOUT` naming what's still missing
+#[napi]
+pub async fn ready(
+    timeout_ms: Option<u32>,
+    signal: Option<AbortSignal>,
+) -> Result<ready::Readiness> {
+    crate::panic::guard_async(crate::abort::abortable(signal, async move {
+        client::get_backend()?;
+        let timeout = Duration::from_millis(u64::from(timeout_ms.unwrap_or(10_000)));
+        Ok(ready::wait(timeout).await?)
+    }))
+    .await
 }
 
+/// @returns which subsystems are ready, without waiting
 #[napi]
-pub fn restart_app_if_necessary(app_id: u32) -> bool {
-    steamworks::restart_app_if_necessary(AppId(app_id))
+pub fn get_readiness() -> Result<ready::Readiness> {
+    crate::panic::guard(|| {
+        client::get_backend()?;
+        Ok(ready::current())
+    })
+}
+
+#[napi(object)]
+pub struct ServerInitOptions {
+    /// The IPv4 address to bind to, defaults to all interfaces.
+    pub ip: Option<String>,
+    /// The port clients connect to for gameplay.
+    pub game_port: u32,
+    /// The port the server browser queries, usually the game port + 1.
+    pub query_port: u32,
+    /// Defaults to `ServerMode.AuthenticationAndSecure`.
+    pub server_mode: Option<ServerMode>,
+    /// The version string of the server, in the form `x.x.x.x`.
+    /// Clients with a different version won't see it in the server browser.
+    pub version: String,
+    /// Same as `InitOptions.autoRunCallbacks`.
+    pub auto_run_callbacks: Option<bool>,
+    /// Same as `InitOptions.callbackIntervalMs`.
+    pub callback_interval_ms: Option<u32>,
 }
 
+/// Initialize a dedicated game server, it doesn't need a Steam client to be running.
+/// Use `logOnAnonymous` or `logOn` from the `server` module to connect it to Steam.
 #[napi]
-pub fn run_callbacks() {
-    client::get_client().unwrap().run_callbacks();
+pub fn init_server(options: ServerInitOptions) -> Result<()> {
+    crate::panic::guard(|| {
+        if server::has_server() {
+            server::drop_server();
+        }
+
+        let ip = options
+            .ip
+            .as_deref()
+            .unwrap_or("0.0.0.0")
+            .parse::<Ipv4Addr>()
+            .map_err(|e| SteamworksError::invalid_parameter(format!("Invalid ip: {}", e)))?;
+        let port = |port: u32, name: &str| {
+            u16::try_from(port).map_err(|_| {
+                SteamworksError::invalid_parameter(format!("Invalid {}: {}", name, port))
+            })
+        };
+        let game_port = port(options.game_port, "game port")?;
+        let query_port = port(options.query_port, "query port")?;
+
+        let (steam_server, callback_client) = Server::init(
+            ip,
+            game_port,
+            query_port,
+            options
+                .server_mode
+                .unwrap_or(ServerMode::AuthenticationAndSecure)
+                .into(),
+            &options.version,
+        )
+        .map_err(init_error)?;
+        steam_server.set_dedicated_server(true);
+
+        server::set_server(steam_server, callback_client);
+        if options.auto_run_callbacks.unwrap_or(false) {
+            pump::start(
+                options
+                    .callback_interval_ms
+                    .unwrap_or(pump::DEFAULT_INTERVAL_MS),
+            );
+        }
+        Ok(())
+    })
+}
+
+/// Forwards log records to `handler` instead of printing them to stderr.
+/// Pass null to go back to stderr.
+#[napi]
+pub fn set_log_handler(
+    #[napi(ts_arg_type = "((record: LogRecord) => void) | null")] handler: Option<
+        Function<'static>,
+    >,
+) -> Result<()> {
+    crate::panic::guard(|| {
+        let handler = handler
+            .map(|handler| {
+                handler
+                    .build_threadsafe_function::<logging::LogRecord>()
+                    .callee_handled::<false>()
+                    .max_queue_size::<0>()
+                    .build_callback(|ctx| Ok(vec![ctx.value]))
+            })
+            .transpose()
+            .map_err(SteamworksError::from)?;
+        logging::set_handler(handler);
+        Ok(())
+    })
+}
+
+/// Sets the most verbose level that gets logged.
+/// Release builds default to `LogLevel.Off`, debug builds to `LogLevel.Info`.
+/// @param target - Only set the level of one module, e.g. "friends". Without it, every module's level is reset to `level`.
+#[napi]
+pub fn set_log_level(level: LogLevel, target: Option<String>) -> Result<()> {
+    crate::panic::guard(|| {
+        logging::set_level(level, target);
+        Ok(())
+    })
+}
+
+#[napi]
+pub fn restart_app_if_necessary(app_id: u32) -> Result<bool> {
+    crate::panic::guard(|| Ok(steamworks::restart_app_if_necessary(AppId(app_id))))
+}
+
+#[napi]
+pub fn run_callbacks() -> Result<()> {
+    crate::panic::guard(|| {
+        if !client::has_backend() && !server::has_server() {
+            return Err(SteamworksError::NotInitialized.into());
+        }
+        pump::run_callbacks();
+        Ok(())
+    })
+}
+
+/// Starts running callbacks and `input.runFrame` on a background thread.
+/// Restarts the pump if it's already running.
+/// @param intervalMs - How often to run callbacks, defaults to 8ms
+#[napi]
+pub fn start_callback_pump(interval_ms: Option<u32>) -> Result<()> {
+    crate::panic::guard(|| {
+        if !client::has_backend() && !server::has_server() {
+            return Err(SteamworksError::NotInitialized.into());
+        }
+        pump::start(interval_ms.unwrap_or(pump::DEFAULT_INTERVAL_MS));
+        Ok(())
+    })
+}
+
+/// Stops the background callback pump.
+/// @returns true if the pump was running
+#[napi]
+pub fn stop_callback_pump() -> Result<bool> {
+    crate::panic::guard(|| Ok(pump::stop()))
+}
+
+#[napi]
+pub fn is_callback_pump_running() -> Result<bool> {
+    crate::panic::guard(|| Ok(pump::is_running()))
 }
 
 /// Shutdown the Steam client instance
+/// Tears down the lobbies, sockets and tickets it handed out, rejects pending promises
+/// with `SHUT_DOWN`, then releases the client.
+/// PLEASE DON'T USE THIS. Use [`shutdown`] instead.
+#[napi]
+pub fn shutdown_client(options: Option<resources::ShutdownOptions>) -> Result<()> {
+    crate::panic::guard(|| {
+        client::get_backend()?;
+        resources::tear_down(&options.unwrap_or_default());
+        client::drop_client();
+        Ok(())
+    })
+}
+
+/// Shutdown the game server instance
 /// PLEASE DON'T USE THIS. Use [`shutdown`] instead.
 #[napi]
-pub fn shutdown_client() {
-    client::get_client().unwrap();
-    client::drop_client();
+pub fn shutdown_server() -> Result<()> {
+    crate::panic::guard(|| {
+        server::get_server()?;
+        server::drop_server();
+        Ok(())
+    })
 }
 
 pub mod api;
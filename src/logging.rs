@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Mutex, RwLock};
+
+use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi_derive::napi;
+
+#[napi]
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LogLevel {
+    /// Don't log anything
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            LogLevel::Off => "OFF",
+            LogLevel::Error => "ERROR",
+            LogLevel::Warn => "WARN",
+            LogLevel::Info => "INFO",
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Trace => "TRACE",
+        };
+        f.pad(name)
+    }
+}
+
+#[napi(object)]
+pub struct LogRecord {
+    pub level: LogLevel,
+    /// The module that logged the record, e.g. "friends"
+    pub target: String,
+    pub message: String,
+}
+
+pub type LogHandler =
+    ThreadsafeFunction<LogRecord, napi::Unknown<'static>, Vec<LogRecord>, napi::Status, false>;
+
+struct Filter {
+    default: LogLevel,
+    targets: HashMap<String, LogLevel>,
+}
+
+lazy_static! {
+    static ref FILTER: RwLock<Filter> = RwLock::new(Filter {
+        // Shipped builds stay quiet unless asked otherwise.
+        default: if cfg!(debug_assertions) {
+            LogLevel::Info
+        } else {
+            LogLevel::Off
+        },
+        targets: HashMap::new(),
+    });
+    static ref HANDLER: Mutex<Option<LogHandler>> = Mutex::new(None);
+}
+
+/// Shortens a `module_path!()` to the module it belongs to, e.g. `steamworksjs::api::friends::friends` to `friends`.
+pub fn target(module_path: &str) -> &str {
+    let path = match module_path.split_once("::") {
+        Some((_, path)) => path,
+        None => return module_path,
+    };
+    let path = path.strip_prefix("api::").unwrap_or(path);
+    path.split("::").next().unwrap_or(path)
+}
+
+pub fn enabled(level: LogLevel, target: &str) -> bool {
+    let filter = FILTER.read().unwrap_or_else(|e| e.into_inner());
+    let max = filter
+        .targets
+        .get(target)
+        .copied()
+        .unwrap_or(filter.default);
+    level != LogLevel::Off && level <= max
+}
+
+/// Sets the most verbose level that gets logged, for one target or, without one, for everything
+/// that doesn't have its own level.
+pub fn set_level(level: LogLevel, target: Option<String>) {
+    let mut filter = FILTER.write().unwrap_or_else(|e| e.into_inner());
+    match target {
+        Some(target) => {
+            filter.targets.insert(target, level);
+        }
+        None => {
+            filter.default = level;
+            filter.targets.clear();
+        }
+    }
+}
+
+/// Routes records to JS, or back to stderr when `None`.
+pub fn set_handler(handler: Option<LogHandler>) {
+    *HANDLER.lock().unwrap_or_else(|e| e.into_inner()) = handler;
+}
+
+pub fn write(level: LogLevel, target: &str, message: String) {
+    let handler = HANDLER.lock().unwrap_or_else(|e| e.into_inner());
+    match handler.as_ref() {
+        Some(handler) => {
+            // Non blocking, records can come from the callback pump thread.
+            handler.call(
+                LogRecord {
+                    level,
+                    target: target.to_string(),
+                    message,
+                },
+                ThreadsafeFunctionCallMode::NonBlocking,
+            );
+        }
+        None => eprintln!("[steamworks.js] {:<5} {}: {}", level, target, message),
+    }
+}
+
+macro_rules! log_at {
+    ($level:expr, $($arg:tt)+) => {{
+        let target = $crate::logging::target(module_path!());
+        if $crate::logging::enabled($level, target) {
+            $crate::logging::write($level, target, format!($($arg)+));
+        }
+    }};
+}
+
+macro_rules! log_error {
+    ($($arg:tt)+) => { log_at!($crate::logging::LogLevel::Error, $($arg)+) };
+}
+
+macro_rules! log_warn {
+    ($($arg:tt)+) => { log_at!($crate::logging::LogLevel::Warn, $($arg)+) };
+}
+
+macro_rules! log_info {
+    ($($arg:tt)+) => { log_at!($crate::logging::LogLevel::Info, $($arg)+) };
+}
+
+macro_rules! log_debug {
+    ($($arg:tt)+) => { log_at!($crate::logging::LogLevel::Debug, $($arg)+) };
+}
+
+macro_rules! log_trace {
+    ($($arg:tt)+) => { log_at!($crate::logging::LogLevel::Trace, $($arg)+) };
+}
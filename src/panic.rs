@@ -0,0 +1,78 @@
+//! Turns Rust panics in exported functions into JS errors, instead of letting them abort the
+//! host process.
+
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::cell::{Cell, RefCell};
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::pin;
+use std::sync::Once;
+use std::task::Poll;
+
+use crate::error::{Result, SteamworksError};
+
+thread_local! {
+    // How many guards the current thread is in, panics outside of them go to the previous hook.
+    static GUARDS: Cell<usize> = const { Cell::new(0) };
+    // Where the last guarded panic happened, the payload doesn't carry it.
+    static LAST_PANIC: RefCell<Option<(String, String)>> = const { RefCell::new(None) };
+}
+
+fn install_hook() {
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if GUARDS.with(Cell::get) == 0 {
+                return previous(info);
+            }
+            let location = info
+                .location()
+                .map(|location| location.to_string())
+                .unwrap_or_default();
+            let backtrace = Backtrace::force_capture().to_string();
+            LAST_PANIC.with(|last| *last.borrow_mut() = Some((location, backtrace)));
+        }));
+    });
+}
+
+fn catch<T>(f: impl FnOnce() -> T) -> Result<T, SteamworksError> {
+    install_hook();
+    GUARDS.with(|guards| guards.set(guards.get() + 1));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    GUARDS.with(|guards| guards.set(guards.get() - 1));
+    result.map_err(to_error)
+}
+
+fn to_error(payload: Box<dyn Any + Send>) -> SteamworksError {
+    let message = payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "Box<dyn Any>".to_string());
+    let (location, backtrace) = LAST_PANIC
+        .with(|last| last.borrow_mut().take())
+        .unwrap_or_default();
+    log_error!("Panicked at {}: {}", location, message);
+    SteamworksError::Panic {
+        message: format!("Panicked at {}: {}", location, message),
+        backtrace,
+    }
+}
+
+/// Runs an exported function, a panic becomes a thrown `PANIC` error.
+pub fn guard<T>(f: impl FnOnce() -> Result<T>) -> Result<T> {
+    catch(f)?
+}
+
+/// Runs an exported async function, a panic in any of its polls becomes a rejected `PANIC` error.
+pub async fn guard_async<T>(future: impl Future<Output = Result<T>>) -> Result<T> {
+    let mut future = pin!(future);
+    std::future::poll_fn(|cx| match catch(|| future.as_mut().poll(cx)) {
+        Ok(poll) => poll,
+        // A panicked future can't be polled again.
+        Err(e) => Poll::Ready(Err(e.into())),
+    })
+    .await
+}
@@ -0,0 +1,156 @@
+//! Stats bound to an achievement's progress, see `achievement.bindProgress` and
+//! `stats.defineRule`.
+//!
+//! The stat writers call `stat_changed` with the value before and after the write, and every
+//! milestone the write went past shows Steam's progress toast. A rule also unlocks its achievement
+//! once the stat reaches `max`. Those unlocks are stored together by `flush` a moment later, Steam
+//! rate limits stores and a write often completes several rules at once.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::backend::SteamBackend;
+
+struct Binding {
+    achievement: String,
+    max: u32,
+    /// Sorted, all below `max`.
+    milestones: Vec<u32>,
+    /// Whether reaching `max` unlocks the achievement.
+    unlock: bool,
+}
+
+/// How long the unlocks wait for others before they're stored.
+const STORE_DELAY: Duration = Duration::from_secs(1);
+
+lazy_static! {
+    static ref BINDINGS: Mutex<HashMap<String, Vec<Binding>>> = Mutex::new(HashMap::new());
+    /// When the unlocks that weren't stored yet are, `None` if there are none.
+    static ref STORE_DUE: Mutex<Option<Instant>> = Mutex::new(None);
+}
+
+/// Every quarter of `max`, the milestones a binding or rule gets when none are given.
+pub fn default_milestones(max: u32) -> Vec<u32> {
+    (1..4u64)
+        .map(|quarter| (u64::from(max) * quarter / 4) as u32)
+        .collect()
+}
+
+/// Binds `stat` to `achievement`, replacing an earlier binding between the two.
+/// @param unlock - Whether to unlock the achievement once the stat reaches `max`
+pub fn bind(stat: &str, achievement: &str, max: u32, mut milestones: Vec<u32>, unlock: bool) {
+    milestones.retain(|&milestone| milestone > 0 && milestone < max);
+    milestones.sort_unstable();
+    milestones.dedup();
+
+    let mut bindings = BINDINGS.lock().unwrap();
+    let bindings = bindings.entry(stat.to_string()).or_default();
+    bindings.retain(|binding| binding.achievement != achievement);
+    bindings.push(Binding {
+        achievement: achievement.to_string(),
+        max,
+        milestones,
+        unlock,
+    });
+}
+
+/// @returns false if the two weren't bound
+pub fn unbind(stat: &str, achievement: &str) -> bool {
+    let mut bindings = BINDINGS.lock().unwrap();
+    let Some(stat_bindings) = bindings.get_mut(stat) else {
+        return false;
+    };
+    let count = stat_bindings.len();
+    stat_bindings.retain(|binding| binding.achievement != achievement);
+    let removed = stat_bindings.len() != count;
+    if stat_bindings.is_empty() {
+        bindings.remove(stat);
+    }
+    removed
+}
+
+/// Shows the progress of every achievement bound to `stat` that the write took past a milestone,
+/// and unlocks the ones whose rule it completed.
+pub fn stat_changed(backend: &dyn SteamBackend, stat: &str, old: f64, new: f64) {
+    let mut unlocks = Vec::new();
+    let crossed: Vec<(String, u32, u32)> = match BINDINGS.lock().unwrap().get(stat) {
+        Some(bindings) => bindings
+            .iter()
+            .filter(|binding| {
+                // Checked on every write, a rule defined after the stat got there still unlocks.
+                if binding.unlock && new >= f64::from(binding.max) {
+                    unlocks.push(binding.achievement.clone());
+                    return false;
+                }
+                binding
+                    .milestones
+                    .iter()
+                    .any(|&milestone| old < f64::from(milestone) && f64::from(milestone) <= new)
+            })
+            .map(|binding| {
+                let current = (new as u32).min(binding.max - 1);
+                (binding.achievement.clone(), current, binding.max)
+            })
+            .collect(),
+        None => return,
+    };
+
+    for (achievement, current, max) in crossed {
+        // Steam refuses once the achievement is unlocked, which is expected past the last milestone.
+        if backend
+            .indicate_achievement_progress(&achievement, current, max)
+            .is_err()
+        {
+            log_debug!(
+                "Steam didn't show the progress of {} at {}/{}",
+                achievement,
+                current,
+                max
+            );
+        }
+    }
+
+    for achievement in unlocks {
+        if backend.get_achievement(&achievement) != Ok(false) {
+            continue;
+        }
+        if backend.set_achievement(&achievement).is_err() {
+            log_warn!("Couldn't unlock {} for its rule on {}", achievement, stat);
+            continue;
+        }
+        log_info!("{} reached {}, unlocked {}", stat, new, achievement);
+        crate::journal::achievement_written(&achievement, true);
+        STORE_DUE
+            .lock()
+            .unwrap()
+            .get_or_insert_with(|| Instant::now() + STORE_DELAY);
+    }
+}
+
+/// Stores the unlocks of the rules once they waited `STORE_DELAY`, called by the callback pump
+/// and `runCallbacks`.
+pub fn flush(backend: &dyn SteamBackend) {
+    {
+        let mut due = STORE_DUE.lock().unwrap();
+        if !due.is_some_and(|due| due <= Instant::now()) {
+            return;
+        }
+        *due = None;
+    }
+    if crate::journal::store(backend).is_err() {
+        log_warn!("Couldn't store the achievements the rules unlocked");
+    }
+}
+
+/// Stores the unlocks still waiting for `STORE_DELAY` and drops every binding and rule, called
+/// when the client shuts down.
+pub fn reset(backend: Option<&dyn SteamBackend>) {
+    BINDINGS.lock().unwrap().clear();
+    let due = STORE_DUE.lock().unwrap().take();
+    if let (Some(_), Some(backend)) = (due, backend) {
+        if crate::journal::store(backend).is_err() {
+            log_warn!("Couldn't store the achievements the rules unlocked");
+        }
+    }
+}
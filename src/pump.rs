@@ -0,0 +1,105 @@
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Mutex, TryLockError};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Same rate the JS side has always used to poll `runCallbacks`.
+pub const DEFAULT_INTERVAL_MS: u32 = 1000 / 120;
+
+struct Pump {
+    stop: Sender<()>,
+    thread: JoinHandle<()>,
+}
+
+lazy_static! {
+    static ref PUMP: Mutex<Option<Pump>> = Mutex::new(None);
+    // Steam's manual dispatch isn't reentrant, so the pump and `runCallbacks` take turns.
+    static ref DISPATCH: Mutex<()> = Mutex::new(());
+}
+
+thread_local! {
+    static ON_PUMP: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Whether callbacks are dispatched on the pump's thread right now, which is free to wait for JS.
+pub fn on_pump_thread() -> bool {
+    ON_PUMP.with(Cell::get)
+}
+
+/// Runs the pending client and game server callbacks, serialized with the background pump.
+pub fn run_callbacks() {
+    let backend = crate::client::get_backend().ok();
+    let _guard = match DISPATCH.try_lock() {
+        Ok(guard) => guard,
+        Err(TryLockError::Poisoned(e)) => e.into_inner(),
+        // The pump is dispatching them already, maybe waiting for JS to drain a `Block` stream.
+        Err(TryLockError::WouldBlock) => return,
+    };
+    if let Some(backend) = backend {
+        backend.run_callbacks();
+        crate::progress::flush(&*backend);
+    }
+    crate::server::run_callbacks();
+}
+
+fn tick() {
+    // Looked up on every tick so the pump never keeps a dropped client alive.
+    let backend = crate::client::get_backend().ok();
+    let _guard = DISPATCH.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(backend) = backend {
+        backend.run_callbacks();
+        backend.input_run_frame();
+        crate::progress::flush(&*backend);
+    }
+    crate::server::run_callbacks();
+}
+
+/// Starts pumping callbacks on a dedicated thread, replacing a pump that's already running.
+pub fn start(interval_ms: u32) {
+    stop();
+
+    let interval = Duration::from_millis(u64::from(interval_ms.max(1)));
+    let (stop_tx, stop_rx) = mpsc::channel();
+    let thread = thread::Builder::new()
+        .name("steamworks-callbacks".to_string())
+        .spawn(move || {
+            ON_PUMP.with(|on_pump| on_pump.set(true));
+            loop {
+                // A panicking callback must not take the pump down with it, or every pending
+                // promise would hang again.
+                if panic::catch_unwind(AssertUnwindSafe(tick)).is_err() {
+                    log_error!("A Steam callback panicked, the pump keeps going.");
+                }
+                match stop_rx.recv_timeout(interval) {
+                    Err(RecvTimeoutError::Timeout) => continue,
+                    Ok(()) | Err(RecvTimeoutError::Disconnected) => break,
+                }
+            }
+        })
+        .expect("failed to spawn the callback pump thread");
+
+    *PUMP.lock().unwrap() = Some(Pump {
+        stop: stop_tx,
+        thread,
+    });
+}
+
+/// Stops the pump and waits for its last tick to finish.
+/// @returns true if a pump was running
+pub fn stop() -> bool {
+    let pump = PUMP.lock().unwrap().take();
+    match pump {
+        Some(pump) => {
+            let _ = pump.stop.send(());
+            let _ = pump.thread.join();
+            true
+        }
+        None => false,
+    }
+}
+
+pub fn is_running() -> bool {
+    PUMP.lock().unwrap().is_some()
+}
@@ -0,0 +1,152 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use napi_derive::napi;
+use serde_json::Value;
+use steamworks::Client;
+use tokio::sync::watch;
+
+use crate::api::callback::callback::SteamCallback;
+use crate::backend::SteamBackend;
+use crate::error::SteamworksError;
+
+#[napi(object)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Readiness {
+    /// The local user's stats and achievements were received, `stats` and `achievement` return real values
+    pub stats: bool,
+    /// The local user's persona name is known
+    pub persona: bool,
+    /// Steam Cloud is enabled for the user's account and for the app, files written are synced
+    pub cloud: bool,
+}
+
+impl Readiness {
+    fn all(&self) -> bool {
+        self.stats && self.persona && self.cloud
+    }
+
+    fn pending(&self) -> Vec<&'static str> {
+        [
+            ("stats", self.stats),
+            ("persona", self.persona),
+            ("cloud", self.cloud),
+        ]
+        .into_iter()
+        .filter(|(_, ready)| !ready)
+        .map(|(name, _)| name)
+        .collect()
+    }
+}
+
+lazy_static! {
+    static ref READINESS: watch::Sender<Readiness> = watch::Sender::new(Readiness::default());
+    static ref SUBSCRIPTIONS: Mutex<Vec<(SteamCallback, u64)>> = Mutex::new(Vec::new());
+}
+
+/// Watches the callbacks that make the client ready.
+pub fn track(client: &Client) {
+    reset();
+
+    let local_id = client.user().steam_id().raw();
+    let app_id = client.utils().app_id().0;
+    let stats = crate::dispatch::subscribe(
+        SteamCallback::UserStatsReceived,
+        Arc::new(move |received: &Value| {
+            // The app id is the low 24 bits of the game id.
+            let game_app_id = received["game_id"].as_u64().map(|id| id & 0xFF_FFFF);
+            if received["steam_id"].as_u64() != Some(local_id)
+                || game_app_id != Some(u64::from(app_id))
+            {
+                return;
+            }
+            match received["success"].as_bool() {
+                Some(true) => READINESS.send_modify(|readiness| readiness.stats = true),
+                _ => log_warn!(
+                    "Couldn't receive the user's stats: {}",
+                    received["error"].as_str().unwrap_or("unknown error")
+                ),
+            }
+        }),
+    );
+    let persona = crate::dispatch::subscribe(
+        SteamCallback::PersonaStateChange,
+        Arc::new(move |change: &Value| {
+            if change["steam_id"].as_u64() == Some(local_id) {
+                READINESS.send_modify(|readiness| readiness.persona = true);
+            }
+        }),
+    );
+    *SUBSCRIPTIONS.lock().unwrap() = [
+        (SteamCallback::UserStatsReceived, stats),
+        (SteamCallback::PersonaStateChange, persona),
+    ]
+    .into_iter()
+    .filter_map(|(kind, id)| match id {
+        Ok(id) => Some((kind, id)),
+        Err(e) => {
+            log_warn!("Couldn't watch {:?}: {}", kind, e);
+            None
+        }
+    })
+    .collect();
+
+    // The persona is usually cached already, and Steam syncs the cloud before the game launches,
+    // so the cloud only has to be turned on.
+    let persona_known = !client.friends().name().is_empty();
+    let storage = client.remote_storage();
+    let cloud_enabled =
+        storage.is_cloud_enabled_for_account() && storage.is_cloud_enabled_for_app();
+    READINESS.send_modify(|readiness| {
+        readiness.persona |= persona_known;
+        readiness.cloud = cloud_enabled;
+    });
+}
+
+/// Updates the cloud's readiness after it was turned on or off for the app.
+pub fn cloud_toggled(backend: &dyn SteamBackend) {
+    let enabled = backend.is_cloud_enabled_for_account() && backend.is_cloud_enabled_for_app();
+    READINESS.send_modify(|readiness| readiness.cloud = enabled);
+}
+
+/// For backends that have everything at hand from the start.
+pub fn mark_all_ready() {
+    reset();
+    READINESS.send_replace(Readiness {
+        stats: true,
+        persona: true,
+        cloud: true,
+    });
+}
+
+/// Stops watching the callbacks.
+pub fn reset() {
+    let subscriptions = std::mem::take(&mut *SUBSCRIPTIONS.lock().unwrap());
+    for (kind, id) in subscriptions {
+        crate::dispatch::unsubscribe(kind, id);
+    }
+    READINESS.send_replace(Readiness::default());
+}
+
+pub fn current() -> Readiness {
+    *READINESS.borrow()
+}
+
+/// Waits until every subsystem is ready.
+pub async fn wait(timeout: Duration) -> Result<Readiness, SteamworksError> {
+    let mut rx = READINESS.subscribe();
+    let result = crate::resources::until_shutdown(tokio::time::timeout(
+        timeout,
+        rx.wait_for(Readiness::all),
+    ))
+    .await?;
+    match result {
+        Ok(Ok(readiness)) => Ok(*readiness),
+        // The sender lives in a static, it's never closed.
+        Ok(Err(_)) => Err(SteamworksError::CallbackDropped),
+        Err(_) => Err(SteamworksError::timeout(format!(
+            "Still waiting for: {}",
+            current().pending().join(", ")
+        ))),
+    }
+}
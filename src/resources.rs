@@ -0,0 +1,125 @@
+//! Resources handed out through this crate that have to be let go of before the client can be
+//! dropped, and the signal that rejects whatever is still pending when it is.
+
+use std::collections::BTreeSet;
+use std::future::Future;
+use std::pin::pin;
+use std::sync::{Arc, Mutex, Weak};
+use std::task::Poll;
+
+use napi_derive::napi;
+use steamworks::networking_sockets::NetConnection;
+use steamworks::LobbyId;
+use tokio::sync::watch;
+
+use crate::error::SteamworksError;
+
+/// A connection shared between its `Socket` and the shutdown, `None` once it's closed.
+pub type SharedConnection = Arc<Mutex<Option<NetConnection>>>;
+
+#[derive(Default)]
+#[napi(object)]
+pub struct ShutdownOptions {
+    /// Store stats and achievements that haven't been stored yet. Defaults to true.
+    pub flush_stats: Option<bool>,
+    /// Leave the lobbies that were created or joined. Defaults to true.
+    pub leave_lobbies: Option<bool>,
+    /// Close the P2P sockets that were opened. Defaults to true.
+    pub close_sockets: Option<bool>,
+    /// Cancel the auth tickets that were handed out. Defaults to true.
+    pub cancel_tickets: Option<bool>,
+}
+
+lazy_static! {
+    // Bumped on every shutdown, pending operations bail out when it changes.
+    static ref SHUTDOWN: watch::Sender<u64> = watch::Sender::new(0);
+    static ref LOBBIES: Mutex<BTreeSet<u64>> = Mutex::new(BTreeSet::new());
+    /// The backend's handles of the tickets.
+    static ref TICKETS: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+    static ref SOCKETS: Mutex<Vec<Weak<Mutex<Option<NetConnection>>>>> = Mutex::new(Vec::new());
+}
+
+pub fn track_lobby(lobby: LobbyId) {
+    LOBBIES.lock().unwrap().insert(lobby.raw());
+}
+
+pub fn untrack_lobby(lobby: LobbyId) {
+    LOBBIES.lock().unwrap().remove(&lobby.raw());
+}
+
+pub fn track_ticket(ticket: u32) {
+    TICKETS.lock().unwrap().push(ticket);
+}
+
+pub fn untrack_ticket(ticket: u32) {
+    TICKETS.lock().unwrap().retain(|tracked| *tracked != ticket);
+}
+
+pub fn track_socket(connection: NetConnection) -> SharedConnection {
+    let connection = Arc::new(Mutex::new(Some(connection)));
+    let mut sockets = SOCKETS.lock().unwrap();
+    // Sockets that were garbage collected already closed their connection.
+    sockets.retain(|socket| socket.strong_count() > 0);
+    sockets.push(Arc::downgrade(&connection));
+    connection
+}
+
+/// Runs `future` to completion, unless the client is shut down first.
+pub async fn until_shutdown<F: Future>(future: F) -> Result<F::Output, SteamworksError> {
+    let mut shutdown = SHUTDOWN.subscribe();
+    let mut future = pin!(future);
+    let mut changed = pin!(shutdown.changed());
+    std::future::poll_fn(|cx| {
+        if let Poll::Ready(output) = future.as_mut().poll(cx) {
+            return Poll::Ready(Ok(output));
+        }
+        match changed.as_mut().poll(cx) {
+            Poll::Ready(_) => Poll::Ready(Err(SteamworksError::ShutDown)),
+            Poll::Pending => Poll::Pending,
+        }
+    })
+    .await
+}
+
+/// Tears the tracked resources down, in order, while the client is still alive.
+pub fn tear_down(options: &ShutdownOptions) {
+    let backend = match crate::client::get_backend() {
+        Ok(backend) => backend,
+        Err(_) => return,
+    };
+
+    if options.flush_stats.unwrap_or(true) && crate::journal::store(&*backend).is_err() {
+        log_warn!("Couldn't store stats on shutdown");
+    }
+
+    let lobbies = std::mem::take(&mut *LOBBIES.lock().unwrap());
+    if options.leave_lobbies.unwrap_or(true) {
+        for lobby in lobbies {
+            log_debug!("Leaving lobby {} on shutdown", lobby);
+            backend.leave_lobby(LobbyId::from_raw(lobby));
+        }
+    }
+
+    let sockets = std::mem::take(&mut *SOCKETS.lock().unwrap());
+    if options.close_sockets.unwrap_or(true) {
+        for socket in sockets.iter().filter_map(Weak::upgrade) {
+            // Dropping the connection closes it.
+            socket.lock().unwrap().take();
+        }
+    }
+
+    let tickets = std::mem::take(&mut *TICKETS.lock().unwrap());
+    if options.cancel_tickets.unwrap_or(true) {
+        for ticket in tickets {
+            backend.cancel_ticket(ticket);
+        }
+    }
+}
+
+/// Forgets every tracked resource and rejects pending operations with `SHUT_DOWN`.
+pub fn release() {
+    LOBBIES.lock().unwrap().clear();
+    SOCKETS.lock().unwrap().clear();
+    TICKETS.lock().unwrap().clear();
+    SHUTDOWN.send_modify(|generation| *generation += 1);
+}
@@ -0,0 +1,265 @@
+//! The app's stats and achievements read from a schema file, see `InitOptions.schema`.
+//!
+//! Steam answers `false` for a name it doesn't know, same as for any other failure. With a schema
+//! loaded the names and types are checked before Steam is asked, and a typo is reported as one.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use serde_json::Value;
+
+use crate::api::stats::stats::{mismatch, StatType};
+use crate::error::{ErrorCode, Result, SteamworksError};
+
+pub struct SchemaStat {
+    /// `None` when the schema doesn't say, which the Web API's doesn't.
+    pub stat_type: Option<StatType>,
+    pub default: f64,
+}
+
+pub struct SchemaAchievement {
+    pub display_name: String,
+    pub description: String,
+    pub hidden: bool,
+}
+
+#[derive(Default)]
+pub struct Schema {
+    pub stats: BTreeMap<String, SchemaStat>,
+    pub achievements: BTreeMap<String, SchemaAchievement>,
+}
+
+lazy_static! {
+    static ref SCHEMA: Mutex<Option<Arc<Schema>>> = Mutex::new(None);
+}
+
+/// A string, or a number written out.
+fn text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    }
+}
+
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// A display string, in English if it's there in several languages.
+fn localized(value: &Value) -> String {
+    match value {
+        Value::Object(languages) => languages
+            .get("english")
+            .or_else(|| {
+                languages
+                    .iter()
+                    .find(|(key, _)| *key != "token")
+                    .map(|(_, value)| value)
+            })
+            .and_then(text)
+            .unwrap_or_default(),
+        value => text(value).unwrap_or_default(),
+    }
+}
+
+enum Kind {
+    Stat(StatType),
+    Achievements,
+}
+
+fn kind(value: &Value) -> Option<Kind> {
+    let kind = text(value)?.to_ascii_uppercase();
+    match kind.as_str() {
+        "1" | "INT" => Some(Kind::Stat(StatType::Int)),
+        "2" | "FLOAT" => Some(Kind::Stat(StatType::Float)),
+        "3" | "AVGRATE" => Some(Kind::Stat(StatType::AvgRate)),
+        "4" | "5" | "ACHIEVEMENTS" | "GROUPACHIEVEMENTS" => Some(Kind::Achievements),
+        _ => None,
+    }
+}
+
+impl Schema {
+    /// Reads a schema in Steam's VDF layout, text or binary, the same layout as JSON, or the JSON
+    /// the Web API's `GetSchemaForGame` returns.
+    pub fn load(path: &Path) -> Result<Self, SteamworksError> {
+        let data = fs::read(path)?;
+        let invalid = |reason: String| {
+            SteamworksError::invalid_parameter(format!(
+                "Couldn't read the schema {}: {}",
+                path.display(),
+                reason
+            ))
+        };
+        let is_json = data.iter().find(|byte| !byte.is_ascii_whitespace()) == Some(&b'{');
+        let value = if is_json {
+            serde_json::from_slice::<Value>(&data).map_err(|e| invalid(e.to_string()))?
+        } else {
+            crate::vdf::parse(&data).map_err(invalid)?
+        };
+        Self::from_value(&value).ok_or_else(|| invalid("there are no stats in it".to_string()))
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        if let Some(stats) = value.pointer("/game/availableGameStats") {
+            return Some(Self::from_web_api(stats));
+        }
+        // The stats are under the app id in Steam's files.
+        let stats = value
+            .get("stats")
+            .or_else(|| value.as_object()?.values().find_map(|app| app.get("stats")))?;
+        Some(Self::from_steam(stats.as_object()?.values()))
+    }
+
+    fn from_steam<'a>(entries: impl Iterator<Item = &'a Value>) -> Self {
+        let mut schema = Schema::default();
+        for entry in entries {
+            match entry.get("type").and_then(kind) {
+                Some(Kind::Stat(stat_type)) => {
+                    let Some(name) = entry.get("name").and_then(text) else {
+                        continue;
+                    };
+                    schema.stats.insert(
+                        name,
+                        SchemaStat {
+                            stat_type: Some(stat_type),
+                            default: entry.get("default").and_then(number).unwrap_or(0.0),
+                        },
+                    );
+                }
+                Some(Kind::Achievements) => {
+                    let Some(bits) = entry.get("bits").and_then(Value::as_object) else {
+                        continue;
+                    };
+                    for bit in bits.values() {
+                        let Some(name) = bit.get("name").and_then(text) else {
+                            continue;
+                        };
+                        let display = &bit["display"];
+                        schema.achievements.insert(
+                            name,
+                            SchemaAchievement {
+                                display_name: localized(&display["name"]),
+                                description: localized(&display["desc"]),
+                                hidden: number(&display["hidden"]) == Some(1.0),
+                            },
+                        );
+                    }
+                }
+                None => {}
+            }
+        }
+        schema
+    }
+
+    fn from_web_api(stats: &Value) -> Self {
+        let mut schema = Schema::default();
+        for stat in stats["stats"].as_array().into_iter().flatten() {
+            if let Some(name) = stat.get("name").and_then(text) {
+                schema.stats.insert(
+                    name,
+                    SchemaStat {
+                        stat_type: None,
+                        default: stat.get("defaultvalue").and_then(number).unwrap_or(0.0),
+                    },
+                );
+            }
+        }
+        for achievement in stats["achievements"].as_array().into_iter().flatten() {
+            if let Some(name) = achievement.get("name").and_then(text) {
+                schema.achievements.insert(
+                    name,
+                    SchemaAchievement {
+                        display_name: localized(&achievement["displayName"]),
+                        description: localized(&achievement["description"]),
+                        hidden: number(&achievement["hidden"]) == Some(1.0),
+                    },
+                );
+            }
+        }
+        schema
+    }
+}
+
+pub fn set(schema: Schema) {
+    *SCHEMA.lock().unwrap() = Some(Arc::new(schema));
+}
+
+pub fn get() -> Option<Arc<Schema>> {
+    SCHEMA.lock().unwrap().clone()
+}
+
+/// Drops the schema, called when the client shuts down.
+pub fn reset() {
+    SCHEMA.lock().unwrap().take();
+}
+
+/// How many single character edits turn `a` into `b`, ignoring case.
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().flat_map(char::to_lowercase).collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().flat_map(char::to_lowercase).enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, &b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != b);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+fn not_found<'a>(
+    what: &str,
+    name: &str,
+    names: impl Iterator<Item = &'a String>,
+) -> napi::Error<ErrorCode> {
+    let closest = names
+        .map(|candidate| (distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= 2)
+        .min();
+    let message = match closest {
+        Some((_, candidate)) => format!(
+            "There's no {} named {} in the schema, did you mean {}?",
+            what, name, candidate
+        ),
+        None => format!("There's no {} named {} in the schema", what, name),
+    };
+    napi::Error::new(ErrorCode::NotFound, message)
+}
+
+/// Checks `name` against the schema, if one is loaded.
+/// @param accepted - The types the stat can have, any if empty
+pub fn check_stat(name: &str, accepted: &[StatType]) -> Result<()> {
+    let Some(schema) = get() else {
+        return Ok(());
+    };
+    match schema.stats.get(name) {
+        None => Err(not_found("stat", name, schema.stats.keys())),
+        Some(SchemaStat {
+            stat_type: Some(stat_type),
+            ..
+        }) if !accepted.is_empty() && !accepted.contains(stat_type) => {
+            Err(mismatch(name, *stat_type))
+        }
+        Some(_) => Ok(()),
+    }
+}
+
+/// Checks `name` against the schema, if one is loaded.
+pub fn check_achievement(name: &str) -> Result<()> {
+    let Some(schema) = get() else {
+        return Ok(());
+    };
+    if schema.achievements.contains_key(name) {
+        Ok(())
+    } else {
+        Err(not_found("achievement", name, schema.achievements.keys()))
+    }
+}
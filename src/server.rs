@@ -0,0 +1,154 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use steamworks::{CallbackHandle, Client, Server, SteamId, ValidateAuthTicketResponse};
+use tokio::sync::oneshot;
+
+use crate::error::SteamworksError;
+
+/// A player the server knows about, from the moment their auth session begins until they leave.
+#[derive(Debug, Clone)]
+pub struct ServerPlayer {
+    pub steam_id: SteamId,
+    pub owner_steam_id: Option<SteamId>,
+    pub name: Option<String>,
+    pub score: u32,
+    pub authenticated: bool,
+}
+
+/// The owner of the license on success, or why Steam rejected the ticket.
+pub type ValidationResult = Result<SteamId, String>;
+
+struct ServerCallbacks {
+    // Declared first so the handle unregisters before the client goes away.
+    _validate_auth_ticket: CallbackHandle,
+    client: Client,
+}
+
+lazy_static! {
+    static ref STEAM_SERVER: Mutex<Option<Arc<Server>>> = Mutex::new(None);
+    static ref SERVER_CALLBACKS: Mutex<Option<Arc<ServerCallbacks>>> = Mutex::new(None);
+    static ref PLAYERS: Mutex<BTreeMap<u64, ServerPlayer>> = Mutex::new(BTreeMap::new());
+    static ref PENDING_VALIDATIONS: Mutex<HashMap<u64, oneshot::Sender<ValidationResult>>> =
+        Mutex::new(HashMap::new());
+}
+
+pub fn has_server() -> bool {
+    STEAM_SERVER.lock().unwrap().is_some()
+}
+
+pub fn get_server() -> Result<Arc<Server>, SteamworksError> {
+    let option = STEAM_SERVER.lock().unwrap().to_owned();
+    option.ok_or(SteamworksError::ServerNotInitialized)
+}
+
+pub fn set_server(server: Server, client: Client) {
+    let validate_auth_ticket = server.register_callback(on_validate_auth_ticket);
+    *SERVER_CALLBACKS.lock().unwrap() = Some(Arc::new(ServerCallbacks {
+        _validate_auth_ticket: validate_auth_ticket,
+        client,
+    }));
+    *STEAM_SERVER.lock().unwrap() = Some(Arc::new(server));
+}
+
+pub fn drop_server() {
+    if !crate::client::has_backend() {
+        crate::pump::stop();
+    }
+    SERVER_CALLBACKS.lock().unwrap().take();
+    STEAM_SERVER.lock().unwrap().take();
+    PLAYERS.lock().unwrap().clear();
+    // Dropping the senders rejects whoever is still waiting on a ticket.
+    PENDING_VALIDATIONS.lock().unwrap().clear();
+}
+
+/// Runs the pending game server callbacks, if a server is running.
+pub fn run_callbacks() {
+    // Cloned out of the lock, the callbacks are free to look the server up again.
+    let callbacks = SERVER_CALLBACKS.lock().unwrap().to_owned();
+    if let Some(callbacks) = callbacks {
+        callbacks.client.run_callbacks();
+    }
+}
+
+/// Starts validating a player's auth ticket and adds them to the roster as unauthenticated.
+/// The receiver resolves once Steam answers with a `ValidateAuthTicketResponse`.
+pub fn begin_player_session(
+    steam_id: SteamId,
+    ticket: &[u8],
+) -> Result<oneshot::Receiver<ValidationResult>, SteamworksError> {
+    let server = get_server()?;
+    server
+        .begin_authentication_session(steam_id, ticket)
+        .map_err(|e| SteamworksError::AuthFailed(e.to_string()))?;
+
+    let (tx, rx) = oneshot::channel();
+    PENDING_VALIDATIONS
+        .lock()
+        .unwrap()
+        .insert(steam_id.raw(), tx);
+    PLAYERS.lock().unwrap().insert(
+        steam_id.raw(),
+        ServerPlayer {
+            steam_id,
+            owner_steam_id: None,
+            name: None,
+            score: 0,
+            authenticated: false,
+        },
+    );
+    Ok(rx)
+}
+
+/// Ends the player's auth session and forgets about them.
+/// @returns true if the player was on the roster
+pub fn end_player_session(steam_id: SteamId) -> Result<bool, SteamworksError> {
+    let server = get_server()?;
+    server.end_authentication_session(steam_id);
+    PENDING_VALIDATIONS.lock().unwrap().remove(&steam_id.raw());
+    Ok(PLAYERS.lock().unwrap().remove(&steam_id.raw()).is_some())
+}
+
+pub fn get_player(steam_id: SteamId) -> Option<ServerPlayer> {
+    PLAYERS.lock().unwrap().get(&steam_id.raw()).cloned()
+}
+
+pub fn get_players() -> Vec<ServerPlayer> {
+    PLAYERS.lock().unwrap().values().cloned().collect()
+}
+
+pub fn update_player(steam_id: SteamId, f: impl FnOnce(&mut ServerPlayer)) -> bool {
+    match PLAYERS.lock().unwrap().get_mut(&steam_id.raw()) {
+        Some(player) => {
+            f(player);
+            true
+        }
+        None => false,
+    }
+}
+
+fn on_validate_auth_ticket(response: ValidateAuthTicketResponse) {
+    let steam_id = response.steam_id;
+    let result = response
+        .response
+        .map(|()| response.owner_steam_id)
+        .map_err(|e| e.to_string());
+    let waiter = PENDING_VALIDATIONS.lock().unwrap().remove(&steam_id.raw());
+
+    match &result {
+        Ok(owner_steam_id) => {
+            update_player(steam_id, |player| {
+                player.authenticated = true;
+                player.owner_steam_id = Some(*owner_steam_id);
+            });
+        }
+        // Steam can also revoke a session long after it was validated, e.g. on a VAC ban.
+        Err(_) => {
+            let _ = end_player_session(steam_id);
+        }
+    }
+
+    if let Some(tx) = waiter {
+        let _ = tx.send(result);
+    }
+}
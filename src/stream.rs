@@ -0,0 +1,142 @@
+//! The bounded queue behind `callback.stream`, filled by a dispatcher listener and drained by JS.
+
+use std::collections::VecDeque;
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+use tokio::sync::Notify;
+
+use crate::api::callback::callback::StreamPolicy;
+
+pub struct CallbackStream {
+    queue: Mutex<Queue>,
+    readable: Notify,
+    writable: Condvar,
+    capacity: usize,
+    policy: StreamPolicy,
+    coalesce_key: Option<String>,
+    block_timeout: Duration,
+}
+
+struct Queue {
+    items: VecDeque<Value>,
+    closed: bool,
+    dropped: u32,
+}
+
+impl CallbackStream {
+    pub fn new(
+        capacity: usize,
+        policy: StreamPolicy,
+        coalesce_key: Option<String>,
+        block_timeout: Duration,
+    ) -> Self {
+        Self {
+            queue: Mutex::new(Queue {
+                items: VecDeque::with_capacity(capacity),
+                closed: false,
+                dropped: 0,
+            }),
+            readable: Notify::new(),
+            writable: Condvar::new(),
+            capacity: capacity.max(1),
+            policy,
+            coalesce_key,
+            block_timeout,
+        }
+    }
+
+    /// Queues a callback according to the policy, called from the dispatcher.
+    pub fn push(&self, value: &Value) {
+        let mut queue = self.queue.lock().unwrap();
+        if queue.closed {
+            return;
+        }
+
+        match self.policy {
+            StreamPolicy::DropOldest => {}
+            // Anywhere but on the pump, e.g. in `runCallbacks` or a replay, waiting could hold up
+            // the JS thread that drains the stream.
+            StreamPolicy::Block if !crate::pump::on_pump_thread() => {}
+            StreamPolicy::Block => {
+                let deadline = Instant::now() + self.block_timeout;
+                while queue.items.len() >= self.capacity && !queue.closed {
+                    let Some(left) = deadline.checked_duration_since(Instant::now()) else {
+                        log_warn!("A blocking stream stayed full, dropping its oldest callback");
+                        break;
+                    };
+                    queue = self.writable.wait_timeout(queue, left).unwrap().0;
+                }
+                if queue.closed {
+                    return;
+                }
+            }
+            StreamPolicy::Coalesce => {
+                let key = self.key(value);
+                if let Some(queued) = queue
+                    .items
+                    .iter_mut()
+                    .find(|queued| self.key(queued) == key)
+                {
+                    *queued = value.clone();
+                    drop(queue);
+                    self.readable.notify_one();
+                    return;
+                }
+            }
+        }
+
+        if queue.items.len() >= self.capacity {
+            queue.items.pop_front();
+            queue.dropped += 1;
+        }
+        queue.items.push_back(value.clone());
+        drop(queue);
+        self.readable.notify_one();
+    }
+
+    fn key<'a>(&self, value: &'a Value) -> &'a Value {
+        match &self.coalesce_key {
+            Some(key) => &value[key.as_str()],
+            None => value,
+        }
+    }
+
+    /// Waits for the next callback.
+    /// @returns `None` once the stream is closed and drained, or the client shut down
+    pub async fn next(&self) -> Option<Value> {
+        loop {
+            {
+                let mut queue = self.queue.lock().unwrap();
+                if let Some(value) = queue.items.pop_front() {
+                    self.writable.notify_one();
+                    return Some(value);
+                }
+                if queue.closed {
+                    return None;
+                }
+            }
+            // `notify_one` keeps a permit when nobody waits yet, so a push in between isn't lost.
+            if crate::resources::until_shutdown(self.readable.notified())
+                .await
+                .is_err()
+            {
+                self.close();
+                return None;
+            }
+        }
+    }
+
+    /// Stops taking callbacks, the ones already queued can still be read.
+    pub fn close(&self) {
+        self.queue.lock().unwrap().closed = true;
+        self.writable.notify_all();
+        self.readable.notify_one();
+    }
+
+    /// @returns how many callbacks were dropped because the stream was full
+    pub fn dropped(&self) -> u32 {
+        self.queue.lock().unwrap().dropped
+    }
+}
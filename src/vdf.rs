@@ -0,0 +1,253 @@
+//! Just enough of a KeyValues (VDF) reader for the stats schema.
+//!
+//! Reads both the text format and the binary one Steam caches schemas in, into the same JSON tree.
+//! Sections become objects, a key that's repeated keeps its last value. Text values are strings,
+//! binary ones keep their type.
+
+use serde_json::{Map, Number, Value};
+
+/// Parses a text or binary VDF file.
+pub fn parse(data: &[u8]) -> Result<Value, String> {
+    // A binary file starts with the type of its first entry, always a section.
+    if data.first() == Some(&0) {
+        let mut reader = Binary { data, at: 0 };
+        return reader.section(true).map(Value::Object);
+    }
+    let text = std::str::from_utf8(data).map_err(|e| e.to_string())?;
+    let mut tokens = Tokens {
+        text: text.strip_prefix('\u{feff}').unwrap_or(text),
+        line: 1,
+    };
+    section(&mut tokens, true).map(Value::Object)
+}
+
+enum Token {
+    Text(String),
+    Open,
+    Close,
+}
+
+struct Tokens<'a> {
+    text: &'a str,
+    line: usize,
+}
+
+impl Tokens<'_> {
+    fn skip_blank(&mut self) {
+        loop {
+            let trimmed = self.text.trim_start();
+            self.line += self.text[..self.text.len() - trimmed.len()]
+                .matches('\n')
+                .count();
+            self.text = trimmed;
+            if !self.text.starts_with("//") {
+                return;
+            }
+            let end = self.text.find('\n').unwrap_or(self.text.len());
+            self.text = &self.text[end..];
+        }
+    }
+
+    fn next(&mut self) -> Result<Option<Token>, String> {
+        self.skip_blank();
+        let text = self.text;
+        let mut chars = text.chars();
+        let token = match chars.next() {
+            None => return Ok(None),
+            Some('{') => {
+                self.text = chars.as_str();
+                Token::Open
+            }
+            Some('}') => {
+                self.text = chars.as_str();
+                Token::Close
+            }
+            Some('"') => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        None => return Err(format!("unterminated string on line {}", self.line)),
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => value.push('\n'),
+                            Some('t') => value.push('\t'),
+                            Some(c) => value.push(c),
+                            None => {}
+                        },
+                        Some(c) => {
+                            if c == '\n' {
+                                self.line += 1;
+                            }
+                            value.push(c);
+                        }
+                    }
+                }
+                self.text = chars.as_str();
+                Token::Text(value)
+            }
+            Some(_) => {
+                let end = text
+                    .find(|c: char| c.is_whitespace() || c == '{' || c == '}' || c == '"')
+                    .unwrap_or(text.len());
+                let value = text[..end].to_string();
+                self.text = &text[end..];
+                Token::Text(value)
+            }
+        };
+        Ok(Some(token))
+    }
+
+    /// Drops a platform conditional like `[$WIN32]` following a key or value.
+    fn skip_conditional(&mut self) {
+        self.skip_blank();
+        if self.text.starts_with('[') {
+            let end = self.text.find(']').map_or(self.text.len(), |end| end + 1);
+            self.text = &self.text[end..];
+        }
+    }
+}
+
+fn section(tokens: &mut Tokens, root: bool) -> Result<Map<String, Value>, String> {
+    let mut entries = Map::new();
+    loop {
+        let key = match tokens.next()? {
+            Some(Token::Text(key)) => key,
+            Some(Token::Close) if !root => return Ok(entries),
+            None if root => return Ok(entries),
+            None => return Err("unexpected end of file, a section isn't closed".to_string()),
+            Some(_) => return Err(format!("expected a key on line {}", tokens.line)),
+        };
+        tokens.skip_conditional();
+        let value = match tokens.next()? {
+            Some(Token::Text(value)) => Value::String(value),
+            Some(Token::Open) => Value::Object(section(tokens, false)?),
+            _ => {
+                return Err(format!(
+                    "expected a value for {} on line {}",
+                    key, tokens.line
+                ))
+            }
+        };
+        tokens.skip_conditional();
+        entries.insert(key, value);
+    }
+}
+
+struct Binary<'a> {
+    data: &'a [u8],
+    at: usize,
+}
+
+impl Binary<'_> {
+    fn take(&mut self, len: usize) -> Result<&[u8], String> {
+        let bytes = self
+            .data
+            .get(self.at..self.at + len)
+            .ok_or("unexpected end of file")?;
+        self.at += len;
+        Ok(bytes)
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let len = self.data[self.at..]
+            .iter()
+            .position(|&byte| byte == 0)
+            .ok_or("unterminated string")?;
+        let value = String::from_utf8_lossy(self.take(len)?).into_owned();
+        self.at += 1;
+        Ok(value)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn section(&mut self, root: bool) -> Result<Map<String, Value>, String> {
+        let mut entries = Map::new();
+        loop {
+            let kind = match self.data.get(self.at) {
+                Some(&kind) => kind,
+                None if root => return Ok(entries),
+                None => return Err("unexpected end of file, a section isn't closed".to_string()),
+            };
+            self.at += 1;
+            // 8, and 11 in some files, end the section.
+            if kind == 8 || kind == 11 {
+                return Ok(entries);
+            }
+            let key = self.string()?;
+            let value = match kind {
+                0 => Value::Object(self.section(false)?),
+                1 => Value::String(self.string()?),
+                // Int, pointer and color.
+                2 | 4 | 6 => Value::from(i32::from_le_bytes(self.array()?)),
+                3 => Number::from_f64(f64::from(f32::from_le_bytes(self.array()?)))
+                    .map_or(Value::Null, Value::Number),
+                7 => Value::from(u64::from_le_bytes(self.array()?)),
+                10 => Value::from(i64::from_le_bytes(self.array()?)),
+                kind => return Err(format!("unknown entry type {} for {}", kind, key)),
+            };
+            entries.insert(key, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn text_sections_and_values() {
+        let text = b"// schema\n\"stats\"\n{\n  \"1\" { \"name\" \"kills\" [$WIN32] }\n  \"1\" { \"name\" \"wins\" }\n}\n";
+        assert_eq!(
+            parse(text),
+            Ok(json!({ "stats": { "1": { "name": "wins" } } }))
+        );
+    }
+
+    #[test]
+    fn malformed_text_is_an_error() {
+        for text in [
+            &b"\"stats\" { \"1\" \"kills\""[..],
+            b"\"stats\" }",
+            b"\"stats\"",
+            b"\"stats\" \"unterminated",
+            b"{ \"stats\" \"kills\" }",
+            b"\"stats\" \xff",
+        ] {
+            assert!(parse(text).is_err(), "{:?}", String::from_utf8_lossy(text));
+        }
+    }
+
+    #[test]
+    fn binary_keeps_types() {
+        let mut data = b"\0stats\0\x01name\0kills\0\x02max\0".to_vec();
+        data.extend(100i32.to_le_bytes());
+        data.extend(b"\x03scale\0");
+        data.extend(0.5f32.to_le_bytes());
+        data.extend(b"\x08\x08");
+        assert_eq!(
+            parse(&data),
+            Ok(json!({ "stats": { "name": "kills", "max": 100, "scale": 0.5 } }))
+        );
+    }
+
+    #[test]
+    fn truncated_binary_is_an_error() {
+        let mut data = b"\0stats\0\x01name\0kills\0\x02max\0".to_vec();
+        data.extend(100i32.to_le_bytes());
+        data.extend(b"\x08\x08");
+        assert!(parse(&data).is_ok());
+
+        // Every cut short of the end leaves a section open, a string unterminated or a value short.
+        for len in 1..data.len() - 1 {
+            assert!(parse(&data[..len]).is_err(), "cut at {}", len);
+        }
+    }
+
+    #[test]
+    fn unknown_binary_type_is_an_error() {
+        assert!(parse(b"\0stats\0\x05odd\0\x08\x08").is_err());
+    }
+}
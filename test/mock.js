@@ -0,0 +1,341 @@
+const assert = require('node:assert')
+const events = require('node:events')
+const fs = require('node:fs')
+const os = require('node:os')
+const path = require('node:path')
+const { init, shutdown, BackendKind, LogLevel, setLogHandler, setLogLevel } = require('../index.js')
+
+const records = []
+setLogLevel(LogLevel.Info)
+setLogHandler((record) => records.push(record))
+
+const journal = path.join(os.tmpdir(), `steamworks-journal-${process.pid}.json`)
+const journalFile = path.join(os.tmpdir(), `steamworks-journal-${process.pid}-76561197960265729-480.json`)
+const client = init({ appId: 480, backend: BackendKind.Mock, autoRunCallbacks: true, statJournal: journal });
+
+(async () => {
+    assert.strictEqual(client.isCallbackPumpRunning(), true)
+    await new Promise((resolve) => setTimeout(resolve, 50))
+    assert.ok(records.some((record) => record.level === LogLevel.Info && record.message.includes('Initializing')))
+
+    assert.strictEqual(client.initReport.backend, BackendKind.Mock)
+    assert.strictEqual(client.initReport.appId, 480)
+    assert.strictEqual(client.initReport.restarting, false)
+    assert.ok(client.initReport.interfaces.includes('ISteamUserStats'))
+    assert.throws(() => init({ backend: BackendKind.Mock, writeSteamAppIdFile: true }), { code: 'INVALID_PARAMETER' })
+    assert.deepStrictEqual(await client.ready(100), { stats: true, persona: true, cloud: true })
+    client.cloud.setEnabledForApp(false)
+    assert.strictEqual(client.getReadiness().cloud, false)
+    client.cloud.setEnabledForApp(true)
+
+    client.mock.defineStat('notes_hit', 0)
+    client.mock.defineAchievement('FIRST_SONG')
+
+    assert.strictEqual(client.stats.setInt('notes_hit', 500), true)
+    assert.strictEqual(client.stats.getInt('notes_hit'), 500)
+    assert.strictEqual(client.stats.setInt('typo', 1), false)
+
+    client.mock.defineStat('accuracy', 0.5, client.stats.StatType.Float)
+    client.mock.defineStat('points_per_hour', 0, client.stats.StatType.AvgRate)
+    assert.strictEqual(client.stats.setFloat('accuracy', 0.75), true)
+    assert.strictEqual(client.stats.getFloat('accuracy'), 0.75)
+    assert.throws(() => client.stats.getInt('accuracy'), { code: 'INVALID_PARAMETER' })
+    assert.strictEqual(client.stats.updateAvgRateStat('points_per_hour', 300, 0.5), true)
+    assert.deepStrictEqual(client.stats.get('points_per_hour'), { name: 'points_per_hour', type: client.stats.StatType.AvgRate, value: 600 })
+    assert.throws(() => client.stats.get('typo'), { code: 'NOT_FOUND' })
+
+    assert.strictEqual(client.achievement.activate('FIRST_SONG'), true)
+    assert.strictEqual(client.achievement.isActivated('FIRST_SONG'), true)
+    assert.deepStrictEqual(client.achievement.names(), ['FIRST_SONG'])
+
+    client.mock.defineAchievement('ALL_SONGS')
+    client.mock.defineAchievementPercent('FIRST_SONG', 87.5)
+    client.mock.defineAchievementPercent('ALL_SONGS', 2.5)
+    assert.strictEqual(client.achievement.getAchievedPercent('FIRST_SONG'), null)
+    await client.achievement.requestGlobalAchievementPercentages()
+    assert.strictEqual(client.achievement.getAchievedPercent('ALL_SONGS'), 2.5)
+    assert.deepStrictEqual(client.achievement.getMostAchieved().map(({ name, achieved }) => [name, achieved]), [['FIRST_SONG', true], ['ALL_SONGS', false]])
+
+    assert.strictEqual(client.mock.defineAchievementInfo('ALL_SONGS', 'Completionist', 'Clear every song', true), true)
+    const { unlockTime, ...allSongs } = client.achievement.getInfo('ALL_SONGS')
+    assert.deepStrictEqual(allSongs, { name: 'ALL_SONGS', displayName: 'Completionist', description: 'Clear every song', hidden: true, unlocked: false, globalPercent: 2.5 })
+    assert.strictEqual(unlockTime ?? null, null)
+    assert.ok(client.achievement.getInfo('FIRST_SONG').unlockTime > 0)
+    assert.throws(() => client.achievement.getInfo('typo'), { code: 'NOT_FOUND' })
+    assert.deepStrictEqual(client.achievement.getAll().map(({ name, unlocked, globalPercent }) => [name, unlocked, globalPercent]), [['ALL_SONGS', false, 2.5], ['FIRST_SONG', true, 87.5]])
+    const exported = path.join(os.tmpdir(), `steamworks-achievements-${process.pid}.json`)
+    client.achievement.exportAll(exported)
+    const snapshot = JSON.parse(fs.readFileSync(exported, 'utf8'))
+    fs.rmSync(exported)
+    assert.strictEqual(snapshot.appId, 480)
+    assert.strictEqual(snapshot.achievements.find(({ name }) => name === 'ALL_SONGS').displayName, 'Completionist')
+    client.mock.defineAchievementIcon('ALL_SONGS', 2, 1, Buffer.from([255, 0, 0, 255, 0, 0, 255, 255]))
+    assert.deepStrictEqual(await client.achievement.getIcon('ALL_SONGS'), { width: 2, height: 1, data: Buffer.from([255, 0, 0, 255, 0, 0, 255, 255]) })
+    const { data: png } = await client.achievement.getIcon('ALL_SONGS', true)
+    assert.strictEqual(png.subarray(1, 4).toString(), 'PNG')
+    await assert.rejects(client.achievement.getIcon('FIRST_SONG', false, 0), { code: 'TIMEOUT' })
+
+    client.mock.defineStat('songs_cleared', 0)
+    assert.strictEqual(client.achievement.indicateAchievementProgress('FIRST_SONG', 1, 2), false)
+    client.achievement.bindProgress({ stat: 'songs_cleared', achievement: 'ALL_SONGS', max: 40 })
+    client.stats.setInt('songs_cleared', 9)
+    assert.strictEqual(client.mock.getAchievementProgress('ALL_SONGS'), null)
+    client.stats.setInt('songs_cleared', 21)
+    assert.deepStrictEqual(client.mock.getAchievementProgress('ALL_SONGS'), [21, 40])
+    assert.strictEqual(client.achievement.unbindProgress('songs_cleared', 'ALL_SONGS'), true)
+    assert.throws(() => client.achievement.bindProgress({ stat: 'typo', achievement: 'ALL_SONGS', max: 40 }), { code: 'NOT_FOUND' })
+    client.stats.defineRule({ stat: 'songs_cleared', threshold: 40, achievement: 'ALL_SONGS', progressMilestones: [30] })
+    client.stats.setInt('songs_cleared', 35)
+    assert.deepStrictEqual(client.mock.getAchievementProgress('ALL_SONGS'), [35, 40])
+    const storesBeforeRule = client.mock.getStoreCount()
+    client.stats.setInt('songs_cleared', 40)
+    assert.strictEqual(client.achievement.isActivated('ALL_SONGS'), true)
+    assert.strictEqual(client.mock.getStoreCount(), storesBeforeRule)
+    await new Promise((resolve) => setTimeout(resolve, 1100))
+    assert.strictEqual(client.mock.getStoreCount(), storesBeforeRule + 1)
+    assert.strictEqual(client.stats.removeRule('songs_cleared', 'ALL_SONGS'), true)
+    assert.throws(() => client.stats.defineRule({ stat: 'songs_cleared', threshold: 0, achievement: 'ALL_SONGS' }), { code: 'INVALID_PARAMETER' })
+    assert.strictEqual(client.achievement.clear('ALL_SONGS'), true)
+
+    client.mock.defineGlobalStat('notes_hit', 12000, [300, 250, 400])
+    assert.strictEqual(client.stats.getGlobalInt('notes_hit'), null)
+    await client.stats.requestGlobalStats(2)
+    assert.strictEqual(client.stats.getGlobalInt('notes_hit'), 12000n)
+    assert.deepStrictEqual(client.stats.getGlobalIntHistory('notes_hit'), [300n, 250n])
+    assert.strictEqual(client.stats.getGlobalFloat('notes_hit'), null)
+
+    const friend = 76561197960265730n
+    client.mock.defineUserStat(friend, 'notes_hit', 870)
+    client.mock.defineUserAchievement(friend, 'FIRST_SONG', true, 1700000000)
+    assert.strictEqual(client.stats.getUserStatInt(friend, 'notes_hit'), null)
+    await client.stats.requestUserStats(friend)
+    assert.strictEqual(client.stats.getUserStatInt(friend, 'notes_hit'), 870)
+    assert.strictEqual(client.stats.getUserStatFloat(friend, 'notes_hit'), null)
+    assert.deepStrictEqual(client.achievement.getUserAchievement(friend, 'FIRST_SONG'), { name: 'FIRST_SONG', unlocked: true, unlockTime: 1700000000 })
+    assert.strictEqual(client.achievement.getUserAchievement(friend, 'ALL_SONGS').unlocked, false)
+    await assert.rejects(client.stats.requestUserStats(76561197960265731n))
+
+    const stores = client.mock.getStoreCount()
+    const batch = client.stats.transaction((tx) => {
+        tx.setInt('notes_hit', 900)
+        tx.clear('FIRST_SONG')
+    })
+    assert.deepStrictEqual(batch, {
+        committed: true,
+        rolledBack: false,
+        items: [
+            { name: 'notes_hit', change: client.stats.TransactionChange.SetInt, applied: true },
+            { name: 'FIRST_SONG', change: client.stats.TransactionChange.Clear, applied: true },
+        ],
+    })
+    assert.strictEqual(client.mock.getStoreCount(), stores + 1)
+    assert.throws(() => client.stats.transaction((tx) => tx.unlock('FIRST_SONGG')), { code: 'NOT_FOUND' })
+    let escaped
+    client.mock.setStoreFails(true)
+    const failed = client.stats.transaction((tx) => {
+        escaped = tx
+        tx.setInt('notes_hit', 1000)
+        tx.unlock('FIRST_SONG')
+    })
+    client.mock.setStoreFails(false)
+    assert.strictEqual(failed.committed, false)
+    assert.strictEqual(failed.rolledBack, true)
+    assert.strictEqual(client.stats.getInt('notes_hit'), 900)
+    assert.strictEqual(client.achievement.isActivated('FIRST_SONG'), false)
+    assert.throws(() => escaped.setInt('notes_hit', 1), { code: 'INVALID_STATE' })
+
+    client.mock.setStoreFails(true)
+    client.stats.setInt('notes_hit', 950)
+    assert.strictEqual(client.achievement.activate('ALL_SONGS'), false)
+    client.mock.setStoreFails(false)
+    const journaled = fs.readFileSync(journalFile, 'utf8')
+    // Parsed as a number, the Steam ID would lose precision
+    assert.match(journaled, /"steam_id":76561197960265729,/)
+    const { steam_id: _, ...writes } = JSON.parse(journaled)
+    assert.deepStrictEqual(writes, { version: 1, app_id: 480, ints: { notes_hit: 950 }, floats: {}, unlocks: ['ALL_SONGS'] })
+    // Lost locally in the meantime, the journal brings it back once Steam's servers connect
+    client.mock.defineStat('notes_hit', 100)
+    client.mock.defineAchievement('ALL_SONGS')
+    const connected = path.join(os.tmpdir(), `steamworks-connected-${process.pid}.jsonl`)
+    fs.writeFileSync(connected, JSON.stringify({ time: 0, kind: 'SteamServersConnected', payload: {} }))
+    assert.strictEqual(await client.callback.replay(connected), 1)
+    assert.strictEqual(client.stats.getInt('notes_hit'), 950)
+    assert.strictEqual(client.achievement.isActivated('ALL_SONGS'), true)
+    assert.strictEqual(fs.existsSync(journalFile), false)
+    // Nothing is replayed into the stats of another user who logged in meanwhile
+    client.mock.setStoreFails(true)
+    client.stats.setInt('notes_hit', 975)
+    client.mock.setStoreFails(false)
+    client.mock.setSteamId(76561197960265730n)
+    client.mock.defineStat('notes_hit', 100)
+    const storesBeforeReplay = client.mock.getStoreCount()
+    assert.strictEqual(await client.callback.replay(connected), 1)
+    assert.strictEqual(client.stats.getInt('notes_hit'), 100)
+    assert.strictEqual(client.mock.getStoreCount(), storesBeforeReplay)
+    assert.strictEqual(fs.existsSync(journalFile), true)
+    client.mock.setSteamId(76561197960265729n)
+    assert.strictEqual(await client.callback.replay(connected), 1)
+    assert.strictEqual(client.stats.getInt('notes_hit'), 975)
+    assert.strictEqual(fs.existsSync(journalFile), false)
+    // A rolled back transaction leaves the journal alone, the writes before it are journaled by the
+    // next store that fails
+    client.stats.setInt('notes_hit', 990)
+    client.mock.setStoreFails(true)
+    assert.strictEqual(client.stats.transaction((tx) => tx.setInt('notes_hit', 995)).rolledBack, true)
+    assert.strictEqual(fs.existsSync(journalFile), false)
+    assert.strictEqual(client.achievement.activate('FIRST_SONG'), false)
+    client.mock.setStoreFails(false)
+    let journaledWrites = JSON.parse(fs.readFileSync(journalFile, 'utf8'))
+    assert.deepStrictEqual(journaledWrites.ints, { notes_hit: 990 })
+    assert.deepStrictEqual(journaledWrites.unlocks, ['FIRST_SONG'])
+    // A committed one stores the writes before it too, and takes the unlock of an achievement it
+    // clears out of the journal
+    client.stats.setInt('notes_hit', 1000)
+    assert.strictEqual(client.stats.transaction((tx) => tx.clear('FIRST_SONG')).committed, true)
+    client.mock.setStoreFails(true)
+    assert.strictEqual(client.stats.store(), false)
+    client.mock.setStoreFails(false)
+    journaledWrites = JSON.parse(fs.readFileSync(journalFile, 'utf8'))
+    assert.deepStrictEqual(journaledWrites.ints, { notes_hit: 990 })
+    assert.deepStrictEqual(journaledWrites.unlocks, [])
+    assert.strictEqual(await client.callback.replay(connected), 1)
+    assert.strictEqual(client.stats.getInt('notes_hit'), 1000)
+    assert.strictEqual(client.achievement.isActivated('FIRST_SONG'), false)
+    assert.strictEqual(fs.existsSync(journalFile), false)
+    fs.rmSync(connected)
+
+    const board = await client.leaderboard.findOrCreate('high_scores', client.leaderboard.SortMethod.Descending, client.leaderboard.DisplayType.Numeric)
+    assert.strictEqual(board.getName(), 'high_scores')
+    assert.deepStrictEqual(await board.uploadScore(500, null, [3, 1]), { score: 500, scoreChanged: true, globalRankNew: 1, globalRankPrevious: 0 })
+    assert.strictEqual((await board.uploadScore(400)).scoreChanged, false)
+    client.mock.addLeaderboardScore('high_scores', friend, 450)
+    assert.deepStrictEqual(await board.uploadScore(300, client.leaderboard.UploadScoreMethod.ForceUpdate), { score: 300, scoreChanged: true, globalRankNew: 2, globalRankPrevious: 1 })
+    assert.deepStrictEqual((await board.downloadGlobal(1, 10)).map((entry) => entry.score), [450, 300])
+    await board.attachUgc(42n)
+    const [mine] = await board.downloadAroundUser(0, 0)
+    assert.strictEqual(mine.globalRank, 2)
+    assert.strictEqual(mine.ugcHandle, 42n)
+    assert.deepStrictEqual((await board.downloadUsers([friend])).map((entry) => entry.score), [450])
+    await assert.rejects(board.uploadScore(1, null, new Array(65).fill(0)), { code: 'INVALID_PARAMETER' })
+    assert.strictEqual(await client.leaderboard.find('typo'), null)
+
+    assert.strictEqual(client.cloud.writeFile('save.json', '{"level":3}'), true)
+    assert.strictEqual(client.cloud.readFile('save.json'), '{"level":3}')
+    assert.deepStrictEqual(client.cloud.listFiles(), [{ name: 'save.json', size: 11n }])
+
+    const lobby = await client.matchmaking.createLobby(client.matchmaking.LobbyType.Public, 4)
+    lobby.setData('mode', 'versus')
+    assert.deepStrictEqual(lobby.getFullData(), { mode: 'versus' })
+    assert.strictEqual((await client.matchmaking.getLobbies()).length, 1)
+
+    const { itemId } = await client.workshop.createItem()
+    await client.workshop.updateItem(itemId, { title: 'My chart' })
+    await client.workshop.subscribe(itemId)
+    assert.deepStrictEqual(client.workshop.getSubscribedItems(), [itemId])
+    assert.strictEqual((await client.workshop.getItem(itemId)).title, 'My chart')
+
+    const recording = path.join(os.tmpdir(), `steamworks-replay-${process.pid}.jsonl`)
+    fs.writeFileSync(recording, [
+        { time: 0, kind: 'GameOverlayActivated', payload: { active: true } },
+        { time: 5, kind: 'DlcInstalled', payload: { app_id: 1234 } },
+    ].map((line) => JSON.stringify(line)).join('\n'))
+    const overlays = []
+    const overlayHandle = client.callback.register(client.callback.SteamCallback.GameOverlayActivated, (value) => overlays.push(value))
+    const dlcs = client.callback.stream(client.callback.SteamCallback.DlcInstalled, { capacity: 8 })
+    const dlc = client.callback.once(client.callback.SteamCallback.DlcInstalled, (value) => value.app_id === 1234, 1000)
+    assert.strictEqual(await client.callback.replay(recording), 2)
+    assert.deepStrictEqual(await dlc, { app_id: 1234 })
+    for await (const installed of dlcs) {
+        assert.deepStrictEqual(installed, { app_id: 1234 })
+        break
+    }
+    assert.strictEqual((await dlcs.next()).done, true)
+    await assert.rejects(client.callback.once(client.callback.SteamCallback.DlcInstalled, null, 10), { code: 'TIMEOUT' })
+    const abortController = new AbortController()
+    const aborted = client.callback.once(client.callback.SteamCallback.DlcInstalled, null, null, abortController.signal)
+    abortController.abort()
+    await assert.rejects(aborted, { code: 'ABORT_ERR' })
+    await assert.rejects(client.matchmaking.getLobbies(AbortSignal.abort()), { code: 'ABORT_ERR' })
+    const tickets = client.mock.getTicketCount()
+    const ticket = await client.auth.getSessionTicketWithIp('127.0.0.1:27015')
+    assert.strictEqual(client.mock.getTicketCount(), tickets + 1)
+    ticket.cancel()
+    assert.strictEqual(client.mock.getTicketCount(), tickets)
+    client.mock.setHoldTickets(true)
+    const ticketController = new AbortController()
+    const heldTicket = client.auth.getAuthTicketForWebApi('test', null, ticketController.signal)
+    await new Promise((resolve) => setTimeout(resolve, 10))
+    assert.strictEqual(client.mock.getTicketCount(), tickets + 1)
+    ticketController.abort()
+    await assert.rejects(heldTicket, { code: 'ABORT_ERR' })
+    assert.strictEqual(client.mock.getTicketCount(), tickets)
+    client.mock.setHoldTickets(false)
+    const reusedController = new AbortController()
+    for (let i = 0; i < 3; i++) {
+        const reusedTicket = await client.auth.getSessionTicketWithIp('127.0.0.1:27015', null, reusedController.signal)
+        reusedTicket.cancel()
+    }
+    await new Promise((resolve) => setTimeout(resolve, 10))
+    assert.strictEqual(events.getEventListeners(reusedController.signal, 'abort').length, 0)
+    await new Promise((resolve) => setTimeout(resolve, 10))
+    assert.deepStrictEqual(overlays, [{ active: true }])
+    overlayHandle.disconnect()
+    fs.rmSync(recording)
+
+    const handle = client.mock.addController(client.input.InputType.PS5Controller)
+    client.mock.setDigitalAction(handle, 'select', true)
+    const [controller] = client.input.getControllers()
+    assert.strictEqual(controller.getType(), client.input.InputType.PS5Controller)
+    assert.strictEqual(controller.isDigitalActionPressed(client.input.getDigitalAction('select')), true)
+    const menu = client.input.getActionHandle('menu')
+    assert.deepStrictEqual(controller.getDigitalActionOrigins(menu, client.input.getDigitalAction('select')), [])
+    assert.deepStrictEqual(controller.getAnalogActionOrigins(menu, client.input.getAnalogAction('move')), [])
+    assert.strictEqual(client.input.getFilePathForAction(client.input.InputActionOrigins.None), '')
+
+    assert.strictEqual((await client.friends.requestUserInformation(friend, true)).name, '[unknown]')
+    client.mock.defineUserName(friend, 'Rival')
+    const rival = await client.friends.requestUserInformation(friend, true)
+    assert.deepStrictEqual([rival.name, rival.id], ['Rival', friend])
+
+    shutdown({ flushStats: true, leaveLobbies: true })
+    assert.strictEqual(client.isCallbackPumpRunning(), false)
+    assert.throws(() => client.stats.getInt('notes_hit'), { code: 'NOT_INITIALIZED' })
+
+    const schema = path.join(os.tmpdir(), `steamworks-schema-${process.pid}.vdf`)
+    fs.writeFileSync(schema, `"480" { "stats" {
+        "1" { "type" "1" "name" "notes_hit" "default" "10" }
+        "2" { "type" "4" "bits" { "0" { "name" "FIRST_SONG" "display" { "name" { "english" "First song" } "desc" { "english" "Play a song" } "hidden" "0" } } } }
+    } }`)
+    const schemaClient = init({ appId: 480, backend: BackendKind.Mock, schema, statJournal: journal })
+    fs.rmSync(schema)
+    assert.strictEqual(schemaClient.stats.getInt('notes_hit'), 10)
+    assert.throws(() => schemaClient.stats.setInt('notes_hti', 1), { code: 'NOT_FOUND', message: /did you mean notes_hit/ })
+    assert.throws(() => schemaClient.stats.getFloat('notes_hit'), { code: 'INVALID_PARAMETER' })
+    assert.strictEqual(schemaClient.achievement.getInfo('FIRST_SONG').displayName, 'First song')
+    assert.throws(() => schemaClient.achievement.activate('FRIST_SONG'), { code: 'NOT_FOUND' })
+
+    // Off the pump a full Block stream drops its oldest callback, waiting would hold up runCallbacks
+    schemaClient.startCallbackPump()
+    const blocking = schemaClient.callback.stream(schemaClient.callback.SteamCallback.DlcInstalled, { capacity: 1, policy: schemaClient.callback.StreamPolicy.Block, blockTimeoutMs: 5000 })
+    schemaClient.stopCallbackPump()
+    for (const app_id of [1, 2, 3]) {
+        schemaClient.mock.queueCallback(schemaClient.callback.SteamCallback.DlcInstalled, { app_id })
+    }
+    const queuedAt = Date.now()
+    await new Promise((resolve) => setTimeout(resolve, 50))
+    assert.ok(Date.now() - queuedAt < 1000)
+    assert.strictEqual(blocking.dropped, 2)
+    assert.deepStrictEqual(await blocking.next(), { done: false, value: { app_id: 3 } })
+    blocking.close()
+
+    // A rule's unlock still waiting for its store goes out on shutdown, journaled as the store fails
+    schemaClient.stats.defineRule({ stat: 'notes_hit', threshold: 20, achievement: 'FIRST_SONG' })
+    schemaClient.mock.setStoreFails(true)
+    schemaClient.stats.setInt('notes_hit', 20)
+    shutdown({ flushStats: false })
+    assert.deepStrictEqual(JSON.parse(fs.readFileSync(journalFile, 'utf8')).unlocks, ['FIRST_SONG'])
+    fs.rmSync(journalFile)
+    // The handler keeps the process alive until it's released
+    setLogHandler(null)
+    console.log('Mock backend OK')
+})()
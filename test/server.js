@@ -0,0 +1,25 @@
+const { initServer, shutdown, ServerMode } = require('../index.js')
+
+const client = initServer({
+    gamePort: 27015,
+    queryPort: 27016,
+    serverMode: ServerMode.Authentication,
+    version: '1.0.0.0',
+    autoRunCallbacks: true,
+})
+
+client.server.setProduct('480')
+client.server.setGameDescription('steamworks.js test server')
+client.server.setServerName('steamworks.js')
+client.server.setMapName('test')
+client.server.setMaxPlayers(8)
+client.server.logOnAnonymous()
+client.server.setAdvertiseServerActive(true)
+
+setTimeout(() => {
+    console.log('isLoggedOn: ', client.server.isLoggedOn())
+    console.log('getSteamId: ', client.server.getSteamId())
+    console.log('getPlayers: ', client.server.getPlayers())
+
+    shutdown()
+}, 5000)